@@ -109,6 +109,21 @@ pub struct CompileCommand {
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f32,
 
+    /// The maximum resolution (in pixels per inch) of raster images in PDF
+    /// export. Images displayed at a higher resolution are downsampled
+    #[arg(long = "max-image-dpi", value_name = "DPI", value_parser = parse_dpi)]
+    pub max_image_dpi: Option<f64>,
+
+    /// The quality (from 1 to 100) with which JPEG images are re-encoded in
+    /// PDF export. Other raster images, like PNGs, are always compressed
+    /// losslessly
+    #[arg(
+        long = "jpeg-quality",
+        value_name = "QUALITY",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+    )]
+    pub jpeg_quality: Option<u8>,
+
     /// Produces performance timings of the compilation process (experimental)
     ///
     /// The resulting JSON file can be loaded into a tracing tool such as
@@ -244,6 +259,16 @@ fn parse_source_date_epoch(raw: &str) -> Result<DateTime<Utc>, String> {
         .ok_or_else(|| "timestamp out of range".to_string())
 }
 
+/// Parses a resolution, which must be a positive, finite number.
+fn parse_dpi(raw: &str) -> Result<f64, String> {
+    let dpi: f64 = raw.parse().map_err(|err| format!("invalid number ({err})"))?;
+    if dpi.is_finite() && dpi > 0.0 {
+        Ok(dpi)
+    } else {
+        Err("resolution must be a positive, finite number".into())
+    }
+}
+
 /// An input that is either stdin or a real path.
 #[derive(Debug, Clone)]
 pub enum Input {
@@ -187,7 +187,17 @@ fn export_pdf(document: &Document, command: &CompileCommand) -> StrResult<()> {
         command.common.creation_timestamp.unwrap_or_else(chrono::Utc::now),
     );
     let exported_page_ranges = command.exported_page_ranges();
-    let buffer = typst_pdf::pdf(document, Smart::Auto, timestamp, exported_page_ranges);
+    let image_options = typst_pdf::ImageOptions {
+        max_dpi: command.max_image_dpi,
+        jpeg_quality: command.jpeg_quality,
+    };
+    let buffer = typst_pdf::pdf(
+        document,
+        Smart::Auto,
+        timestamp,
+        exported_page_ranges,
+        image_options,
+    );
     command
         .output()
         .write(&buffer)
@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 use fontdb::{Database, Source};
@@ -72,6 +72,7 @@ impl FontSearcher {
         // Font paths have highest priority.
         for path in font_paths {
             db.load_fonts_dir(path);
+            self.search_woff(path);
         }
 
         if !ignore_system_fonts {
@@ -106,6 +107,44 @@ impl FontSearcher {
         self.add_embedded();
     }
 
+    /// Add the WOFF and WOFF2 fonts in a directory and its subdirectories.
+    ///
+    /// The font database only picks up raw font files, so we search for these
+    /// ourselves.
+    fn search_woff(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_type().is_ok_and(|ty| ty.is_dir()) {
+                self.search_woff(&path);
+                continue;
+            }
+
+            let is_woff =
+                path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+                    ext.eq_ignore_ascii_case("woff") || ext.eq_ignore_ascii_case("woff2")
+                });
+
+            if !is_woff {
+                continue;
+            }
+
+            let Ok(data) = fs::read(&path) else { continue };
+            let Some(count) = FontInfo::count(&data) else { continue };
+
+            for index in 0..count {
+                if let Some(info) = FontInfo::new(&data, index) {
+                    self.book.push(info);
+                    self.fonts.push(FontSlot {
+                        path: path.clone(),
+                        index,
+                        font: OnceLock::new(),
+                    });
+                }
+            }
+        }
+    }
+
     /// Add fonts that are embedded in the binary.
     #[cfg(feature = "embed-fonts")]
     fn add_embedded(&mut self) {
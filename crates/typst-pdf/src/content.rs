@@ -1,6 +1,7 @@
 //! Generic writer for PDF content.
 //!
-//! It is used to write page contents, color glyph instructions, and patterns.
+//! It is used to write page contents, color glyph instructions, patterns, and
+//! transparency groups.
 //!
 //! See also [`pdf_writer::Content`].
 
@@ -10,18 +11,19 @@ use pdf_writer::{
     Content, Finish, Name, Rect, Str,
 };
 use typst::layout::{
-    Abs, Em, Frame, FrameItem, GroupItem, Point, Ratio, Size, Transform,
+    Abs, Angle, Em, Frame, FrameItem, GroupItem, Point, Ratio, Size, Transform,
 };
 use typst::model::Destination;
 use typst::text::{color::is_color_glyph, Font, TextItem, TextItemView};
 use typst::utils::{Deferred, Numeric, SliceExt};
 use typst::visualize::{
-    FixedStroke, Geometry, Image, LineCap, LineJoin, Paint, Path, PathItem, Shape,
+    FillRule, FixedShadow, FixedStroke, Geometry, Image, LineCap, LineJoin, MaskMode,
+    Paint, Path, PathItem, Shape,
 };
 
 use crate::color_font::ColorFontMap;
-use crate::extg::ExtGState;
-use crate::image::deferred_image;
+use crate::extg::{ExtGState, GroupRemapper, PdfGroup, PdfMask};
+use crate::image::image_color_space;
 use crate::{color::PaintEncode, resources::Resources};
 use crate::{deflate_deferred, AbsExt, EmExt};
 
@@ -193,7 +195,7 @@ impl Builder<'_, ()> {
     fn set_external_graphics_state(&mut self, graphics_state: &ExtGState) {
         let current_state = self.state.external_graphics_state.as_ref();
         if current_state != Some(graphics_state) {
-            let index = self.resources.ext_gs.insert(*graphics_state);
+            let index = self.resources.ext_gs.insert(graphics_state.clone());
             let name = eco_format!("Gs{index}");
             self.content.set_parameters(Name(name.as_bytes()));
 
@@ -224,7 +226,11 @@ impl Builder<'_, ()> {
                 color.alpha().map_or(255, |v| (v * 255.0).round() as u8)
             })
             .unwrap_or(255);
-        self.set_external_graphics_state(&ExtGState { stroke_opacity, fill_opacity });
+        self.set_external_graphics_state(&ExtGState {
+            stroke_opacity,
+            fill_opacity,
+            ..Default::default()
+        });
     }
 
     pub fn transform(&mut self, transform: Transform) {
@@ -373,16 +379,207 @@ fn write_group(ctx: &mut Builder, pos: Point, group: &GroupItem) {
     }
 
     ctx.transform(translation.pre_concat(group.transform));
+
+    // The shadow is painted before clipping, so that the clip doesn't cut it
+    // off. It clips its silhouette itself.
+    if let Some(shadow) = &group.shadow {
+        write_shadow(ctx, group, shadow);
+    }
+
     if let Some(clip_path) = &group.clip_path {
-        write_path(ctx, 0.0, 0.0, clip_path);
-        ctx.content.clip_nonzero();
-        ctx.content.end_path();
+        write_clip(ctx, clip_path, group.clip_rule);
+    }
+
+    if group.is_composited() {
+        write_composited(ctx, group);
+    } else {
+        write_frame(ctx, &group.frame);
     }
 
-    write_frame(ctx, &group.frame);
     ctx.restore_state();
 }
 
+/// Encode a group that is composited as a whole into a transparency group and
+/// paint it with the group's opacity, blend mode, and mask.
+fn write_composited(ctx: &mut Builder, group: &GroupItem) {
+    let groups = ctx
+        .resources
+        .groups
+        .get_or_insert_with(|| Box::new(GroupRemapper::new()));
+
+    let content = build(&mut groups.resources, &group.frame, None);
+    let index = groups.remapper.insert(PdfGroup {
+        size: group.frame.size(),
+        content: content.content.wait().clone(),
+    });
+
+    let soft_mask = group.mask.as_ref().map(|mask| {
+        let content = build(&mut groups.resources, &mask.frame, None);
+        PdfMask {
+            mode: mask.mode,
+            size: mask.frame.size(),
+            content: content.content.wait().clone(),
+        }
+    });
+
+    let opacity = (group.opacity.get() * 255.0).round() as u8;
+    ctx.set_external_graphics_state(&ExtGState {
+        stroke_opacity: opacity,
+        fill_opacity: opacity,
+        blend_mode: group.blend_mode,
+        soft_mask,
+    });
+
+    let name = eco_format!("Xg{index}");
+    ctx.content.x_object(Name(name.as_bytes()));
+
+    // The links in the group are not part of this content stream, but they
+    // must still be written into the page's annotations.
+    write_links(ctx, &group.frame);
+}
+
+/// Intersect the clipping area with the given path.
+fn write_clip(ctx: &mut Builder, clip_path: &Path, rule: FillRule) {
+    write_path(ctx, 0.0, 0.0, clip_path);
+    match rule {
+        FillRule::NonZero => ctx.content.clip_nonzero(),
+        FillRule::EvenOdd => ctx.content.clip_even_odd(),
+    };
+    ctx.content.end_path();
+}
+
+/// Encode the shadow that a group casts.
+///
+/// PDF has no blur filters, so the shadow is approximated: The group's
+/// silhouette is encoded once as a transparency group, which is then painted
+/// several times at offsets spread around a few rings. Where all copies
+/// overlap, the shadow has its full opacity, and towards its edges, it fades
+/// out. Shadows with a negative spread are not shrunk.
+fn write_shadow(ctx: &mut Builder, group: &GroupItem, shadow: &FixedShadow) {
+    let groups = ctx
+        .resources
+        .groups
+        .get_or_insert_with(|| Box::new(GroupRemapper::new()));
+    let silhouette = encode_silhouette(&mut groups.resources, group, shadow);
+    let index = groups.remapper.insert(silhouette);
+
+    let spread = shadow.spread.max(Abs::zero());
+    let mut radii = vec![];
+    if spread > Abs::zero() {
+        radii.push(spread);
+    }
+    if shadow.blur > Abs::zero() {
+        radii.extend([spread + shadow.blur / 3.0, spread + shadow.blur * 2.0 / 3.0]);
+    }
+
+    let mut offsets = vec![Point::zero()];
+    for radius in radii {
+        offsets.extend((0..8).map(|i| {
+            let angle = Angle::deg(45.0 * i as f64);
+            Point::new(radius * angle.cos(), radius * angle.sin())
+        }));
+    }
+
+    // Choose the opacity of each copy such that they add up to the shadow's
+    // opacity where they all overlap.
+    let alpha = shadow.color.alpha().unwrap_or(1.0);
+    let layer_alpha = 1.0 - (1.0 - alpha).powf(1.0 / offsets.len() as f32);
+    let opacity = (layer_alpha * 255.0).round() as u8;
+    let state = ExtGState {
+        stroke_opacity: opacity,
+        fill_opacity: opacity,
+        ..ExtGState::default()
+    };
+
+    let name = eco_format!("Xg{index}");
+    for offset in offsets {
+        ctx.save_state();
+        ctx.transform(Transform::translate(
+            shadow.offset.x + offset.x,
+            shadow.offset.y + offset.y,
+        ));
+        ctx.set_external_graphics_state(&state);
+        ctx.content.x_object(Name(name.as_bytes()));
+        ctx.restore_state();
+    }
+}
+
+/// Encode the silhouette of a group's content in the opaque color of its
+/// shadow as a transparency group.
+///
+/// The color is painted through a soft mask made of the content and the
+/// group's clip path.
+fn encode_silhouette(
+    resources: &mut Resources<()>,
+    group: &GroupItem,
+    shadow: &FixedShadow,
+) -> PdfGroup {
+    // Like in `write_composited`, the mask's content uses the resources of
+    // the groups that are nested in this one.
+    let size = group.frame.size();
+    let groups = resources.groups.get_or_insert_with(|| Box::new(GroupRemapper::new()));
+    let content = build(&mut groups.resources, &group.frame, None);
+    let soft_mask = PdfMask {
+        mode: MaskMode::Alpha,
+        size,
+        content: content.content.wait().clone(),
+    };
+
+    let mut ctx = Builder::new(resources, size);
+
+    // Make the coordinate system start at the top-left, like in `build`.
+    ctx.transform(
+        Transform::scale(Ratio::one(), -Ratio::one())
+            .post_concat(Transform::translate(Abs::zero(), size.y)),
+    );
+
+    if let Some(clip_path) = &group.clip_path {
+        write_clip(&mut ctx, clip_path, group.clip_rule);
+    }
+
+    ctx.set_external_graphics_state(&ExtGState {
+        soft_mask: Some(soft_mask),
+        ..ExtGState::default()
+    });
+
+    // The mask hides everything outside of the content, so the painted area
+    // only needs to cover the content. That one may extend a bit beyond its
+    // frame.
+    let margin = size.x.max(size.y) / 2.0;
+    let paint = Paint::Solid(shadow.color.with_alpha(1.0));
+    ctx.set_fill(&paint, false, ctx.state.transforms(size, Point::zero()));
+    ctx.content.rect(
+        -margin.to_f32(),
+        -margin.to_f32(),
+        (size.x + margin * 2.0).to_f32(),
+        (size.y + margin * 2.0).to_f32(),
+    );
+    ctx.content.fill_nonzero();
+
+    PdfGroup {
+        size,
+        content: deflate_deferred(ctx.content.finish()).wait().clone(),
+    }
+}
+
+/// Save the links in a frame that is encoded in a separate content stream.
+fn write_links(ctx: &mut Builder, frame: &Frame) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let prev = ctx.state.transform;
+                ctx.state.transform = prev
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                write_links(ctx, &group.frame);
+                ctx.state.transform = prev;
+            }
+            FrameItem::Link(dest, size) => write_link(ctx, *pos, dest, *size),
+            _ => {}
+        }
+    }
+}
+
 /// Encode a text run into the content stream.
 fn write_text(ctx: &mut Builder, pos: Point, text: &TextItem) {
     let ttf = text.font.ttf();
@@ -439,7 +636,14 @@ fn write_normal_text(ctx: &mut Builder, pos: Point, text: TextItemView) {
 
     *ctx.resources.languages.entry(text.item.lang).or_insert(0) += text.glyph_range.len();
 
-    let glyph_set = ctx.resources.glyph_sets.entry(text.item.font.clone()).or_default();
+    // Fonts are always embedded at their default instance, so that all
+    // instances of a variable font share one font program. Thus, the glyphs of
+    // other instances are drawn as outlines instead and the text is only
+    // written invisibly to keep it searchable and copyable.
+    let font = text.item.font.default_instance();
+    let instanced = font != text.item.font;
+
+    let glyph_set = ctx.resources.glyph_sets.entry(font.clone()).or_default();
     for g in text.glyphs() {
         let t = text.text();
         let segment = &t[g.range()];
@@ -459,12 +663,17 @@ fn write_normal_text(ctx: &mut Builder, pos: Point, text: TextItemView) {
 
     if let Some(stroke) = stroke {
         ctx.set_stroke(stroke, true, fill_transform);
+    }
+
+    if instanced {
+        ctx.set_text_rendering_mode(TextRenderingMode::Invisible);
+    } else if stroke.is_some() {
         ctx.set_text_rendering_mode(TextRenderingMode::FillStroke);
     } else {
         ctx.set_text_rendering_mode(TextRenderingMode::Fill);
     }
 
-    ctx.set_font(&text.item.font, text.item.size);
+    ctx.set_font(&font, text.item.size);
     ctx.set_opacities(text.item.stroke.as_ref(), Some(&text.item.fill));
     ctx.content.begin_text();
 
@@ -490,11 +699,11 @@ fn write_normal_text(ctx: &mut Builder, pos: Point, text: TextItemView) {
             adjustment = Em::zero();
         }
 
-        let cid = crate::font::glyph_cid(&text.item.font, glyph.id);
+        let cid = crate::font::glyph_cid(&font, glyph.id);
         encoded.push((cid >> 8) as u8);
         encoded.push((cid & 0xff) as u8);
 
-        if let Some(advance) = text.item.font.advance(glyph.id) {
+        if let Some(advance) = font.advance(glyph.id) {
             adjustment += glyph.x_advance - advance;
         }
 
@@ -508,6 +717,101 @@ fn write_normal_text(ctx: &mut Builder, pos: Point, text: TextItemView) {
     items.finish();
     positioned.finish();
     ctx.content.end_text();
+
+    if instanced {
+        write_outlines(ctx, pos, &text, stroke.is_some());
+    }
+}
+
+/// Encodes the glyph outlines of a text run as paths into the content stream.
+///
+/// The fill and stroke must already be set up.
+fn write_outlines(ctx: &mut Builder, pos: Point, text: &TextItemView, stroke: bool) {
+    let font = &text.item.font;
+    let scale = text.item.size.to_f32() / font.units_per_em() as f32;
+
+    let mut x = pos.x.to_f32();
+    let mut any = false;
+
+    for glyph in text.glyphs() {
+        // Glyphs with different vertical offsets end up in separate text
+        // items during shaping, so the offset is already part of `pos.y`.
+        let mut builder = OutlineBuilder {
+            content: &mut ctx.content,
+            x: x + glyph.x_offset.at(text.item.size).to_f32(),
+            y: pos.y.to_f32(),
+            scale,
+            last: (0.0, 0.0),
+        };
+
+        let id = ttf_parser::GlyphId(glyph.id);
+        any |= font.ttf().outline_glyph(id, &mut builder).is_some();
+        x += glyph.x_advance.at(text.item.size).to_f32();
+    }
+
+    if any {
+        if stroke {
+            ctx.content.fill_nonzero_and_stroke();
+        } else {
+            ctx.content.fill_nonzero();
+        }
+    }
+}
+
+/// Writes glyph outlines in font units into a content stream.
+struct OutlineBuilder<'a> {
+    content: &'a mut Content,
+    x: f32,
+    y: f32,
+    scale: f32,
+    last: (f32, f32),
+}
+
+impl OutlineBuilder<'_> {
+    /// Transform a point from font units into content stream coordinates and
+    /// remember it as the current point.
+    fn point(&mut self, x: f32, y: f32) -> (f32, f32) {
+        self.last = (self.x + x * self.scale, self.y - y * self.scale);
+        self.last
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineBuilder<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.content.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.content.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        // PDF only supports cubic curves, so we elevate the quadratic one.
+        let (x0, y0) = self.last;
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.content.cubic_to(
+            x0 + 2.0 / 3.0 * (x1 - x0),
+            y0 + 2.0 / 3.0 * (y1 - y0),
+            x + 2.0 / 3.0 * (x1 - x),
+            y + 2.0 / 3.0 * (y1 - y),
+            x,
+            y,
+        );
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.content.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.content.close_path();
+    }
 }
 
 /// Encodes a text run made only of color glyphs into the content stream
@@ -600,11 +904,13 @@ fn write_shape(ctx: &mut Builder, pos: Point, shape: &Shape) {
         }
     }
 
-    match (&shape.fill, stroke) {
-        (None, None) => unreachable!(),
-        (Some(_), None) => ctx.content.fill_nonzero(),
-        (None, Some(_)) => ctx.content.stroke(),
-        (Some(_), Some(_)) => ctx.content.fill_nonzero_and_stroke(),
+    match (&shape.fill, shape.fill_rule, stroke) {
+        (None, _, None) => unreachable!(),
+        (Some(_), FillRule::NonZero, None) => ctx.content.fill_nonzero(),
+        (Some(_), FillRule::EvenOdd, None) => ctx.content.fill_even_odd(),
+        (None, _, Some(_)) => ctx.content.stroke(),
+        (Some(_), FillRule::NonZero, Some(_)) => ctx.content.fill_nonzero_and_stroke(),
+        (Some(_), FillRule::EvenOdd, Some(_)) => ctx.content.fill_even_odd_and_stroke(),
     };
 }
 
@@ -634,13 +940,21 @@ fn write_path(ctx: &mut Builder, x: f32, y: f32, path: &Path) {
 /// Encode a vector or raster image into the content stream.
 fn write_image(ctx: &mut Builder, x: f32, y: f32, image: &Image, size: Size) {
     let index = ctx.resources.images.insert(image.clone());
-    ctx.resources.deferred_images.entry(index).or_insert_with(|| {
-        let (image, color_space) = deferred_image(image.clone());
-        if let Some(color_space) = color_space {
+
+    // Keep track of the largest size at which the image is displayed, so that
+    // it can be downsampled accordingly.
+    let ts = ctx.state.transform;
+    let displayed = Size::new(
+        size.x * ts.sx.get().hypot(ts.ky.get()),
+        size.y * ts.kx.get().hypot(ts.sy.get()),
+    );
+    let largest = ctx.resources.image_sizes.entry(index).or_insert_with(|| {
+        if let Some(color_space) = image_color_space(image) {
             ctx.resources.colors.mark_as_used(color_space);
         }
-        image
+        Size::zero()
     });
+    *largest = largest.max(displayed);
 
     let name = eco_format!("Im{index}");
     let w = size.x.to_f32();
@@ -1,31 +1,53 @@
 use std::collections::HashMap;
 
-use pdf_writer::Ref;
+use pdf_writer::types::{self, MaskType};
+use pdf_writer::{Chunk, Filter, Finish, Name, Rect, Ref};
+use typst::layout::{Abs, Ratio, Size, Transform};
+use typst::visualize::{BlendMode, MaskMode};
 
-use crate::{PdfChunk, WithGlobalRefs};
+use crate::resources::{Remapper, Resources, ResourcesRefs};
+use crate::{transform_to_array, AbsExt, PdfChunk, WithGlobalRefs};
 
 /// A PDF external graphics state.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct ExtGState {
     // In the range 0-255, needs to be divided before being written into the graphics state!
     pub stroke_opacity: u8,
     // In the range 0-255, needs to be divided before being written into the graphics state!
     pub fill_opacity: u8,
+    /// How content is blended with what is below it.
+    pub blend_mode: BlendMode,
+    /// A mask that determines which parts of the content are visible.
+    pub soft_mask: Option<PdfMask>,
 }
 
 impl Default for ExtGState {
     fn default() -> Self {
-        Self { stroke_opacity: 255, fill_opacity: 255 }
+        Self {
+            stroke_opacity: 255,
+            fill_opacity: 255,
+            blend_mode: BlendMode::Normal,
+            soft_mask: None,
+        }
     }
 }
 
 impl ExtGState {
+    /// Whether the state makes content transparent or composites it in a
+    /// special way, which requires a transparency group on the page.
     pub fn uses_opacities(&self) -> bool {
-        self.stroke_opacity != 255 || self.fill_opacity != 255
+        self.stroke_opacity != 255
+            || self.fill_opacity != 255
+            || self.blend_mode != BlendMode::Normal
+            || self.soft_mask.is_some()
     }
 }
 
 /// Embed all used external graphics states into the PDF.
+///
+/// Soft masks are written alongside the graphics state that uses them. Their
+/// content streams use the resources of the transparency groups in the same
+/// resource dictionary.
 pub fn write_graphic_states(
     context: &WithGlobalRefs,
 ) -> (PdfChunk, HashMap<ExtGState, Ref>) {
@@ -38,13 +60,179 @@ pub fn write_graphic_states(
             }
 
             let id = chunk.alloc();
-            out.insert(*external_gs, id);
-            chunk
-                .ext_graphics(id)
+            let mask_id = external_gs.soft_mask.as_ref().map(|_| chunk.alloc());
+            out.insert(external_gs.clone(), id);
+
+            let mut ext_gs = chunk.ext_graphics(id);
+            ext_gs
                 .non_stroking_alpha(external_gs.fill_opacity as f32 / 255.0)
                 .stroking_alpha(external_gs.stroke_opacity as f32 / 255.0);
+            if external_gs.blend_mode != BlendMode::Normal {
+                ext_gs.blend_mode(to_pdf_blend_mode(external_gs.blend_mode));
+            }
+            if let Some(mask_id) = mask_id {
+                let mode = external_gs.soft_mask.as_ref().unwrap().mode;
+                ext_gs
+                    .soft_mask()
+                    .subtype(match mode {
+                        MaskMode::Alpha => MaskType::Alpha,
+                        MaskMode::Luminance => MaskType::Luminosity,
+                    })
+                    .group(mask_id);
+            }
+            ext_gs.finish();
+
+            if let (Some(mask), Some(mask_id)) = (&external_gs.soft_mask, mask_id) {
+                let groups = resources
+                    .groups
+                    .as_ref()
+                    .expect("soft masks are encoded with the groups' resources");
+                write_transparency_group(
+                    &mut chunk,
+                    mask_id,
+                    mask.size,
+                    &mask.content,
+                    groups.resources.reference,
+                );
+            }
+        }
+    });
+
+    (chunk, out)
+}
+
+/// Writes the transparency groups of composited content to the PDF.
+pub fn write_groups(context: &WithGlobalRefs) -> (PdfChunk, HashMap<PdfGroup, Ref>) {
+    let mut chunk = PdfChunk::new();
+    let mut out = HashMap::new();
+    context.resources.traverse(&mut |resources| {
+        let Some(groups) = &resources.groups else {
+            return;
+        };
+
+        for group in groups.remapper.items() {
+            if out.contains_key(group) {
+                continue;
+            }
+
+            let id = chunk.alloc();
+            out.insert(group.clone(), id);
+            write_transparency_group(
+                &mut chunk,
+                id,
+                group.size,
+                &group.content,
+                groups.resources.reference,
+            );
         }
     });
 
     (chunk, out)
 }
+
+/// Write a content stream built for a frame of the given size as an isolated
+/// transparency group.
+fn write_transparency_group(
+    chunk: &mut Chunk,
+    id: Ref,
+    size: Size,
+    content: &[u8],
+    resources: Ref,
+) {
+    // Content can extend beyond its frame, so we don't tightly bound the
+    // group to it.
+    let margin = size.x.max(size.y).to_f32() + 1000.0;
+    let mut form = chunk.form_xobject(id, content);
+    form.bbox(Rect::new(
+        -margin,
+        -margin,
+        size.x.to_f32() + margin,
+        size.y.to_f32() + margin,
+    ))
+    // Undo the flip of the content stream's y-axis, as the group is painted
+    // in the coordinate system of the frame.
+    .matrix(transform_to_array(
+        Transform::scale(Ratio::one(), -Ratio::one())
+            .post_concat(Transform::translate(Abs::zero(), size.y)),
+    ))
+    .filter(Filter::FlateDecode);
+
+    // The actual resource dict will be written in a later step
+    form.pair(Name(b"Resources"), resources);
+
+    form.group()
+        .transparency()
+        .isolated(true)
+        .knockout(false)
+        .color_space()
+        .srgb();
+}
+
+/// Content that is composited as a whole, encoded as a content stream.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PdfGroup {
+    /// The size of the group's frame.
+    pub size: Size,
+    /// The compressed content stream.
+    pub content: Vec<u8>,
+}
+
+/// A soft mask, encoded as a content stream.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PdfMask {
+    /// How the mask determines the visibility of content.
+    pub mode: MaskMode,
+    /// The size of the mask's frame.
+    pub size: Size,
+    /// The compressed content stream.
+    pub content: Vec<u8>,
+}
+
+/// De-duplicate transparency groups and the resources they require to be
+/// drawn.
+///
+/// The content of soft masks uses the same resources.
+pub struct GroupRemapper<R> {
+    /// Transparency group de-duplicator.
+    pub remapper: Remapper<PdfGroup>,
+    /// PDF resources that are used by these groups and masks.
+    pub resources: Resources<R>,
+}
+
+impl GroupRemapper<()> {
+    pub fn new() -> Self {
+        Self {
+            remapper: Remapper::new("Xg"),
+            resources: Resources::default(),
+        }
+    }
+
+    /// Allocate a reference to the resource dictionary of these groups.
+    pub fn with_refs(self, refs: &ResourcesRefs) -> GroupRemapper<Ref> {
+        GroupRemapper {
+            remapper: self.remapper,
+            resources: self.resources.with_refs(refs),
+        }
+    }
+}
+
+fn to_pdf_blend_mode(blend_mode: BlendMode) -> types::BlendMode {
+    match blend_mode {
+        BlendMode::Normal => types::BlendMode::Normal,
+        BlendMode::Multiply => types::BlendMode::Multiply,
+        BlendMode::Screen => types::BlendMode::Screen,
+        BlendMode::Overlay => types::BlendMode::Overlay,
+        BlendMode::Darken => types::BlendMode::Darken,
+        BlendMode::Lighten => types::BlendMode::Lighten,
+        BlendMode::ColorDodge => types::BlendMode::ColorDodge,
+        BlendMode::ColorBurn => types::BlendMode::ColorBurn,
+        BlendMode::HardLight => types::BlendMode::HardLight,
+        BlendMode::SoftLight => types::BlendMode::SoftLight,
+        BlendMode::Difference => types::BlendMode::Difference,
+        BlendMode::Exclusion => types::BlendMode::Exclusion,
+        BlendMode::Hue => types::BlendMode::Hue,
+        BlendMode::Saturation => types::BlendMode::Saturation,
+        BlendMode::Color => types::BlendMode::Color,
+        BlendMode::Luminosity => types::BlendMode::Luminosity,
+    }
+}
@@ -9,10 +9,10 @@ use pdf_writer::{
     Filter, Finish, Name, Ref,
 };
 
-use typst::layout::{Abs, Angle, Point, Quadrant, Ratio, Transform};
+use typst::layout::{Abs, Angle, Axes, Point, Quadrant, Ratio, Transform};
 use typst::utils::Numeric;
 use typst::visualize::{
-    Color, ColorSpace, Gradient, RatioOrAngle, RelativeTo, WeightedColor,
+    Color, ColorSpace, Gradient, MeshGradient, RatioOrAngle, RelativeTo, WeightedColor,
 };
 
 use crate::color::{self, ColorSpaceExt, PaintEncode, QuantizedColor};
@@ -126,8 +126,14 @@ pub fn write_gradients(
 
                     shading_pattern
                 }
-                Gradient::Conic(_) => {
-                    let vertices = compute_vertex_stream(gradient, *aspect_ratio);
+                Gradient::Conic(_) | Gradient::Mesh(_) => {
+                    let (vertices, bounds) = match gradient {
+                        Gradient::Mesh(mesh) => compute_mesh_stream(mesh),
+                        _ => (
+                            compute_vertex_stream(gradient, *aspect_ratio),
+                            [0.0, 1.0, 0.0, 1.0],
+                        ),
+                    };
 
                     let stream_shading_id = chunk.alloc();
                     let mut stream_shading =
@@ -146,8 +152,8 @@ pub fn write_gradients(
                         .bits_per_flag(8)
                         .shading_type(StreamShadingType::CoonsPatch)
                         .decode([
-                            0.0, 1.0, 0.0, 1.0, range[0], range[1], range[2], range[3],
-                            range[4], range[5],
+                            bounds[0], bounds[1], bounds[2], bounds[3], range[0],
+                            range[1], range[2], range[3], range[4], range[5],
                         ])
                         .anti_alias(gradient.anti_alias())
                         .filter(Filter::FlateDecode);
@@ -495,6 +501,125 @@ fn compute_vertex_stream(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<Vec<u8
     Arc::new(deflate(&vertices))
 }
 
+/// Encodes the patches of a mesh gradient as Coons patches.
+///
+/// Returns the compressed vertex stream and the bounds `[x0, x1, y0, y1]`
+/// within which its coordinates are quantized.
+#[comemo::memoize]
+fn compute_mesh_stream(mesh: &MeshGradient) -> (Arc<Vec<u8>>, [f32; 4]) {
+    // PDF viewers interpolate the colors of a patch in the encoding space.
+    // This matches the gradient's interpolation except for hue-based
+    // spaces, whose patches are thus subdivided.
+    let encode_space = mesh
+        .space
+        .hue_index()
+        .map(|_| ColorSpace::Oklab)
+        .unwrap_or(mesh.space);
+    let steps = if mesh.space.hue_index().is_some() { 8 } else { 1 };
+
+    let mut patches: Vec<([Axes<f64>; 12], [[u16; 3]; 4])> = vec![];
+    let size = mesh.patches();
+    for row in 0..size.y {
+        for column in 0..size.x {
+            if steps == 1 {
+                patches.push((
+                    mesh.controls(row, column),
+                    mesh.colors(row, column).map(|c| encode_space.convert(c)),
+                ));
+                continue;
+            }
+
+            for i in 0..steps {
+                for j in 0..steps {
+                    let (u0, u1) =
+                        (j as f64 / steps as f64, (j + 1) as f64 / steps as f64);
+                    let (v0, v1) =
+                        (i as f64 / steps as f64, (i + 1) as f64 / steps as f64);
+
+                    // Fit each edge of the sub-patch with a cubic curve through
+                    // the patch's points at a third and two thirds of it.
+                    let edge = |a: (f64, f64), b: (f64, f64)| {
+                        let point = |t: f64| {
+                            let u = a.0 + (b.0 - a.0) * t;
+                            let v = a.1 + (b.1 - a.1) * t;
+                            mesh.point_at(row, column, u, v)
+                        };
+                        let (p0, q1, q2, p3) =
+                            (point(0.0), point(1.0 / 3.0), point(2.0 / 3.0), point(1.0));
+                        let fit = |p0: f64, q1: f64, q2: f64, p3: f64| {
+                            let a = 27.0 * q1 - 8.0 * p0 - p3;
+                            let b = 27.0 * q2 - p0 - 8.0 * p3;
+                            ((2.0 * a - b) / 18.0, (2.0 * b - a) / 18.0)
+                        };
+                        let (x1, x2) = fit(p0.x, q1.x, q2.x, p3.x);
+                        let (y1, y2) = fit(p0.y, q1.y, q2.y, p3.y);
+                        [p0, Axes::new(x1, y1), Axes::new(x2, y2)]
+                    };
+
+                    let corners = [(u0, v0), (u1, v0), (u1, v1), (u0, v1)];
+                    let mut points = corners
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(k, &c)| edge(c, corners[(k + 1) % 4]));
+                    patches.push((
+                        std::array::from_fn(|_| points.next().unwrap()),
+                        corners.map(|(u, v)| {
+                            encode_space.convert(mesh.color_at(row, column, u, v))
+                        }),
+                    ));
+                }
+            }
+        }
+    }
+
+    let points = || patches.iter().flat_map(|(points, _)| points);
+    let x0 = points().map(|p| p.x).fold(f64::INFINITY, f64::min) as f32;
+    let x1 = points().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max) as f32;
+    let y0 = points().map(|p| p.y).fold(f64::INFINITY, f64::min) as f32;
+    let y1 = points().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max) as f32;
+    // Avoid empty ranges for degenerate meshes.
+    let bounds = [
+        x0,
+        if x1 > x0 { x1 } else { x0 + 1.0 },
+        y0,
+        if y1 > y0 { y1 } else { y0 + 1.0 },
+    ];
+
+    let mut vertices = Vec::new();
+    for (points, colors) in patches {
+        write_mesh_patch(&mut vertices, points, colors, bounds);
+    }
+
+    (Arc::new(deflate(&vertices)), bounds)
+}
+
+/// Writes a single Coons patch to a binary vec.
+///
+/// The twelve control points of the patch's edges go around it clockwise,
+/// starting at its top-left corner, and every third point is a corner.
+fn write_mesh_patch(
+    target: &mut Vec<u8>,
+    points: [Axes<f64>; 12],
+    colors: [[u16; 3]; 4],
+    [x0, x1, y0, y1]: [f32; 4],
+) {
+    let quantize = |p: Axes<f64>| {
+        [
+            u16::quantize(p.x as f32, [x0, x1]).to_be(),
+            u16::quantize(p.y as f32, [y0, y1]).to_be(),
+        ]
+    };
+
+    // Push the flag
+    target.push(0);
+
+    // Push the points, going around the patch.
+    target.extend_from_slice(bytemuck::cast_slice(&points.map(quantize)));
+
+    // Push the colors.
+    target.extend_from_slice(bytemuck::cast_slice(&colors.map(|c| c.map(u16::to_be))));
+}
+
 fn color_space_of(gradient: &Gradient) -> ColorSpace {
     if gradient.space().hue_index().is_some() {
         ColorSpace::Oklab
@@ -1,28 +1,92 @@
 use std::collections::HashMap;
 use std::io::Cursor;
 
-use image::{DynamicImage, GenericImageView, Rgba};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageOutputFormat, Rgba};
 use pdf_writer::{Chunk, Filter, Finish, Ref};
-use typst::utils::Deferred;
-use typst::visualize::{
-    ColorSpace, Image, ImageKind, RasterFormat, RasterImage, SvgImage,
-};
+use typst::layout::{Abs, Size};
+use typst::utils::{hash128, Deferred};
+use typst::visualize::{ColorSpace, Image, ImageKind, RasterFormat, SvgImage};
 
 use crate::{color, deflate, PdfChunk, WithGlobalRefs};
 
+/// Options that control how raster images are embedded into the PDF.
+///
+/// The default options embed images at their full source resolution.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ImageOptions {
+    /// The maximum effective resolution of raster images, in pixels per inch.
+    ///
+    /// The effective resolution is determined by the largest size at which an
+    /// image is displayed anywhere in the document. Images exceeding it are
+    /// downsampled before embedding. Images are never upsampled.
+    pub max_dpi: Option<f64>,
+    /// The quality (from 1 to 100) with which JPEG images are re-encoded.
+    /// Values outside of this range are clamped.
+    ///
+    /// When `None`, the encoder's default quality is used. Only images that
+    /// are JPEGs in the source are re-encoded lossily. All other raster
+    /// images, including photos stored as PNGs, are compressed losslessly and
+    /// can thus only be made smaller through `max_dpi`.
+    pub jpeg_quality: Option<u8>,
+}
+
 /// Embed all used images into the PDF.
+///
+/// Images with identical data are only embedded once, even if they are used
+/// with different alternative descriptions or in different resource
+/// dictionaries.
 #[typst_macros::time(name = "write images")]
 pub fn write_images(context: &WithGlobalRefs) -> (PdfChunk, HashMap<Image, Ref>) {
+    let options = context.image_options;
+
+    // Determine the largest size at which each distinct image is displayed.
+    // If the size of an image is unknown, it is kept at its full resolution.
+    let mut sizes: HashMap<u128, Size> = HashMap::new();
+    context.resources.traverse(&mut |resources| {
+        for (i, image) in resources.images.items().enumerate() {
+            let size = resources
+                .image_sizes
+                .get(&i)
+                .copied()
+                .unwrap_or_else(|| Size::splat(Abs::inf()));
+            let largest = sizes.entry(hash128(image.kind())).or_default();
+            *largest = largest.max(size);
+        }
+    });
+
+    // Start encoding all images before waiting on any of them, so that the
+    // encoding happens in parallel.
+    let mut handles: HashMap<u128, Deferred<EncodedImage>> = HashMap::new();
+    context.resources.traverse(&mut |resources| {
+        for image in resources.images.items() {
+            let key = hash128(image.kind());
+            handles.entry(key).or_insert_with(|| {
+                let resize =
+                    options.max_dpi.zip(sizes.get(&key)).and_then(|(dpi, &displayed)| {
+                        downsampled_size(image, displayed, dpi)
+                    });
+                deferred_image(image.clone(), resize, options.jpeg_quality)
+            });
+        }
+    });
+
     let mut chunk = PdfChunk::new();
     let mut out = HashMap::new();
+    let mut written: HashMap<u128, Ref> = HashMap::new();
     context.resources.traverse(&mut |resources| {
-        for (i, image) in resources.images.items().enumerate() {
+        for image in resources.images.items() {
             if out.contains_key(image) {
                 continue;
             }
 
-            let handle = resources.deferred_images.get(&i).unwrap();
-            match handle.wait() {
+            let key = hash128(image.kind());
+            if let Some(&image_ref) = written.get(&key) {
+                out.insert(image.clone(), image_ref);
+                continue;
+            }
+
+            match handles[&key].wait() {
                 EncodedImage::Raster {
                     data,
                     filter,
@@ -34,6 +98,7 @@ pub fn write_images(context: &WithGlobalRefs) -> (PdfChunk, HashMap<Image, Ref>)
                 } => {
                     let image_ref = chunk.alloc();
                     out.insert(image.clone(), image_ref);
+                    written.insert(key, image_ref);
 
                     let mut image = chunk.chunk.image_xobject(image_ref, data);
                     image.filter(*filter);
@@ -96,6 +161,7 @@ pub fn write_images(context: &WithGlobalRefs) -> (PdfChunk, HashMap<Image, Ref>)
                         *map.entry(old).or_insert_with(|| chunk.alloc.bump())
                     });
                     out.insert(image.clone(), map[&id]);
+                    written.insert(key, map[&id]);
                 }
             }
         }
@@ -104,12 +170,9 @@ pub fn write_images(context: &WithGlobalRefs) -> (PdfChunk, HashMap<Image, Ref>)
     (chunk, out)
 }
 
-/// Creates a new PDF image from the given image.
-///
-/// Also starts the deferred encoding of the image.
-#[comemo::memoize]
-pub fn deferred_image(image: Image) -> (Deferred<EncodedImage>, Option<ColorSpace>) {
-    let color_space = match image.kind() {
+/// The color space that a raster image without an ICC profile is embedded in.
+pub fn image_color_space(image: &Image) -> Option<ColorSpace> {
+    match image.kind() {
         ImageKind::Raster(raster) if raster.icc().is_none() => {
             if raster.dynamic().color().channel_count() > 2 {
                 Some(ColorSpace::Srgb)
@@ -118,17 +181,32 @@ pub fn deferred_image(image: Image) -> (Deferred<EncodedImage>, Option<ColorSpac
             }
         }
         _ => None,
-    };
+    }
+}
 
-    let deferred = Deferred::new(move || match image.kind() {
+/// Starts the deferred encoding of the given image.
+///
+/// Raster images are resized to `resize` pixels if given and JPEG images are
+/// re-encoded with the given quality.
+#[comemo::memoize]
+fn deferred_image(
+    image: Image,
+    resize: Option<(u32, u32)>,
+    jpeg_quality: Option<u8>,
+) -> Deferred<EncodedImage> {
+    Deferred::new(move || match image.kind() {
         ImageKind::Raster(raster) => {
             let raster = raster.clone();
-            let (width, height) = (raster.width(), raster.height());
-            let (data, filter, has_color) = encode_raster_image(&raster);
+            let resized = resize.map(|(width, height)| {
+                raster.dynamic().resize_exact(width, height, FilterType::Lanczos3)
+            });
+            let dynamic = resized.as_ref().unwrap_or(raster.dynamic());
+            let (width, height) = dynamic.dimensions();
+            let (data, filter, has_color) =
+                encode_raster_image(dynamic, raster.format(), jpeg_quality);
             let icc = raster.icc().map(deflate);
 
-            let alpha =
-                raster.dynamic().color().has_alpha().then(|| encode_alpha(&raster));
+            let alpha = dynamic.color().has_alpha().then(|| encode_alpha(dynamic));
 
             EncodedImage::Raster { data, filter, has_color, width, height, icc, alpha }
         }
@@ -136,23 +214,51 @@ pub fn deferred_image(image: Image) -> (Deferred<EncodedImage>, Option<ColorSpac
             let (chunk, id) = encode_svg(svg);
             EncodedImage::Svg(chunk, id)
         }
-    });
+    })
+}
+
+/// Determine the pixel dimensions a raster image should be downsampled to so
+/// that its effective resolution at the given displayed size does not exceed
+/// `max_dpi`.
+///
+/// Returns `None` if the image should be embedded at its source resolution.
+fn downsampled_size(image: &Image, displayed: Size, max_dpi: f64) -> Option<(u32, u32)> {
+    let ImageKind::Raster(raster) = image.kind() else { return None };
+    let (width, height) = (raster.width() as f64, raster.height() as f64);
 
-    (deferred, color_space)
+    // Scale both axes by the same factor to preserve the aspect ratio. The
+    // larger of the two factors ensures that neither axis drops below the
+    // target resolution, even if the image is displayed stretched.
+    let factor = (displayed.x.to_inches() * max_dpi / width)
+        .max(displayed.y.to_inches() * max_dpi / height);
+    if !(factor > 0.0 && factor < 1.0) {
+        return None;
+    }
+
+    let scale = |pixels: f64| ((pixels * factor).round() as u32).max(1);
+    Some((scale(width), scale(height)))
 }
 
 /// Encode an image with a suitable filter and return the data, filter and
 /// whether the image has color.
 ///
-/// Skips the alpha channel as that's encoded separately.
-fn encode_raster_image(image: &RasterImage) -> (Vec<u8>, Filter, bool) {
-    let dynamic = image.dynamic();
+/// JPEG images are re-encoded with the given quality, all other images are
+/// deflated losslessly. Skips the alpha channel as that's encoded separately.
+fn encode_raster_image(
+    dynamic: &DynamicImage,
+    format: RasterFormat,
+    jpeg_quality: Option<u8>,
+) -> (Vec<u8>, Filter, bool) {
     let channel_count = dynamic.color().channel_count();
     let has_color = channel_count > 2;
 
-    if image.format() == RasterFormat::Jpg {
+    if format == RasterFormat::Jpg {
         let mut data = Cursor::new(vec![]);
-        dynamic.write_to(&mut data, image::ImageFormat::Jpeg).unwrap();
+        let output = match jpeg_quality {
+            Some(quality) => ImageOutputFormat::Jpeg(quality.clamp(1, 100)),
+            None => image::ImageFormat::Jpeg.into(),
+        };
+        dynamic.write_to(&mut data, output).unwrap();
         (data.into_inner(), Filter::DctDecode, has_color)
     } else {
         // TODO: Encode flate streams with PNG-predictor?
@@ -169,12 +275,8 @@ fn encode_raster_image(image: &RasterImage) -> (Vec<u8>, Filter, bool) {
 }
 
 /// Encode an image's alpha channel if present.
-fn encode_alpha(raster: &RasterImage) -> (Vec<u8>, Filter) {
-    let pixels: Vec<_> = raster
-        .dynamic()
-        .pixels()
-        .map(|(_, _, Rgba([_, _, _, a]))| a)
-        .collect();
+fn encode_alpha(dynamic: &DynamicImage) -> (Vec<u8>, Filter) {
+    let pixels: Vec<_> = dynamic.pixels().map(|(_, _, Rgba([_, _, _, a]))| a).collect();
     (deflate(&pixels), Filter::FlateDecode)
 }
 
@@ -207,3 +309,81 @@ pub enum EncodedImage {
     /// The chunk is the SVG converted to PDF objects.
     Svg(Chunk, Ref),
 }
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage};
+    use typst::foundations::Bytes;
+    use typst::visualize::ImageFormat;
+
+    use super::*;
+
+    /// A noisy image, so that its compressed size depends on the quality.
+    fn photo(width: u32, height: u32) -> DynamicImage {
+        let mut seed = 0x9e37_79b9_u32;
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let noise = (seed % 64) as u8;
+            Rgb([(x % 256) as u8 ^ noise, (y % 256) as u8, noise.wrapping_mul(3)])
+        }))
+    }
+
+    fn png(dynamic: &DynamicImage) -> Image {
+        let mut data = Cursor::new(vec![]);
+        dynamic.write_to(&mut data, ImageOutputFormat::Png).unwrap();
+        let format = ImageFormat::Raster(RasterFormat::Png);
+        Image::new(Bytes::from(data.into_inner()), format, None).unwrap()
+    }
+
+    fn inches(x: f64, y: f64) -> Size {
+        Size::new(Abs::inches(x), Abs::inches(y))
+    }
+
+    #[test]
+    fn test_downsampled_size() {
+        let image = png(&photo(300, 200));
+
+        // Displayed at 300 dpi, so it is halved for 150 dpi.
+        assert_eq!(
+            downsampled_size(&image, inches(1.0, 2.0 / 3.0), 150.0),
+            Some((150, 100))
+        );
+
+        // Displayed at 100 dpi, which is below the maximum.
+        assert_eq!(downsampled_size(&image, inches(3.0, 2.0), 150.0), None);
+
+        // Stretched vertically, so that the vertical resolution is too low
+        // for downsampling.
+        assert_eq!(downsampled_size(&image, inches(1.0, 2.0), 150.0), None);
+
+        // Never downsampled to nothing.
+        assert_eq!(downsampled_size(&image, inches(0.001, 0.001), 1.0), Some((1, 1)));
+
+        // Not displayed at a known size.
+        assert_eq!(downsampled_size(&image, Size::splat(Abs::inf()), 150.0), None);
+        assert_eq!(downsampled_size(&image, Size::zero(), 150.0), None);
+    }
+
+    #[test]
+    fn test_jpeg_quality() {
+        let dynamic = photo(128, 128);
+        let encode = |quality| encode_raster_image(&dynamic, RasterFormat::Jpg, quality);
+
+        let (low, filter, has_color) = encode(Some(10));
+        assert_eq!(filter, Filter::DctDecode);
+        assert!(has_color);
+        let (high, ..) = encode(Some(95));
+        let (default, ..) = encode(None);
+        assert!(low.len() < default.len());
+        assert!(default.len() < high.len());
+        assert_eq!(encode(Some(0)).0, encode(Some(1)).0);
+        assert_eq!(encode(Some(200)).0, encode(Some(100)).0);
+
+        // Other formats are always compressed losslessly.
+        let (png, filter, _) = encode_raster_image(&dynamic, RasterFormat::Png, Some(10));
+        assert_eq!(filter, Filter::FlateDecode);
+        assert_eq!(png, encode_raster_image(&dynamic, RasterFormat::Png, None).0);
+    }
+}
@@ -14,6 +14,8 @@ mod page;
 mod pattern;
 mod resources;
 
+pub use self::image::ImageOptions;
+
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::ops::{Deref, DerefMut};
@@ -30,7 +32,7 @@ use typst::visualize::Image;
 use crate::catalog::write_catalog;
 use crate::color::{alloc_color_functions_refs, ColorFunctionRefs};
 use crate::color_font::{write_color_fonts, ColorFontSlice};
-use crate::extg::{write_graphic_states, ExtGState};
+use crate::extg::{write_graphic_states, write_groups, ExtGState, PdfGroup};
 use crate::font::write_fonts;
 use crate::gradient::{write_gradients, PdfGradient};
 use crate::image::write_images;
@@ -63,14 +65,19 @@ use crate::resources::{
 ///
 /// The `page_ranges` option specifies which ranges of pages should be exported
 /// in the PDF. When `None`, all pages should be exported.
+///
+/// The `image_options` control the resolution and quality with which raster
+/// images are embedded. Pass `ImageOptions::default()` to embed them at their
+/// source resolution.
 #[typst_macros::time(name = "pdf")]
 pub fn pdf(
     document: &Document,
     ident: Smart<&str>,
     timestamp: Option<Datetime>,
     page_ranges: Option<PageRanges>,
+    image_options: ImageOptions,
 ) -> Vec<u8> {
-    PdfBuilder::new(document, page_ranges)
+    PdfBuilder::new(document, page_ranges, image_options)
         .phase(|builder| builder.run(traverse_pages))
         .phase(|builder| GlobalRefs {
             color_functions: builder.run(alloc_color_functions_refs),
@@ -84,6 +91,7 @@ pub fn pdf(
             images: builder.run(write_images),
             gradients: builder.run(write_gradients),
             patterns: builder.run(write_patterns),
+            groups: builder.run(write_groups),
             ext_gs: builder.run(write_graphic_states),
         })
         .phase(|builder| builder.run(write_page_tree))
@@ -127,6 +135,8 @@ struct WithDocument<'a> {
     /// Page ranges to export.
     /// When `None`, all pages are exported.
     exported_pages: Option<PageRanges>,
+    /// How to embed raster images.
+    image_options: ImageOptions,
 }
 
 /// At this point, resources were listed, but they don't have any reference
@@ -136,6 +146,7 @@ struct WithDocument<'a> {
 struct WithResources<'a> {
     document: &'a Document,
     exported_pages: Option<PageRanges>,
+    image_options: ImageOptions,
     /// The content of the pages encoded as PDF content streams.
     ///
     /// The pages are at the index corresponding to their page number, but they
@@ -171,6 +182,7 @@ impl<'a> From<(WithDocument<'a>, (Vec<Option<EncodedPage>>, Resources<()>))>
         Self {
             document: previous.document,
             exported_pages: previous.exported_pages,
+            image_options: previous.image_options,
             pages,
             resources,
         }
@@ -185,6 +197,7 @@ impl<'a> From<(WithDocument<'a>, (Vec<Option<EncodedPage>>, Resources<()>))>
 struct WithGlobalRefs<'a> {
     document: &'a Document,
     exported_pages: Option<PageRanges>,
+    image_options: ImageOptions,
     pages: Vec<Option<EncodedPage>>,
     /// Resources are the same as in previous phases, but each dictionary now has a reference.
     resources: Resources,
@@ -197,6 +210,7 @@ impl<'a> From<(WithResources<'a>, GlobalRefs)> for WithGlobalRefs<'a> {
         Self {
             document: previous.document,
             exported_pages: previous.exported_pages,
+            image_options: previous.image_options,
             pages: previous.pages,
             resources: previous.resources.with_refs(&globals.resources),
             globals,
@@ -218,6 +232,8 @@ struct References {
     gradients: HashMap<PdfGradient, Ref>,
     /// The IDs of written patterns.
     patterns: HashMap<PdfPattern, Ref>,
+    /// The IDs of written transparency groups.
+    groups: HashMap<PdfGroup, Ref>,
     /// The IDs of written external graphics states.
     ext_gs: HashMap<ExtGState, Ref>,
 }
@@ -284,11 +300,15 @@ impl<'a> From<(WithRefs<'a>, Ref)> for WithEverything<'a> {
 
 impl<'a> PdfBuilder<WithDocument<'a>> {
     /// Start building a PDF for a Typst document.
-    fn new(document: &'a Document, exported_pages: Option<PageRanges>) -> Self {
+    fn new(
+        document: &'a Document,
+        exported_pages: Option<PageRanges>,
+        image_options: ImageOptions,
+    ) -> Self {
         Self {
             alloc: Ref::new(1),
             pdf: Pdf::new(),
-            state: WithDocument { document, exported_pages },
+            state: WithDocument { document, exported_pages, image_options },
         }
     }
 }
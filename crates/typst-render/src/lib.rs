@@ -2,6 +2,7 @@
 
 mod image;
 mod paint;
+mod shadow;
 mod shape;
 mod text;
 
@@ -10,7 +11,7 @@ use typst::layout::{
     Abs, Axes, Frame, FrameItem, FrameKind, GroupItem, Point, Size, Transform,
 };
 use typst::model::Document;
-use typst::visualize::Color;
+use typst::visualize::{BlendMode, Color, MaskMode};
 
 /// Export a frame into a raster image.
 ///
@@ -195,7 +196,7 @@ fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &Group
                 let mut mask = mask.clone();
                 mask.intersect_path(
                     &path,
-                    sk::FillRule::default(),
+                    shape::to_sk_fill_rule(group.clip_rule),
                     false,
                     sk::Transform::default(),
                 );
@@ -211,7 +212,7 @@ fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &Group
 
                 mask.fill_path(
                     &path,
-                    sk::FillRule::default(),
+                    shape::to_sk_fill_rule(group.clip_rule),
                     false,
                     sk::Transform::default(),
                 );
@@ -222,7 +223,70 @@ fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &Group
         }
     }
 
-    render_frame(canvas, state.with_mask(mask), &group.frame);
+    let state = state.with_mask(mask);
+    if let Some(shadow) = &group.shadow {
+        shadow::render_shadow(canvas, state, group, shadow);
+    }
+
+    if group.is_composited() {
+        render_composited(canvas, state, group);
+    } else {
+        render_frame(canvas, state, &group.frame);
+    }
+}
+
+/// Render a group into a separate layer and composite that onto the canvas.
+fn render_composited(canvas: &mut sk::Pixmap, state: State, group: &GroupItem) {
+    let Some(mut layer) = sk::Pixmap::new(canvas.width(), canvas.height()) else {
+        return;
+    };
+    render_frame(&mut layer, state, &group.frame);
+
+    let mask = group.mask.as_ref().and_then(|mask| {
+        let mut pixmap = sk::Pixmap::new(canvas.width(), canvas.height())?;
+        render_frame(&mut pixmap, state.with_mask(None), &mask.frame);
+        let mask_type = match mask.mode {
+            MaskMode::Alpha => sk::MaskType::Alpha,
+            MaskMode::Luminance => sk::MaskType::Luminance,
+        };
+        Some(sk::Mask::from_pixmap(pixmap.as_ref(), mask_type))
+    });
+
+    let paint = sk::PixmapPaint {
+        opacity: group.opacity.get() as f32,
+        blend_mode: to_sk_blend_mode(group.blend_mode),
+        quality: sk::FilterQuality::Nearest,
+    };
+
+    canvas.draw_pixmap(
+        0,
+        0,
+        layer.as_ref(),
+        &paint,
+        sk::Transform::identity(),
+        mask.as_ref(),
+    );
+}
+
+fn to_sk_blend_mode(blend_mode: BlendMode) -> sk::BlendMode {
+    match blend_mode {
+        BlendMode::Normal => sk::BlendMode::SourceOver,
+        BlendMode::Multiply => sk::BlendMode::Multiply,
+        BlendMode::Screen => sk::BlendMode::Screen,
+        BlendMode::Overlay => sk::BlendMode::Overlay,
+        BlendMode::Darken => sk::BlendMode::Darken,
+        BlendMode::Lighten => sk::BlendMode::Lighten,
+        BlendMode::ColorDodge => sk::BlendMode::ColorDodge,
+        BlendMode::ColorBurn => sk::BlendMode::ColorBurn,
+        BlendMode::HardLight => sk::BlendMode::HardLight,
+        BlendMode::SoftLight => sk::BlendMode::SoftLight,
+        BlendMode::Difference => sk::BlendMode::Difference,
+        BlendMode::Exclusion => sk::BlendMode::Exclusion,
+        BlendMode::Hue => sk::BlendMode::Hue,
+        BlendMode::Saturation => sk::BlendMode::Saturation,
+        BlendMode::Color => sk::BlendMode::Color,
+        BlendMode::Luminosity => sk::BlendMode::Luminosity,
+    }
 }
 
 fn to_sk_transform(transform: &Transform) -> sk::Transform {
@@ -0,0 +1,186 @@
+use tiny_skia as sk;
+use typst::layout::GroupItem;
+use typst::visualize::FixedShadow;
+
+use crate::{paint, render_frame, AbsExt, State};
+
+/// Render the shadow that a group casts into the canvas.
+///
+/// The group's content is rendered into a separate layer that covers the
+/// group's bounds. Its alpha channel is then grown or shrunk by the shadow's
+/// spread, blurred, colored, and drawn at the shadow's offset.
+pub fn render_shadow(
+    canvas: &mut sk::Pixmap,
+    state: State,
+    group: &GroupItem,
+    shadow: &FixedShadow,
+) {
+    let ts = state.transform;
+    let scale = (ts.sx * ts.sy - ts.kx * ts.ky).abs().sqrt();
+    let spread = shadow.spread.to_f32() * scale;
+    // Like on the web, the blur radius is twice the standard deviation.
+    let sigma = shadow.blur.to_f32() * scale / 2.0;
+    let (x, y) = (shadow.offset.x.to_f32(), shadow.offset.y.to_f32());
+    let dx = ts.sx * x + ts.kx * y;
+    let dy = ts.ky * x + ts.sy * y;
+
+    // Leave room for the spread and the blur around the content.
+    let margin = (spread.max(0.0) + 3.0 * sigma).ceil() as usize + 1;
+
+    // The layer only spans the group's bounds on the canvas. Content may
+    // overflow its frame (e.g. the ink of glyphs), so there is some extra room
+    // around it. Parts that can't end up on the canvas are left out.
+    let size = group.frame.size();
+    let (fw, fh) = (size.x.to_f32(), size.y.to_f32());
+    let mut corners = [(0.0, 0.0), (fw, 0.0), (0.0, fh), (fw, fh)]
+        .map(|(x, y)| sk::Point::from_xy(x, y));
+    ts.map_points(&mut corners);
+    let (mut min, mut max) = (corners[0], corners[0]);
+    for p in &corners[1..] {
+        min = sk::Point::from_xy(min.x.min(p.x), min.y.min(p.y));
+        max = sk::Point::from_xy(max.x.max(p.x), max.y.max(p.y));
+    }
+    let pad = 0.5 * (max.x - min.x).max(max.y - min.y) + margin as f32;
+    let m = margin as f32;
+    let (cw, ch) = (canvas.width() as f32, canvas.height() as f32);
+    let left = (min.x - pad).max(-dx - m).floor();
+    let top = (min.y - pad).max(-dy - m).floor();
+    let right = (max.x + pad).min(cw - dx + m).ceil();
+    let bottom = (max.y + pad).min(ch - dy + m).ceil();
+    if !(left < right && top < bottom) {
+        return;
+    }
+
+    let Some(mut layer) = sk::Pixmap::new((right - left) as u32, (bottom - top) as u32)
+    else {
+        return;
+    };
+    let shift = |ts: sk::Transform| ts.post_translate(-left, -top);
+    let layer_state = State {
+        transform: shift(state.transform),
+        container_transform: shift(state.container_transform),
+        ..state.with_mask(None)
+    };
+    render_frame(&mut layer, layer_state, &group.frame);
+
+    // Only process the area covered by the content.
+    let stride = layer.width() as usize;
+    let alphas: Vec<u8> = layer.pixels().iter().map(|p| p.alpha()).collect();
+    let Some((x0, y0, x1, y1)) = bounds(&alphas, stride) else {
+        return;
+    };
+
+    let w = x1 - x0 + 2 * margin;
+    let h = y1 - y0 + 2 * margin;
+    let mut buf = vec![0.0; w * h];
+    for y in y0..y1 {
+        for x in x0..x1 {
+            buf[(y - y0 + margin) * w + x - x0 + margin] =
+                alphas[y * stride + x] as f32 / 255.0;
+        }
+    }
+
+    let radius = spread.abs().round() as usize;
+    if radius > 0 {
+        morph(&mut buf, w, h, radius, spread > 0.0);
+    }
+    blur(&mut buf, w, h, sigma);
+
+    let Some(mut pixmap) = sk::Pixmap::new(w as u32, h as u32) else {
+        return;
+    };
+    let color = paint::to_sk_color_u8(shadow.color);
+    for (pixel, &alpha) in pixmap.pixels_mut().iter_mut().zip(&buf) {
+        let a = (alpha.clamp(0.0, 1.0) * color.alpha() as f32).round() as u8;
+        let premultiply = |c: u8| ((c as u32 * a as u32 + 127) / 255) as u8;
+        if let Some(premultiplied) = sk::PremultipliedColorU8::from_rgba(
+            premultiply(color.red()),
+            premultiply(color.green()),
+            premultiply(color.blue()),
+            a,
+        ) {
+            *pixel = premultiplied;
+        }
+    }
+
+    canvas.draw_pixmap(
+        left as i32 + x0 as i32 - margin as i32 + dx.round() as i32,
+        top as i32 + y0 as i32 - margin as i32 + dy.round() as i32,
+        pixmap.as_ref(),
+        &sk::PixmapPaint::default(),
+        sk::Transform::identity(),
+        state.mask,
+    );
+}
+
+/// The bounds of the non-transparent pixels as `(x0, y0, x1, y1)`, with
+/// exclusive ends.
+fn bounds(alphas: &[u8], stride: usize) -> Option<(usize, usize, usize, usize)> {
+    let mut bounds = None;
+    for (y, row) in alphas.chunks(stride).enumerate() {
+        let Some(first) = row.iter().position(|&a| a > 0) else { continue };
+        let last = row.iter().rposition(|&a| a > 0).unwrap_or(first);
+        let (x0, y0, x1, _) = bounds.unwrap_or((first, y, last + 1, y + 1));
+        bounds = Some((x0.min(first), y0, x1.max(last + 1), y + 1));
+    }
+    bounds
+}
+
+/// Grow (dilate) or shrink (erode) the covered area of an alpha buffer by the
+/// given radius in pixels.
+fn morph(buf: &mut [f32], w: usize, h: usize, radius: usize, grow: bool) {
+    let pick: fn(f32, f32) -> f32 = if grow { f32::max } else { f32::min };
+    let mut line = vec![];
+    for (len, count, stride, step) in [(w, h, w, 1), (h, w, 1, w)] {
+        for i in 0..count {
+            let base = i * stride;
+            line.clear();
+            line.extend((0..len).map(|j| buf[base + j * step]));
+            for j in 0..len {
+                let window = &line[j.saturating_sub(radius)..(j + radius + 1).min(len)];
+                buf[base + j * step] =
+                    window.iter().copied().fold(line[j], |acc, v| pick(acc, v));
+            }
+        }
+    }
+}
+
+/// Blur an alpha buffer with the given standard deviation in pixels.
+///
+/// The Gaussian blur is approximated with three successive box blurs.
+fn blur(buf: &mut [f32], w: usize, h: usize, sigma: f32) {
+    if sigma <= 0.0 {
+        return;
+    }
+
+    // Three box blurs of width `n` have a variance of `(n² - 1) / 4`.
+    let n = (4.0 * sigma * sigma + 1.0).sqrt();
+    let radius = ((n - 1.0) / 2.0).round() as usize;
+    if radius == 0 {
+        return;
+    }
+
+    let norm = 1.0 / (2 * radius + 1) as f32;
+    let mut line = vec![];
+    for _ in 0..3 {
+        for (len, count, stride, step) in [(w, h, w, 1), (h, w, 1, w)] {
+            for i in 0..count {
+                let base = i * stride;
+                line.clear();
+                line.extend((0..len).map(|j| buf[base + j * step]));
+
+                // Keep a running sum over the window around each pixel.
+                let mut sum: f32 = line[..(radius + 1).min(len)].iter().sum();
+                for j in 0..len {
+                    buf[base + j * step] = sum * norm;
+                    if j + radius + 1 < len {
+                        sum += line[j + radius + 1];
+                    }
+                    if j >= radius {
+                        sum -= line[j - radius];
+                    }
+                }
+            }
+        }
+    }
+}
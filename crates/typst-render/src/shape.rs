@@ -1,7 +1,8 @@
 use tiny_skia as sk;
 use typst::layout::{Abs, Axes, Point, Ratio, Size};
 use typst::visualize::{
-    DashPattern, FixedStroke, Geometry, LineCap, LineJoin, Path, PathItem, Shape,
+    DashPattern, FillRule, FixedStroke, Geometry, LineCap, LineJoin, Path, PathItem,
+    Shape,
 };
 
 use crate::{paint, AbsExt, State};
@@ -51,7 +52,7 @@ pub fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Opt
             paint.anti_alias = false;
         }
 
-        let rule = sk::FillRule::default();
+        let rule = to_sk_fill_rule(shape.fill_rule);
         canvas.fill_path(&path, &paint, rule, ts, state.mask);
     }
 
@@ -148,6 +149,13 @@ fn offset_bounding_box(bbox: Size, stroke_width: Abs) -> Size {
     Size::new(bbox.x + stroke_width * 2.0, bbox.y + stroke_width * 2.0)
 }
 
+pub fn to_sk_fill_rule(rule: FillRule) -> sk::FillRule {
+    match rule {
+        FillRule::NonZero => sk::FillRule::Winding,
+        FillRule::EvenOdd => sk::FillRule::EvenOdd,
+    }
+}
+
 pub fn to_sk_line_cap(cap: LineCap) -> sk::LineCap {
     match cap {
         LineCap::Butt => sk::LineCap::Butt,
@@ -15,10 +15,12 @@ use typst::layout::{
 };
 use typst::model::Document;
 use typst::utils::hash128;
-use typst::visualize::{Gradient, Pattern};
+use typst::visualize::{
+    BlendMode, FillRule, FixedShadow, Gradient, Mask, MaskMode, Pattern,
+};
 use xmlwriter::XmlWriter;
 
-use crate::paint::{GradientRef, PatternRef, SVGSubGradient};
+use crate::paint::{ColorEncode, GradientRef, PatternRef, SVGSubGradient};
 use crate::text::RenderedGlyph;
 
 /// Export a frame into a SVG file.
@@ -73,8 +75,8 @@ struct SVGRenderer {
     /// Clip paths are used to clip a group. A clip path is a path that defines
     /// the clipping region. The clip path is referenced by the `clip-path`
     /// attribute of the group. The clip path is in the format of `M x y L x y C
-    /// x1 y1 x2 y2 x y Z`.
-    clip_paths: Deduplicator<EcoString>,
+    /// x1 y1 x2 y2 x y Z` and comes with the rule that determines its inside.
+    clip_paths: Deduplicator<(EcoString, FillRule)>,
     /// Deduplicated gradients with transform matrices. They use a reference
     /// (`href`) to a "source" gradient instead of being defined inline.
     /// This saves a lot of space since gradients are often reused but with
@@ -102,6 +104,11 @@ struct SVGRenderer {
     patterns: Deduplicator<Pattern>,
     /// These are the gradients that compose a conic gradient.
     conic_subgradients: Deduplicator<SVGSubGradient>,
+    /// Masks of composited groups, together with the transform of the group
+    /// whose user space the mask is defined in.
+    masks: Deduplicator<(Mask, Transform)>,
+    /// Shadow filters of groups, together with the size of the group's frame.
+    shadows: Deduplicator<(FixedShadow, Size)>,
 }
 
 /// Contextual information for rendering.
@@ -154,6 +161,8 @@ impl SVGRenderer {
             conic_subgradients: Deduplicator::new('s'),
             pattern_refs: Deduplicator::new('p'),
             patterns: Deduplicator::new('t'),
+            masks: Deduplicator::new('m'),
+            shadows: Deduplicator::new('d'),
         }
     }
 
@@ -227,23 +236,60 @@ impl SVGRenderer {
                 .with_size(group.frame.size()),
         };
 
+        // The shadow is drawn by a wrapping group, so that the group's clip
+        // path doesn't cut it off.
+        if let Some(shadow) = &group.shadow {
+            let size = group.frame.size();
+            let hash = hash128(&(shadow, size));
+            let id = self.shadows.insert_with(hash, || (shadow.clone(), size));
+            self.xml.start_element("g");
+            self.xml.write_attribute_fmt("filter", format_args!("url(#{id})"));
+        }
+
         self.xml.start_element("g");
         self.xml.write_attribute("class", "typst-group");
 
         if let Some(clip_path) = &group.clip_path {
             let hash = hash128(&group);
-            let id = self.clip_paths.insert_with(hash, || shape::convert_path(clip_path));
+            let id = self
+                .clip_paths
+                .insert_with(hash, || (shape::convert_path(clip_path), group.clip_rule));
             self.xml.write_attribute_fmt("clip-path", format_args!("url(#{id})"));
         }
 
+        if group.opacity != Ratio::one() {
+            self.xml.write_attribute("opacity", &group.opacity.get());
+        }
+
+        if group.blend_mode != BlendMode::Normal {
+            self.xml.write_attribute_fmt(
+                "style",
+                format_args!("mix-blend-mode: {}", blend_mode_to_css(group.blend_mode)),
+            );
+        }
+
+        if let Some(mask) = &group.mask {
+            let hash = hash128(&(mask, group.transform));
+            let id = self.masks.insert_with(hash, || (mask.clone(), group.transform));
+            self.xml.write_attribute_fmt("mask", format_args!("url(#{id})"));
+        }
+
         self.render_frame(state, group.transform, &group.frame);
         self.xml.end_element();
+
+        if group.shadow.is_some() {
+            self.xml.end_element();
+        }
     }
 
     /// Finalize the SVG file. This must be called after all rendering is done.
     fn finalize(mut self) -> String {
+        // Masks are written first because their content can use glyphs,
+        // clip paths, gradients, and patterns.
+        self.write_mask_defs();
         self.write_glyph_defs();
         self.write_clip_path_defs();
+        self.write_shadow_defs();
         self.write_gradients();
         self.write_gradient_refs();
         self.write_subgradients();
@@ -261,12 +307,164 @@ impl SVGRenderer {
         self.xml.start_element("defs");
         self.xml.write_attribute("id", "clip-path");
 
-        for (id, path) in self.clip_paths.iter() {
+        for (id, (path, rule)) in self.clip_paths.iter() {
             self.xml.start_element("clipPath");
             self.xml.write_attribute("id", &id);
             self.xml.start_element("path");
             self.xml.write_attribute("d", &path);
+            if *rule == FillRule::EvenOdd {
+                self.xml.write_attribute("clip-rule", "evenodd");
+            }
+            self.xml.end_element();
+            self.xml.end_element();
+        }
+
+        self.xml.end_element();
+    }
+
+    /// Build the mask definitions.
+    fn write_mask_defs(&mut self) {
+        if self.masks.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.xml.write_attribute("id", "masks");
+
+        // Masks can contain groups with masks themselves, which are only
+        // discovered while writing the outer mask.
+        let mut written = 0;
+        loop {
+            let pending: Vec<_> = self
+                .masks
+                .iter()
+                .skip(written)
+                .map(|(id, mask)| (id, mask.clone()))
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+            written += pending.len();
+
+            for (id, (mask, ts)) in pending {
+                self.xml.start_element("mask");
+                self.xml.write_attribute("id", &id);
+                self.xml.write_attribute("maskUnits", "userSpaceOnUse");
+                self.xml.write_attribute(
+                    "mask-type",
+                    match mask.mode {
+                        MaskMode::Alpha => "alpha",
+                        MaskMode::Luminance => "luminance",
+                    },
+                );
+
+                // The mask's region is the bounding box of its transformed
+                // frame.
+                let size = mask.frame.size();
+                let corners = [
+                    Point::zero(),
+                    Point::with_x(size.x),
+                    Point::with_y(size.y),
+                    size.to_point(),
+                ]
+                .map(|point| point.transform(ts));
+                let min_x = corners.iter().map(|p| p.x).fold(Abs::inf(), Abs::min);
+                let min_y = corners.iter().map(|p| p.y).fold(Abs::inf(), Abs::min);
+                let max_x = corners.iter().map(|p| p.x).fold(-Abs::inf(), Abs::max);
+                let max_y = corners.iter().map(|p| p.y).fold(-Abs::inf(), Abs::max);
+                self.xml.write_attribute("x", &min_x.to_pt());
+                self.xml.write_attribute("y", &min_y.to_pt());
+                self.xml.write_attribute("width", &(max_x - min_x).to_pt());
+                self.xml.write_attribute("height", &(max_y - min_y).to_pt());
+
+                let state = State::new(size, Transform::identity());
+                self.render_frame(state, ts, &mask.frame);
+                self.xml.end_element();
+            }
+        }
+
+        self.xml.end_element();
+    }
+
+    /// Build the filter definitions that draw shadows below groups.
+    fn write_shadow_defs(&mut self) {
+        if self.shadows.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.xml.write_attribute("id", "shadows");
+
+        for (id, (shadow, size)) in self.shadows.iter() {
+            self.xml.start_element("filter");
+            self.xml.write_attribute("id", &id);
+            self.xml.write_attribute("filterUnits", "userSpaceOnUse");
+            self.xml.write_attribute("color-interpolation-filters", "sRGB");
+
+            // Content can extend beyond its frame, so the filter region
+            // generously covers the frame and the shadow.
+            let margin = size.x.max(size.y)
+                + shadow.offset.x.abs()
+                + shadow.offset.y.abs()
+                + shadow.spread.max(Abs::zero())
+                + shadow.blur * 2.0;
+            self.xml.write_attribute("x", &(-margin).to_pt());
+            self.xml.write_attribute("y", &(-margin).to_pt());
+            self.xml.write_attribute("width", &(size.x + margin * 2.0).to_pt());
+            self.xml.write_attribute("height", &(size.y + margin * 2.0).to_pt());
+
+            let mut input = "SourceAlpha";
+            if shadow.spread != Abs::zero() {
+                self.xml.start_element("feMorphology");
+                self.xml.write_attribute("in", input);
+                self.xml.write_attribute(
+                    "operator",
+                    if shadow.spread > Abs::zero() { "dilate" } else { "erode" },
+                );
+                self.xml.write_attribute("radius", &shadow.spread.abs().to_pt());
+                self.xml.write_attribute("result", "spread");
+                self.xml.end_element();
+                input = "spread";
+            }
+
+            if shadow.blur > Abs::zero() {
+                // Like on the web, the blur radius is twice the standard
+                // deviation.
+                self.xml.start_element("feGaussianBlur");
+                self.xml.write_attribute("in", input);
+                self.xml.write_attribute("stdDeviation", &(shadow.blur / 2.0).to_pt());
+                self.xml.write_attribute("result", "blur");
+                self.xml.end_element();
+                input = "blur";
+            }
+
+            self.xml.start_element("feOffset");
+            self.xml.write_attribute("in", input);
+            self.xml.write_attribute("dx", &shadow.offset.x.to_pt());
+            self.xml.write_attribute("dy", &shadow.offset.y.to_pt());
+            self.xml.write_attribute("result", "offset");
             self.xml.end_element();
+
+            self.xml.start_element("feFlood");
+            self.xml
+                .write_attribute("flood-color", &shadow.color.with_alpha(1.0).encode());
+            self.xml
+                .write_attribute("flood-opacity", &shadow.color.alpha().unwrap_or(1.0));
+            self.xml.end_element();
+
+            self.xml.start_element("feComposite");
+            self.xml.write_attribute("in2", "offset");
+            self.xml.write_attribute("operator", "in");
+            self.xml.end_element();
+
+            self.xml.start_element("feMerge");
+            self.xml.start_element("feMergeNode");
+            self.xml.end_element();
+            self.xml.start_element("feMergeNode");
+            self.xml.write_attribute("in", "SourceGraphic");
+            self.xml.end_element();
+            self.xml.end_element();
+
             self.xml.end_element();
         }
 
@@ -274,6 +472,28 @@ impl SVGRenderer {
     }
 }
 
+/// The CSS name of a blend mode.
+fn blend_mode_to_css(blend_mode: BlendMode) -> &'static str {
+    match blend_mode {
+        BlendMode::Normal => "normal",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Overlay => "overlay",
+        BlendMode::Darken => "darken",
+        BlendMode::Lighten => "lighten",
+        BlendMode::ColorDodge => "color-dodge",
+        BlendMode::ColorBurn => "color-burn",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::SoftLight => "soft-light",
+        BlendMode::Difference => "difference",
+        BlendMode::Exclusion => "exclusion",
+        BlendMode::Hue => "hue",
+        BlendMode::Saturation => "saturation",
+        BlendMode::Color => "color",
+        BlendMode::Luminosity => "luminosity",
+    }
+}
+
 /// Deduplicates its elements. It is used to deduplicate glyphs and clip paths.
 /// The `H` is the hash type, and `T` is the value type. The `PREFIX` is the
 /// prefix of the index. This is used to distinguish between glyphs and clip
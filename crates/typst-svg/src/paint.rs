@@ -15,6 +15,9 @@ use crate::{Id, SVGRenderer, State, SvgMatrix, SvgPathBuilder};
 /// Smaller values could be interesting for optimization.
 const CONIC_SEGMENT: usize = 360;
 
+/// The number of cells along each side of a patch of a mesh gradient.
+const MESH_SUBDIVISIONS: usize = 16;
+
 impl SVGRenderer {
     /// Render a frame to a string.
     pub(super) fn render_pattern_frame(
@@ -227,6 +230,70 @@ impl SVGRenderer {
                         self.xml.end_element();
                     }
 
+                    // We skip the default stop generation code.
+                    self.xml.end_element();
+                    continue;
+                }
+                Gradient::Mesh(mesh) => {
+                    // The pattern covers a generous area around the
+                    // container, so that vertices outside of it don't repeat.
+                    self.xml.start_element("pattern");
+                    self.xml.write_attribute("id", &id);
+                    self.xml.write_attribute("viewBox", "-1 -1 3 3");
+                    self.xml.write_attribute("preserveAspectRatio", "none");
+                    self.xml.write_attribute("patternUnits", "userSpaceOnUse");
+                    self.xml.write_attribute("width", "3");
+                    self.xml.write_attribute("height", "3");
+                    self.xml.write_attribute("x", "-1");
+                    self.xml.write_attribute("y", "-1");
+
+                    // SVG has no mesh gradients, so we approximate each patch
+                    // with a grid of small, evenly colored cells.
+                    let patches = mesh.patches();
+                    for row in 0..patches.y {
+                        for column in 0..patches.x {
+                            let point = |u: f64, v: f64| {
+                                let p = mesh.point_at(row, column, u, v);
+                                (p.x as f32, p.y as f32)
+                            };
+
+                            for i in 0..MESH_SUBDIVISIONS {
+                                for j in 0..MESH_SUBDIVISIONS {
+                                    let n = MESH_SUBDIVISIONS as f64;
+                                    let (u0, u1) = (j as f64 / n, (j + 1) as f64 / n);
+                                    let (v0, v1) = (i as f64 / n, (i + 1) as f64 / n);
+
+                                    let mut builder = SvgPathBuilder::default();
+                                    let (x, y) = point(u0, v0);
+                                    builder.move_to(x, y);
+                                    for (u, v) in [(u1, v0), (u1, v1), (u0, v1)] {
+                                        let (x, y) = point(u, v);
+                                        builder.line_to(x, y);
+                                    }
+                                    builder.close();
+
+                                    let color = mesh
+                                        .color_at(
+                                            row,
+                                            column,
+                                            (u0 + u1) / 2.0,
+                                            (v0 + v1) / 2.0,
+                                        )
+                                        .to_hex();
+
+                                    // The thin stroke hides the seams between
+                                    // the cells.
+                                    self.xml.start_element("path");
+                                    self.xml.write_attribute("d", &builder.0);
+                                    self.xml.write_attribute("fill", &color);
+                                    self.xml.write_attribute("stroke", &color);
+                                    self.xml.write_attribute("stroke-width", "0.002");
+                                    self.xml.end_element();
+                                }
+                            }
+                        }
+                    }
+
                     // We skip the default stop generation code.
                     self.xml.end_element();
                     continue;
@@ -336,7 +403,7 @@ impl SVGRenderer {
                         &SvgMatrix(gradient_ref.transform),
                     );
                 }
-                GradientKind::Conic => {
+                GradientKind::Conic | GradientKind::Mesh => {
                     self.xml.start_element("pattern");
                     self.xml.write_attribute(
                         "patternTransform",
@@ -476,6 +543,8 @@ enum GradientKind {
     Radial,
     /// A conic gradient.
     Conic,
+    /// A mesh gradient.
+    Mesh,
 }
 
 impl From<&Gradient> for GradientKind {
@@ -484,6 +553,7 @@ impl From<&Gradient> for GradientKind {
             Gradient::Linear { .. } => GradientKind::Linear,
             Gradient::Radial { .. } => GradientKind::Radial,
             Gradient::Conic { .. } => GradientKind::Conic,
+            Gradient::Mesh { .. } => GradientKind::Mesh,
         }
     }
 }
@@ -41,6 +41,10 @@ pub struct PackageInfo {
     pub entrypoint: EcoString,
     /// The minimum required compiler version for the package.
     pub compiler: Option<PackageVersion>,
+    /// Paths of font files within the package that are made available to
+    /// documents importing the package.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fonts: Vec<EcoString>,
 }
 
 impl PackageManifest {
@@ -7,6 +7,7 @@ use crate::foundations::{Content, Module, Value};
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::package::{PackageManifest, PackageSpec};
 use crate::syntax::{FileId, Span, VirtualPath};
+use crate::text::FontFile;
 use crate::World;
 
 impl Eval for ast::ModuleImport<'_> {
@@ -179,6 +180,20 @@ fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Mo
         .at(span)?;
     manifest.validate(&spec).at(span)?;
 
+    // Make the package's fonts available to the whole document.
+    for path in &manifest.package.fonts {
+        let id = manifest_id.join(path);
+        let data = vm.world().file(id).at(span)?;
+        let file = FontFile::new(path.clone(), data)
+            .map_err(|_| {
+                eco_format!(
+                    "failed to load package font {path} (unknown or malformed format)"
+                )
+            })
+            .at(span)?;
+        vm.engine.tracer.font(file);
+    }
+
     // Evaluate the entry point.
     let entrypoint_id = manifest_id.join(&manifest.package.entrypoint);
     let source = vm.world().source(entrypoint_id).at(span)?;
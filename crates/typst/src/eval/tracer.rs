@@ -5,6 +5,7 @@ use ecow::EcoVec;
 use crate::diag::SourceDiagnostic;
 use crate::foundations::{Styles, Value};
 use crate::syntax::{FileId, Span};
+use crate::text::FontFile;
 use crate::utils::hash128;
 
 /// Traces warnings and which values existed for an expression at a span.
@@ -15,6 +16,7 @@ pub struct Tracer {
     warnings_set: HashSet<u128>,
     delayed: EcoVec<SourceDiagnostic>,
     values: EcoVec<(Value, Option<Styles>)>,
+    fonts: EcoVec<FontFile>,
 }
 
 impl Tracer {
@@ -46,6 +48,11 @@ impl Tracer {
     pub fn values(self) -> EcoVec<(Value, Option<Styles>)> {
         self.values
     }
+
+    /// Get the font files declared by imported packages.
+    pub fn fonts(&self) -> &[FontFile] {
+        &self.fonts
+    }
 }
 
 #[comemo::track]
@@ -73,6 +80,21 @@ impl Tracer {
         }
     }
 
+    /// Add a font file declared by an imported package.
+    pub fn font(&mut self, file: FontFile) {
+        if !self.fonts.contains(&file) {
+            self.fonts.push(file);
+        }
+    }
+
+    /// Whether a font file declared by an imported package provides the given
+    /// lowercased family.
+    pub fn has_font_family(&self, family: &str) -> bool {
+        self.fonts
+            .iter()
+            .any(|file| file.families().iter().any(|name| name == family))
+    }
+
     /// Trace a value for the span.
     pub fn value(&mut self, value: Value, styles: Option<Styles>) {
         if self.values.len() < Self::MAX_VALUES {
@@ -2,16 +2,20 @@ use std::cmp::Ordering;
 use std::hash::Hash;
 use std::ops::{Add, Sub};
 
+use comemo::Tracked;
 use ecow::{eco_format, EcoString, EcoVec};
 use time::error::{Format, InvalidFormatDescription};
+use time::format_description::modifier::{MonthRepr, WeekdayRepr};
+use time::format_description::{Component, OwnedFormatItem};
 use time::macros::format_description;
 use time::{format_description, Month, PrimitiveDateTime};
 
 use crate::diag::{bail, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, func, repr, scope, ty, Dict, Duration, Repr, Smart, Str, Value,
+    cast, func, repr, scope, ty, Context, Dict, Duration, Repr, Smart, Str, Value,
 };
+use crate::text::{month_name, weekday_name, Lang, Region, TextElem};
 use crate::World;
 
 /// Represents a date, a time, or a combination of both.
@@ -93,9 +97,9 @@ use crate::World;
 ///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
 ///     month is padded.
 ///   - `repr`: Can be either `numerical`, `long` or `short`. Specifies if the
-///     month should be displayed as a number or a word. Unfortunately, when
-///     choosing the word representation, it can currently only display the
-///     English version. In the future, it is planned to support localization.
+///     month should be displayed as a number or a word. The word is in the
+///     [language]($text.lang) of the text if the datetime is displayed within
+///     a [`context`] expression and in English otherwise.
 /// - `day`: Displays the day of the datetime.
 ///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
 ///     day is padded.
@@ -107,10 +111,10 @@ use crate::World;
 ///      and 53.
 /// - `weekday`: Displays the weekday of the date.
 ///   - `repr` Can be either `long`, `short`, `sunday` or `monday`. In the case
-///     of `long` and `short`, the corresponding English name will be displayed
-///     (same as for the month, other languages are currently not supported). In
-///     the case of `sunday` and `monday`, the numerical value will be displayed
-///     (assuming Sunday and Monday as the first day of the week, respectively).
+///     of `long` and `short`, the corresponding name will be displayed (in the
+///     same language as for the month). In the case of `sunday` and `monday`,
+///     the numerical value will be displayed (assuming Sunday and Monday as
+///     the first day of the week, respectively).
 ///   - `one_indexed`: Can be either `true` or `false`. Defines whether the
 ///     numerical representation of the week starts with 0 or 1.
 /// - `hour`: Displays the hour of the date.
@@ -229,6 +233,15 @@ impl Datetime {
             Datetime::Time(_) => "time",
         }
     }
+
+    /// The date of the datetime, or `None` for times without a date.
+    fn date(&self) -> Option<time::Date> {
+        match self {
+            Self::Date(date) => Some(*date),
+            Self::Time(_) => None,
+            Self::Datetime(datetime) => Some(datetime.date()),
+        }
+    }
 }
 
 #[scope]
@@ -341,9 +354,22 @@ impl Datetime {
     /// `[[year]-[month]-[day] [hour]:[minute]:[second]]`.
     ///
     /// See the [format syntax]($datetime/#format) for more information.
-    #[func]
+    ///
+    /// Within a [`context`] expression, the names of months and weekdays are
+    /// in the [language]($text.lang) of the text. They are given in their
+    /// standalone form and fall back to English for languages Typst has no
+    /// translations for.
+    ///
+    /// ```example
+    /// #set text(lang: "fr")
+    /// #let date = datetime(year: 2024, month: 3, day: 1)
+    /// #context date.display("[weekday] [day] [month repr:long] [year]")
+    /// ```
+    #[func(contextual)]
     pub fn display(
         &self,
+        /// The callsite context.
+        context: Tracked<Context>,
         /// The format used to display the datetime.
         #[default]
         pattern: Smart<DisplayPattern>,
@@ -358,11 +384,23 @@ impl Datetime {
                 }
             },
 
-            Smart::Custom(DisplayPattern(_, format)) => match self {
-                Self::Date(date) => date.format(&format),
-                Self::Time(time) => time.format(&format),
-                Self::Datetime(datetime) => datetime.format(&format),
-            },
+            Smart::Custom(DisplayPattern(_, format)) => {
+                let format = match (context.styles(), self.date()) {
+                    (Ok(styles), Some(date)) => localize(
+                        &format,
+                        TextElem::lang_in(styles),
+                        TextElem::region_in(styles),
+                        date,
+                    ),
+                    _ => format,
+                };
+
+                match self {
+                    Self::Date(date) => date.format(&format),
+                    Self::Time(time) => time.format(&format),
+                    Self::Datetime(datetime) => datetime.format(&format),
+                }
+            }
         };
         result.map(EcoString::from).map_err(format_time_format_error)
     }
@@ -533,6 +571,48 @@ cast! {
     v: u8 => Self::try_from(v).map_err(|_| "month is invalid")?
 }
 
+/// Replace the names of months and weekdays in a format with their names in
+/// the given language.
+fn localize(
+    item: &OwnedFormatItem,
+    lang: Lang,
+    region: Option<Region>,
+    date: time::Date,
+) -> OwnedFormatItem {
+    let month = u8::from(date.month());
+    let weekday = date.weekday().number_from_monday();
+    let name = match item {
+        OwnedFormatItem::Component(Component::Month(modifier)) => match modifier.repr {
+            MonthRepr::Long => month_name(lang, region, month, false),
+            MonthRepr::Short => month_name(lang, region, month, true),
+            _ => return item.clone(),
+        },
+        OwnedFormatItem::Component(Component::Weekday(modifier)) => match modifier.repr {
+            WeekdayRepr::Long => weekday_name(lang, region, weekday, false),
+            WeekdayRepr::Short => weekday_name(lang, region, weekday, true),
+            _ => return item.clone(),
+        },
+        OwnedFormatItem::Compound(items) => {
+            return OwnedFormatItem::Compound(
+                items.iter().map(|item| localize(item, lang, region, date)).collect(),
+            );
+        }
+        OwnedFormatItem::Optional(item) => {
+            return OwnedFormatItem::Optional(Box::new(localize(
+                item, lang, region, date,
+            )));
+        }
+        OwnedFormatItem::First(items) => {
+            return OwnedFormatItem::First(
+                items.iter().map(|item| localize(item, lang, region, date)).collect(),
+            );
+        }
+        _ => return item.clone(),
+    };
+
+    OwnedFormatItem::Literal(name.as_bytes().into())
+}
+
 /// Format the `Format` error of the time crate in an appropriate way.
 fn format_time_format_error(error: Format) -> EcoString {
     match error {
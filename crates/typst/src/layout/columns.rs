@@ -2,21 +2,25 @@ use std::num::NonZeroUsize;
 
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::foundations::{
+    elem, Content, NativeElement, Packed, Resolve, Show, StyleChain, StyledElem, Styles,
+};
+use crate::introspection::Locator;
 use crate::layout::{
-    Abs, Axes, BlockElem, Dir, Fragment, Frame, Length, Point, Ratio, Regions, Rel, Size,
+    Abs, Axes, BlockElem, Dir, FixedAlignment, FlowElem, Fragment, Frame, Length,
+    PlaceElem, Point, Ratio, Regions, Rel, Size, VAlignment,
 };
-use crate::realize::{Behave, Behaviour};
+use crate::realize::{realize_flow, Arenas, Behave, Behaviour};
 use crate::text::TextElem;
 use crate::utils::Numeric;
 
 /// Separates a region into multiple equally sized columns.
 ///
 /// The `column` function allows to separate the interior of any container into
-/// multiple columns. It will not equalize the height of the columns, instead,
-/// the columns will take up the height of their container or the remaining
-/// height on the page. The columns function can break across pages if
-/// necessary.
+/// multiple columns. Unless [balanced]($columns.balance), it will not equalize
+/// the height of the columns, instead, the columns will take up the height of
+/// their container or the remaining height on the page. The columns function
+/// can break across pages if necessary.
 ///
 /// If you need to insert columns across your whole document, you can use the
 /// [`{page}` function's `columns` parameter]($page.columns) instead.
@@ -53,6 +57,29 @@ pub struct ColumnsElem {
     #[default(Ratio::new(0.04).into())]
     pub gutter: Rel<Length>,
 
+    /// Whether to balance the columns in the last region.
+    ///
+    /// By default, the columns are filled one after another, so that the
+    /// last column of a section may end up much shorter than the others or
+    /// even empty. With balancing, the content in the last region is
+    /// distributed such that all its columns have about the same height. To
+    /// balance the [columns of pages]($page.columns), use
+    /// `{set columns(balance: true)}`. This applies to the last page of the
+    /// document and to pages that end with a page break.
+    ///
+    /// ```example
+    /// #columns(2, balance: true)[
+    ///   #lorem(30)
+    /// ]
+    /// ```
+    #[default(false)]
+    pub balance: bool,
+
+    /// Whether page-scoped floats in the body are collected by the columns.
+    #[internal]
+    #[ghost]
+    pub collect_floats: bool,
+
     /// The content that should be layouted into the columns.
     #[required]
     pub body: Content,
@@ -66,6 +93,10 @@ impl Show for Packed<ColumnsElem> {
     }
 }
 
+/// How often the columns are laid out at most to find the regions of all
+/// page-scoped floats.
+const MAX_ATTEMPTS: usize = 5;
+
 /// Layout the columns.
 #[typst_macros::time(span = elem.span())]
 fn layout_columns(
@@ -87,59 +118,324 @@ fn layout_columns(
     let gutter = elem.gutter(styles).relative_to(regions.base().x);
     let width = (regions.size.x - gutter * (columns - 1) as f64) / columns as f64;
 
-    let backlog: Vec<_> = std::iter::once(&regions.size.y)
-        .chain(regions.backlog)
-        .flat_map(|&height| std::iter::repeat(height).take(columns))
-        .skip(1)
-        .collect();
-
-    // Create the pod regions.
-    let pod = Regions {
-        size: Size::new(width, regions.size.y),
-        full: regions.full,
-        backlog: &backlog,
-        last: regions.last,
-        expand: Axes::new(true, regions.expand.y),
-        root: regions.root,
+    // The body is laid out as a flow directly, so that the flow can hand its
+    // page-scoped floats to the columns. It may already be a realized flow
+    // if the columns are those of a page.
+    let collect = ColumnsElem::set_collect_floats(true).wrap();
+    let chained = styles.chain(&collect);
+    let arenas = Arenas::default();
+    let (flow, flow_styles) = match body.to_packed::<FlowElem>() {
+        Some(flow) => (flow.clone(), chained),
+        None => realize_flow(engine, &arenas, body, chained)?,
+    };
+
+    let layouter = ColumnsLayouter {
+        flow: &flow,
+        styles: flow_styles,
+        regions,
+        columns,
+        width,
     };
 
-    // Layout the children.
-    let mut frames = body.layout(engine, styles, pod)?.into_iter();
+    // Layout the children. Page-scoped floats reduce the height of the
+    // columns in the region they appear in, which may move content and
+    // other floats to different regions. Hence, we repeat the layout until
+    // no new floats show up.
+    let locator = engine.locator.clone();
+    let mut floats: Vec<PageFloat> = vec![];
+    let mut frames;
+    let mut attempts = 0;
+    loop {
+        *engine.locator = locator.clone();
+        let markers;
+        (frames, markers) = layouter.layout(engine, &floats, None)?;
+        attempts += 1;
+
+        let mut changed = false;
+        for (i, marker) in markers {
+            if floats.iter().any(|float| float.marker == marker) {
+                continue;
+            }
+
+            let float = PageFloat::new(engine, marker, i / columns, styles, regions)?;
+            floats.push(float);
+            changed = true;
+        }
+
+        if !changed || attempts >= MAX_ATTEMPTS {
+            break;
+        }
+    }
+
+    // Balance the columns in the last region.
+    if elem.balance(styles) {
+        if let Some((region, height)) =
+            layouter.balance(engine, &locator, &frames, &floats)?
+        {
+            *engine.locator = locator.clone();
+            (frames, _) = layouter.layout(engine, &floats, Some((region, height)))?;
+            frames.truncate((region + 1) * columns);
+        }
+    }
+
+    let mut frames = frames.into_iter();
     let mut finished = vec![];
 
     let dir = TextElem::dir_in(styles);
     let total_regions = (frames.len() as f32 / columns as f32).ceil() as usize;
 
     // Stitch together the columns for each region.
-    for region in regions.iter().take(total_regions) {
+    for (k, region) in regions.iter().take(total_regions).enumerate() {
+        let top = reserved(&floats, k, false);
+        let bottom = reserved(&floats, k, true);
+
         // The height should be the parent height if we should expand.
         // Otherwise its the maximum column height for the frame. In that
         // case, the frame is first created with zero height and then
         // resized.
-        let height = if regions.expand.y { region.y } else { Abs::zero() };
+        let height = if regions.expand.y { region.y } else { top + bottom };
         let mut output = Frame::hard(Size::new(regions.size.x, height));
         let mut cursor = Abs::zero();
 
         for _ in 0..columns {
             let Some(frame) = frames.next() else { break };
             if !regions.expand.y {
-                output.size_mut().y.set_max(frame.height());
+                output.size_mut().y.set_max(top + frame.height() + bottom);
             }
 
+            // Only right-to-left columns are mirrored. Vertical pages are laid
+            // out like left-to-right ones and turned afterwards, which puts
+            // the first column at the top.
             let width = frame.width();
             let x =
-                if dir == Dir::LTR { cursor } else { regions.size.x - cursor - width };
+                if dir == Dir::RTL { regions.size.x - cursor - width } else { cursor };
 
-            output.push_frame(Point::with_x(x), frame);
+            output.push_frame(Point::new(x, top), frame);
             cursor += width + gutter;
         }
 
+        // Place the page-scoped floats of the region above and below the
+        // columns.
+        let mut top_offset = Abs::zero();
+        let mut bottom_offset = output.height() - bottom;
+        for float in floats.iter().filter(|float| float.region == k) {
+            let (placed, map) = unpack_marker(&float.marker).unwrap();
+            let chained = styles.chain(map);
+            let mut frame = placed.layout(engine, chained, regions.base())?.into_frame();
+            frame.post_process(chained);
+
+            let size = output.size();
+            let x = float.x_align.position(size.x - frame.width());
+            let y = if float.bottom {
+                let y = bottom_offset + float.clearance;
+                bottom_offset += float.clearance + frame.height();
+                y
+            } else {
+                let y = top_offset;
+                top_offset += frame.height() + float.clearance;
+                y
+            };
+
+            let pos =
+                Point::new(x, y) + float.delta.zip_map(size, Rel::relative_to).to_point();
+            output.push_frame(pos, frame);
+        }
+
         finished.push(output);
     }
 
     Ok(Fragment::frames(finished))
 }
 
+/// The markers of page-scoped floats in the body of columns, each with the
+/// index of the column frame it appeared in.
+type Markers = Vec<(usize, Content)>;
+
+/// Lays out the body of columns into column regions.
+struct ColumnsLayouter<'a> {
+    /// The body of the columns, realized into a flow.
+    flow: &'a Packed<FlowElem>,
+    /// The styles of the columns.
+    styles: StyleChain<'a>,
+    /// The regions of the columns.
+    regions: Regions<'a>,
+    /// The number of columns.
+    columns: usize,
+    /// The width of each column.
+    width: Abs,
+}
+
+impl ColumnsLayouter<'_> {
+    /// Layout the body into the columns, reducing their height by the space
+    /// the page-scoped floats need in each region.
+    ///
+    /// If `balanced` is given, the columns of the given region have the
+    /// given height and all following regions as well.
+    ///
+    /// Returns the column frames together with the markers of the page-scoped
+    /// floats in the body and the index of the frame each appeared in.
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        floats: &[PageFloat],
+        balanced: Option<(usize, Abs)>,
+    ) -> SourceResult<(Vec<Frame>, Markers)> {
+        let count = floats
+            .iter()
+            .map(|float| float.region + 1)
+            .chain(balanced.map(|(region, _)| region + 1))
+            .fold(self.regions.backlog.len() + 1, usize::max);
+
+        let heights: Vec<_> = self
+            .regions
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(k, size)| match balanced {
+                Some((region, height)) if region == k => height,
+                _ => (size.y - reserved(floats, k, false) - reserved(floats, k, true))
+                    .max(Abs::zero()),
+            })
+            .flat_map(|height| std::iter::repeat(height).take(self.columns))
+            .collect();
+
+        let last = match balanced {
+            Some((_, height)) => Some(height),
+            None => self.regions.last,
+        };
+
+        // Create the pod regions.
+        let pod = Regions {
+            size: Size::new(self.width, heights[0]),
+            full: self.regions.full,
+            backlog: &heights[1..],
+            last,
+            expand: Axes::new(true, self.regions.expand.y),
+            root: self.regions.root,
+        };
+
+        let (fragment, markers) =
+            self.flow.layout_with_floats(engine, self.styles, pod)?;
+        Ok((fragment.into_frames(), markers))
+    }
+
+    /// Find the smallest height of the columns in the last region with which
+    /// all content still fits into the region.
+    ///
+    /// Returns the region together with the height or `None` if there is
+    /// nothing to balance.
+    fn balance<'e>(
+        &self,
+        engine: &mut Engine<'e>,
+        locator: &Locator<'e>,
+        frames: &[Frame],
+        floats: &[PageFloat],
+    ) -> SourceResult<Option<(usize, Abs)>> {
+        let Some(last) = frames.iter().rposition(|frame| !frame.is_empty()) else {
+            return Ok(None);
+        };
+
+        let region = last / self.columns;
+        let Some(size) = self.regions.iter().nth(region) else {
+            return Ok(None);
+        };
+
+        let full =
+            size.y - reserved(floats, region, false) - reserved(floats, region, true);
+        if !full.is_finite() || full <= Abs::zero() {
+            return Ok(None);
+        }
+
+        // Bisect the height. A height fits if no content ends up in the
+        // columns after the region.
+        let precision = Abs::pt(1.0);
+        let (mut lower, mut upper) = (Abs::zero(), full);
+        while upper - lower > precision {
+            let height = (lower + upper) / 2.0;
+            *engine.locator = locator.clone();
+            let (frames, _) = self.layout(engine, floats, Some((region, height)))?;
+            let fits =
+                frames.iter().skip((region + 1) * self.columns).all(Frame::is_empty);
+            if fits {
+                upper = height;
+            } else {
+                lower = height;
+            }
+        }
+
+        Ok(Some((region, upper)))
+    }
+}
+
+/// A floating element that spans all columns.
+struct PageFloat {
+    /// The marker of the float handed over by the flow: The placed element
+    /// together with its styles.
+    marker: Content,
+    /// The index of the region the float is placed in.
+    region: usize,
+    /// The height of the float including its clearance.
+    height: Abs,
+    /// The clearance between the float and the columns.
+    clearance: Abs,
+    /// Whether the float is placed at the bottom of the region.
+    bottom: bool,
+    /// The horizontal alignment of the float.
+    x_align: FixedAlignment,
+    /// The displacement of the float.
+    delta: Axes<Rel<Abs>>,
+}
+
+impl PageFloat {
+    /// Measure a float found in a region of the columns.
+    fn new(
+        engine: &mut Engine,
+        marker: Content,
+        region: usize,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Self> {
+        let (placed, map) = unpack_marker(&marker).unwrap();
+        let styles = styles.chain(map);
+        let frame = placed.layout(engine, styles, regions.base())?.into_frame();
+        let clearance = placed.clearance(styles);
+        let alignment = placed.alignment(styles);
+        let bottom =
+            alignment.is_custom_and(|align| align.y() == Some(VAlignment::Bottom));
+        let x_align = alignment.map_or(FixedAlignment::Center, |align| {
+            align.x().unwrap_or_default().resolve(styles)
+        });
+        let delta = Axes::new(placed.dx(styles), placed.dy(styles)).resolve(styles);
+        Ok(Self {
+            height: frame.height() + clearance,
+            marker,
+            region,
+            clearance,
+            bottom,
+            x_align,
+            delta,
+        })
+    }
+}
+
+/// The space reserved for page-scoped floats at the top or bottom of a
+/// region.
+fn reserved(floats: &[PageFloat], region: usize, bottom: bool) -> Abs {
+    floats
+        .iter()
+        .filter(|float| float.region == region && float.bottom == bottom)
+        .map(|float| float.height)
+        .sum()
+}
+
+/// Split the marker of a page-scoped float into the placed element and its
+/// styles.
+fn unpack_marker(marker: &Content) -> Option<(&Packed<PlaceElem>, &Styles)> {
+    let styled = marker.to_packed::<StyledElem>()?;
+    let placed = styled.child.to_packed::<PlaceElem>()?;
+    Some((placed, &styled.styles))
+}
+
 /// Forces a column break.
 ///
 /// The function will behave like a [page break]($pagebreak) when used in a
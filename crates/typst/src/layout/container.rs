@@ -11,8 +11,9 @@ use crate::layout::{
     Abs, Axes, Corners, Em, Fr, Fragment, Frame, FrameKind, Length, Region, Regions, Rel,
     Sides, Size, Spacing, VElem,
 };
+use crate::syntax::Span;
 use crate::utils::Numeric;
-use crate::visualize::{clip_rect, Paint, Stroke};
+use crate::visualize::{clip_rect, find_outline, Paint, Shadow, Stroke};
 
 /// An inline-level container that sizes content.
 ///
@@ -105,8 +106,37 @@ pub struct BoxElem {
     pub outset: Sides<Option<Rel<Length>>>,
 
     /// Whether to clip the content inside the box.
-    #[default(false)]
-    pub clip: bool,
+    ///
+    /// With `{true}`, the content is clipped to the box's rectangle, which
+    /// can be rounded with the [`radius`]($box.radius). Alternatively, the
+    /// content can be clipped to the outline of a shape like a [`circle`],
+    /// [`polygon`], or [`path`]. The shape is laid out in the area of the
+    /// box.
+    ///
+    /// ```example
+    /// #box(
+    ///   clip: circle(),
+    ///   image("tiger.jpg", width: 60pt),
+    /// )
+    /// ```
+    #[default(Clip::Bool(false))]
+    pub clip: Clip,
+
+    /// A shadow that the box casts onto what is below it.
+    ///
+    /// The shadow follows the outline of the box's fill, stroke, and content.
+    /// See the [rectangle's documentation]($rect.shadow) for the available
+    /// settings.
+    ///
+    /// ```example
+    /// #box(
+    ///   fill: white,
+    ///   inset: 6pt,
+    ///   shadow: (dy: 3pt, blur: 5pt),
+    /// )[Floating]
+    /// ```
+    #[resolve]
+    pub shadow: Option<Shadow>,
 
     /// The contents of the box.
     #[positional]
@@ -174,9 +204,15 @@ impl Packed<BoxElem> {
         let radius = Lazy::new(|| self.radius(styles).unwrap_or_default());
 
         // Clip the contents, if requested.
-        if self.clip(styles) {
-            let size = frame.size() + outset.relative_to(frame.size()).sum_by_axis();
-            frame.clip(clip_rect(size, &radius, &stroke));
+        match self.clip(styles) {
+            Clip::Bool(false) => {}
+            Clip::Bool(true) => {
+                let size = frame.size() + outset.relative_to(frame.size()).sum_by_axis();
+                frame.clip(clip_rect(size, &radius, &stroke));
+            }
+            Clip::Shape(shape) => {
+                clip_to_shape(engine, styles, &mut frame, &shape, self.span())?
+            }
         }
 
         // Add fill and/or stroke.
@@ -184,6 +220,11 @@ impl Packed<BoxElem> {
             frame.fill_and_stroke(fill, &stroke, &outset, &radius, self.span());
         }
 
+        // Add the shadow below everything else.
+        if let Some(shadow) = self.shadow(styles) {
+            frame.shadow(shadow);
+        }
+
         Ok(frame)
     }
 
@@ -278,6 +319,10 @@ pub enum InlineItem {
     Space(Abs, bool),
     /// Layouted inline-level content.
     Frame(Frame),
+    /// Layouted inline-level content that extends beyond the left and right
+    /// edge of the frame by the given amounts. The paragraph makes room for
+    /// the overhang unless the adjacent text is spacing or punctuation.
+    Overhang(Frame, Abs, Abs),
 }
 
 /// A block-level container.
@@ -427,15 +472,36 @@ pub struct BlockElem {
     pub below: VElem,
 
     /// Whether to clip the content inside the block.
-    #[default(false)]
-    pub clip: bool,
+    ///
+    /// Like for [boxes]($box.clip), this can also be a shape to whose
+    /// outline the content is clipped. If the block is split across several
+    /// regions, the shape is laid out in each of them.
+    #[default(Clip::Bool(false))]
+    pub clip: Clip,
+
+    /// A shadow that the block casts onto what is below it. See the
+    /// [box's documentation]($box.shadow) for more details.
+    ///
+    /// If the block is split across several regions, each part casts its own
+    /// shadow.
+    #[resolve]
+    pub shadow: Option<Shadow>,
 
-    /// Whether this block must stick to the following one.
+    /// Whether this block must stick to the following one (keep with next).
     ///
-    /// Use this to prevent page breaks between e.g. a heading and its body.
-    #[internal]
+    /// If the block's successor does not fit into the remaining space of the
+    /// page or column, a sticky block moves to the next page together with
+    /// it. Use this to prevent page breaks between e.g. a heading and its
+    /// body. Headings are sticky by default. To keep a block itself together,
+    /// disable [`breakable`]($block.breakable) instead.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #v(40pt)
+    /// #block(sticky: true)[*Title*]
+    /// #block(breakable: false, lorem(12))
+    /// ```
     #[default(false)]
-    #[parse(None)]
     pub sticky: bool,
 
     /// Whether this block can host footnotes.
@@ -594,6 +660,7 @@ impl Packed<BlockElem> {
 
         // Fetch/compute these outside of the loop.
         let clip = self.clip(styles);
+        let shadow = self.shadow(styles);
         let has_fill_or_stroke = fill.is_some() || stroke.iter().any(Option::is_some);
         let has_inset = !inset.is_zero();
         let is_explicit = matches!(body, None | Some(BlockChild::Content(_)));
@@ -624,9 +691,16 @@ impl Packed<BlockElem> {
             }
 
             // Clip the contents, if requested.
-            if clip {
-                let size = frame.size() + outset.relative_to(frame.size()).sum_by_axis();
-                frame.clip(clip_rect(size, &radius, &stroke));
+            match &clip {
+                Clip::Bool(false) => {}
+                Clip::Bool(true) => {
+                    let size =
+                        frame.size() + outset.relative_to(frame.size()).sum_by_axis();
+                    frame.clip(clip_rect(size, &radius, &stroke));
+                }
+                Clip::Shape(shape) => {
+                    clip_to_shape(engine, styles, frame, shape, self.span())?
+                }
             }
 
             // Add fill and/or stroke.
@@ -639,6 +713,11 @@ impl Packed<BlockElem> {
                     self.span(),
                 );
             }
+
+            // Add the shadow below everything else.
+            if let Some(shadow) = &shadow {
+                frame.shadow(shadow.clone());
+            }
         }
 
         Ok(fragment)
@@ -783,6 +862,46 @@ cast! {
     v: Content => Self::Content(v),
 }
 
+/// How to clip the contents of a container.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Clip {
+    /// Whether to clip to the container's rectangle.
+    Bool(bool),
+    /// Clip to the outline of a shape.
+    Shape(Content),
+}
+
+cast! {
+    Clip,
+    self => match self {
+        Self::Bool(v) => v.into_value(),
+        Self::Shape(v) => v.into_value(),
+    },
+    v: bool => Self::Bool(v),
+    v: Content => Self::Shape(v),
+}
+
+/// Clip a container's frame to the outline of a shape laid out in its area.
+fn clip_to_shape(
+    engine: &mut Engine,
+    styles: StyleChain,
+    frame: &mut Frame,
+    shape: &Content,
+    span: Span,
+) -> SourceResult<()> {
+    let pod = Regions::one(frame.size(), Axes::splat(true));
+    let laid_out = shape.layout(engine, styles, pod)?.into_frame();
+    let Some((path, rule)) = find_outline(&laid_out) else {
+        bail!(
+            span,
+            "failed to find the outline of the clip shape";
+            hint: "try a rect, circle, ellipse, polygon, or path"
+        );
+    };
+    frame.clip_with_rule(path, rule);
+    Ok(())
+}
+
 /// Defines how to size something along an axis.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Sizing {
@@ -6,18 +6,26 @@
 
 use std::fmt::{self, Debug, Formatter};
 
+use ecow::EcoString;
+
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
     elem, Args, Construct, Content, NativeElement, Packed, Resolve, Smart, StyleChain,
+    StyledElem,
 };
-use crate::introspection::TagElem;
+use crate::introspection::{Location, TagElem};
 use crate::layout::{
-    Abs, AlignElem, Axes, BlockElem, ColbreakElem, FixedAlignment, FlushElem, Fr,
-    Fragment, Frame, FrameItem, PlaceElem, Point, Regions, Rel, Size, Spacing, VElem,
+    Abs, AlignElem, Axes, Binding, BlockElem, ColbreakElem, ColumnsElem, Dir,
+    FixedAlignment, FloatSide, FlushElem, Fr, Fragment, Frame, FrameItem, HElem,
+    PageElem, PlaceElem, PlacementScope, Point, Regions, Rel, Size, Spacing, VElem,
+    WrapShape,
+};
+use crate::model::{
+    DropcapInitialElem, FootnoteElem, FootnoteEntry, FootnoteSeparator, ParElem, ParLine,
 };
-use crate::model::{FootnoteElem, FootnoteEntry, ParElem};
 use crate::realize::StyleVec;
+use crate::text::{TextElem, TopEdge, TopEdgeMetric};
 use crate::utils::Numeric;
 
 /// Arranges spacing, paragraphs and block-level elements into a flow.
@@ -39,13 +47,27 @@ impl Construct for FlowElem {
 }
 
 impl Packed<FlowElem> {
-    #[typst_macros::time(name = "flow", span = self.span())]
+    /// Layout the flow.
     pub fn layout(
         &self,
         engine: &mut Engine,
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        self.layout_with_floats(engine, styles, regions)
+            .map(|(fragment, _)| fragment)
+    }
+
+    /// Layout the flow and also return the page-scoped floats it collected
+    /// for the surrounding columns, each together with the index of the
+    /// region it appeared in.
+    #[typst_macros::time(name = "flow", span = self.span())]
+    pub(crate) fn layout_with_floats(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<(Fragment, Vec<(usize, Content)>)> {
         if !regions.size.x.is_finite() && regions.expand.x {
             bail!(self.span(), "cannot expand into infinite width");
         }
@@ -101,6 +123,14 @@ impl Debug for FlowElem {
     }
 }
 
+/// How often a paragraph or block is laid out again to wrap around side
+/// floats.
+const WRAP_ATTEMPTS: usize = 3;
+
+/// How close to a line of the baseline grid something must be to count as
+/// being on it, in grid steps.
+const GRID_TOLERANCE: f64 = 1e-4;
+
 /// Performs flow layout.
 struct FlowLayouter<'a> {
     /// Whether this is the root flow.
@@ -111,6 +141,9 @@ struct FlowLayouter<'a> {
     styles: StyleChain<'a>,
     /// Whether the flow should expand to fill the region.
     expand: Axes<bool>,
+    /// The step and offset of the page's baseline grid if this is the root
+    /// flow.
+    grid: Option<(Abs, Abs)>,
     /// The initial size of `regions.size` that was available before we started
     /// subtracting.
     initial: Size,
@@ -124,8 +157,11 @@ struct FlowLayouter<'a> {
     pending_tags: Vec<Content>,
     /// A queue of floating elements.
     pending_floats: Vec<FlowItem>,
-    /// Whether we have any footnotes in the current region.
-    has_footnotes: bool,
+    /// Page-scoped floats that are handed to the surrounding columns, each
+    /// together with the index of the region it appeared in.
+    page_floats: Vec<(usize, Content)>,
+    /// The footnote listings in the current region, one per series.
+    footnote_listings: Vec<FootnoteListing>,
     /// Footnote configuration.
     footnote_config: FootnoteConfig,
     /// Finished frames for previous regions.
@@ -134,9 +170,30 @@ struct FlowLayouter<'a> {
 
 /// Cached footnote configuration.
 struct FootnoteConfig {
-    separator: Content,
+    separator: FootnoteSeparator,
     clearance: Abs,
     gap: Abs,
+    columns: usize,
+    run_in: bool,
+}
+
+impl FootnoteConfig {
+    /// Whether the entries are laid out together instead of one by one.
+    fn compact(&self) -> bool {
+        self.columns > 1 || self.run_in
+    }
+}
+
+/// The footnotes of one series in a region.
+#[derive(Debug, Clone)]
+struct FootnoteListing {
+    /// The series of the footnotes.
+    series: Option<EcoString>,
+    /// The footnotes in a compact listing. Entries that are laid out one by
+    /// one are directly added to the flow items instead.
+    notes: Vec<Packed<FootnoteElem>>,
+    /// The height of the compact listing.
+    height: Abs,
 }
 
 /// A prepared item in a flow layout.
@@ -168,8 +225,96 @@ enum FlowItem {
         float: bool,
         clearance: Abs,
     },
-    /// A footnote frame (can also be the separator).
-    Footnote(Frame),
+    /// A floating frame at the side of the region that text wraps around.
+    Side { frame: Frame, delta: Axes<Rel<Abs>>, exclusion: Exclusion },
+    /// A footnote frame (can also be the separator) and the index of its
+    /// series' listing.
+    Footnote(Frame, usize),
+}
+
+/// The area next to a side float that the lines of paragraphs avoid.
+#[derive(Debug, Clone)]
+struct Exclusion {
+    /// Whether the float is at the left side of the region.
+    left: bool,
+    /// The vertical position of the float among the in-flow content.
+    top: Abs,
+    /// The height of the float.
+    height: Abs,
+    /// The clearance around the float.
+    clearance: Abs,
+    /// The widths of horizontal slices of the float of equal height, from top
+    /// to bottom.
+    widths: Vec<Abs>,
+}
+
+impl Exclusion {
+    /// Create the exclusion for a float frame with the given wrap shape.
+    fn new(
+        frame: &Frame,
+        left: bool,
+        shape: &WrapShape,
+        clearance: Abs,
+        styles: StyleChain,
+    ) -> Self {
+        let size = frame.size();
+        let widths = match shape {
+            WrapShape::Rect => vec![size.x],
+            WrapShape::Ellipse => {
+                // Each slice is as wide as the ellipse at the slice's point
+                // closest to the vertical center.
+                let n = 24;
+                let radius = size.y / 2.0;
+                (0..n)
+                    .map(|i| {
+                        let y0 = size.y * (i as f64 / n as f64);
+                        let y1 = size.y * ((i + 1) as f64 / n as f64);
+                        let d = if y1 < radius {
+                            radius - y1
+                        } else if y0 > radius {
+                            y0 - radius
+                        } else {
+                            Abs::zero()
+                        };
+                        let t = if radius > Abs::zero() { d / radius } else { 0.0 };
+                        size.x / 2.0 * (1.0 + (1.0 - t * t).max(0.0).sqrt())
+                    })
+                    .collect()
+            }
+            WrapShape::Profile(widths) => widths
+                .iter()
+                .map(|width| width.resolve(styles).relative_to(size.x))
+                .collect(),
+        };
+
+        Self {
+            left,
+            top: Abs::zero(),
+            height: size.y,
+            clearance,
+            widths,
+        }
+    }
+
+    /// How far the area extends into the region between the two vertical
+    /// positions.
+    fn width_within(&self, from: Abs, to: Abs) -> Abs {
+        let from = from - self.clearance - self.top;
+        let to = to + self.clearance - self.top;
+        if to <= Abs::zero() || from >= self.height || self.widths.is_empty() {
+            return Abs::zero();
+        }
+
+        let n = self.widths.len();
+        let slice = self.height / n as f64;
+        let first = (from / slice).floor().max(0.0) as usize;
+        let last = ((to / slice).ceil() as usize).clamp(first + 1, n);
+        self.widths[first.min(n - 1)..last]
+            .iter()
+            .copied()
+            .max()
+            .map_or(Abs::zero(), |width| width + self.clearance)
+    }
 }
 
 impl FlowItem {
@@ -202,21 +347,30 @@ impl<'a> FlowLayouter<'a> {
             regions.expand.y = false;
         }
 
+        let grid = PageElem::baseline_grid_in(styles)
+            .filter(|_| root)
+            .map(|grid| grid.resolve(styles))
+            .filter(|&(step, _)| step > Abs::zero());
+
         Self {
             root,
             regions,
             styles,
             expand,
+            grid,
             initial: regions.size,
             last_was_par: false,
             items: vec![],
             pending_tags: vec![],
             pending_floats: vec![],
-            has_footnotes: false,
+            page_floats: vec![],
+            footnote_listings: vec![],
             footnote_config: FootnoteConfig {
                 separator: FootnoteEntry::separator_in(styles),
                 clearance: FootnoteEntry::clearance_in(styles),
                 gap: FootnoteEntry::gap_in(styles),
+                columns: FootnoteEntry::columns_in(styles).get(),
+                run_in: FootnoteEntry::run_in_in(styles),
             },
             finished: vec![],
         }
@@ -255,41 +409,16 @@ impl<'a> FlowLayouter<'a> {
     ) -> SourceResult<()> {
         let align = AlignElem::alignment_in(styles).resolve(styles);
         let leading = ParElem::leading_in(styles);
-        let consecutive = self.last_was_par;
-        let lines = par
-            .layout(
-                engine,
-                styles,
-                consecutive,
-                self.regions.base(),
-                self.regions.expand.x,
-            )?
-            .into_frames();
+        let locator = engine.locator.clone();
+        let mut lines = self.layout_par_lines(engine, par, styles)?;
 
         // If the first line doesn’t fit in this region, then defer any
-        // previous sticky frame to the next region (if available)
+        // previous sticky frame to the next region (if available) and lay
+        // the paragraph out again there, as side floats may differ.
         if let Some(first) = lines.first() {
-            while !self.regions.size.y.fits(first.height()) && !self.regions.in_last() {
-                let mut sticky = self.items.len();
-                for (i, item) in self.items.iter().enumerate().rev() {
-                    match *item {
-                        FlowItem::Absolute(_, _) => {}
-                        FlowItem::Frame { sticky: true, .. } => sticky = i,
-                        _ => break,
-                    }
-                }
-
-                let carry: Vec<_> = self.items.drain(sticky..).collect();
-                self.finish_region(engine, false)?;
-                let in_last = self.regions.in_last();
-
-                for item in carry {
-                    self.layout_item(engine, item)?;
-                }
-
-                if in_last {
-                    break;
-                }
+            if self.carry_sticky(engine, first.height())? {
+                *engine.locator = locator;
+                lines = self.layout_par_lines(engine, par, styles)?;
             }
         }
 
@@ -309,6 +438,195 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Layout the lines of a paragraph at the current position.
+    fn layout_par_lines(
+        &self,
+        engine: &mut Engine,
+        par: &Packed<ParElem>,
+        styles: StyleChain,
+    ) -> SourceResult<Vec<Frame>> {
+        let leading = ParElem::leading_in(styles);
+        let consecutive = self.last_was_par;
+        let layout = |engine: &mut Engine, insets: &[(Abs, Abs)]| {
+            par.layout(
+                engine,
+                styles,
+                consecutive,
+                self.regions.base(),
+                insets,
+                self.regions.expand.x,
+            )
+            .map(Fragment::into_frames)
+        };
+
+        // Wrap the lines around side floats based on where the lines of the
+        // previous attempt ended up. Shortening lines can change their
+        // heights, so repeat until the insets settle.
+        let locator = engine.locator.clone();
+        let mut insets = vec![];
+        let mut lines = layout(engine, &insets)?;
+        for _ in 0..WRAP_ATTEMPTS {
+            let next = self.wrap_insets(&lines, leading);
+            if next == insets {
+                break;
+            }
+            insets = next;
+            *engine.locator = locator.clone();
+            lines = layout(engine, &insets)?;
+        }
+
+        if ParLine::numbering_in(styles).is_some() {
+            self.number_lines(engine, par, &mut lines, styles)?;
+        }
+
+        // Keep the first and last lines together to prevent orphans and
+        // widows. Positive costs enable prevention, while zero and negative
+        // costs disable it.
+        let costs = TextElem::costs_in(styles);
+        let orphans = if costs.orphan().get() > 0.0 {
+            ParElem::orphans_in(styles).get()
+        } else {
+            1
+        };
+        let widows =
+            if costs.widow().get() > 0.0 { ParElem::widows_in(styles).get() } else { 1 };
+
+        // The lines next to a drop cap are never separated from it.
+        let orphans = par
+            .children()
+            .iter()
+            .find(|(child, _)| !child.is::<TagElem>())
+            .and_then(|(child, _)| child.to_packed::<DropcapInitialElem>())
+            .map_or(orphans, |dropcap| orphans.max(dropcap.lines().get()));
+        let step = self.grid.map(|(step, _)| step);
+        Ok(keep_together(lines, orphans, widows, leading, step))
+    }
+
+    /// Attach line numbers in the margin to the lines of a paragraph.
+    fn number_lines(
+        &self,
+        engine: &mut Engine,
+        par: &Packed<ParElem>,
+        lines: &mut [Frame],
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let margin = ParLine::number_margin_in(styles).resolve(styles);
+        let clearance = ParLine::number_clearance_in(styles);
+        let pod = Regions::one(self.regions.base(), Axes::splat(false));
+        for line in lines.iter_mut().filter(|line| !line.is_empty()) {
+            // Remember the labelled elements in the line so that references
+            // to them can display the line number.
+            let mut targets = vec![];
+            find_labelled(&mut targets, line);
+
+            let number = ParLine::new()
+                .with_numbering(ParLine::numbering_in(styles).clone())
+                .with_numbering_scope(ParLine::numbering_scope_in(styles))
+                .with_targets(targets)
+                .pack()
+                .spanned(par.span())
+                .styled(ParLine::set_numbering(None))
+                .styled(TextElem::set_top_edge(TopEdge::Metric(TopEdgeMetric::Baseline)))
+                .layout(engine, styles, pod)?
+                .into_frame();
+
+            let x = match margin {
+                FixedAlignment::End => line.width() + clearance,
+                _ => -clearance - number.width(),
+            };
+            // The number's top edge is its baseline, so it can be aligned
+            // with the line's baseline directly.
+            line.push_frame(Point::new(x, line.baseline()), number);
+        }
+
+        Ok(())
+    }
+
+    /// Determine how much the given lines of a paragraph at the current
+    /// position must be shortened on the left and right to wrap around side
+    /// floats.
+    fn wrap_insets(&self, lines: &[Frame], leading: Abs) -> Vec<(Abs, Abs)> {
+        let mut insets = vec![];
+        let mut y = self.cursor();
+        for line in lines {
+            insets.push(self.wrap_inset(y, y + line.height()));
+            y += line.height() + leading;
+        }
+
+        // Lines below the side floats are not shortened.
+        while insets
+            .last()
+            .is_some_and(|&(left, right)| left + right <= Abs::zero())
+        {
+            insets.pop();
+        }
+
+        insets
+    }
+
+    /// Determine how far the side floats extend into the region on the left
+    /// and right between the two vertical positions.
+    fn wrap_inset(&self, from: Abs, to: Abs) -> (Abs, Abs) {
+        let mut inset = (Abs::zero(), Abs::zero());
+        for item in &self.items {
+            let FlowItem::Side { exclusion, .. } = item else { continue };
+            let width = exclusion.width_within(from, to);
+            if exclusion.left {
+                inset.0.set_max(width);
+            } else {
+                inset.1.set_max(width);
+            }
+        }
+        inset
+    }
+
+    /// The vertical position of the next in-flow item among the in-flow
+    /// content of the current region.
+    fn cursor(&self) -> Abs {
+        self.items
+            .iter()
+            .map(|item| match item {
+                FlowItem::Absolute(v, _) => *v,
+                FlowItem::Frame { frame, .. } => frame.height(),
+                _ => Abs::zero(),
+            })
+            .sum()
+    }
+
+    /// How far to move a paragraph line down so that its baseline lies on the
+    /// baseline grid.
+    fn grid_shift(&self, line: &Frame) -> Abs {
+        let Some((step, offset)) = self.grid else { return Abs::zero() };
+
+        // Account for the floats at the top of the region, which are placed
+        // above the in-flow content.
+        let floats: Abs = self
+            .items
+            .iter()
+            .map(|item| match item {
+                FlowItem::Placed {
+                    frame,
+                    float: true,
+                    y_align: Smart::Custom(Some(FixedAlignment::Start)),
+                    ..
+                } => frame.height(),
+                _ => Abs::zero(),
+            })
+            .sum();
+
+        let baseline = floats + self.cursor() + line.baseline();
+        let steps = ((baseline - offset) / step - GRID_TOLERANCE).ceil().max(0.0);
+        (offset + step * steps - baseline).max(Abs::zero())
+    }
+
+    /// How much space to add after something of the given height so that it
+    /// takes up whole steps of the baseline grid.
+    fn grid_padding(&self, height: Abs) -> Abs {
+        let Some((step, _)) = self.grid else { return Abs::zero() };
+        let steps = (height / step - GRID_TOLERANCE).ceil().max(0.0);
+        (step * steps - height).max(Abs::zero())
+    }
+
     /// Layout into multiple regions.
     fn layout_block(
         &mut self,
@@ -330,7 +648,19 @@ impl<'a> FlowLayouter<'a> {
 
         // Layout the block itself.
         let sticky = block.sticky(styles);
-        let fragment = block.layout(engine, styles, self.regions)?;
+        let locator = engine.locator.clone();
+        let (mut fragment, mut inset) =
+            self.layout_block_wrapped(engine, block, styles)?;
+
+        // If the block's first frame doesn't fit in this region, then defer
+        // any previous sticky frame to the next region together with the
+        // block and lay the block out again there.
+        if let Some(first) = fragment.as_slice().first() {
+            if self.carry_sticky(engine, first.height())? {
+                *engine.locator = locator;
+                (fragment, inset) = self.layout_block_wrapped(engine, block, styles)?;
+            }
+        }
 
         // How to align the block.
         let align = AlignElem::alignment_in(styles).resolve(styles);
@@ -348,10 +678,31 @@ impl<'a> FlowLayouter<'a> {
 
             self.drain_tag(&mut frame);
             frame.post_process(styles);
+
+            // Place the first frame next to the side floats.
+            if i == 0
+                && inset.0 + inset.1 > Abs::zero()
+                && self.regions.size.x.is_finite()
+            {
+                let width = self.regions.size.x;
+                let x =
+                    inset.0 + align.x.position(width - inset.0 - inset.1 - frame.width());
+                let mut outer = Frame::soft(Size::new(width, frame.height()));
+                outer.push_frame(Point::with_x(x), frame);
+                frame = outer;
+            }
+
+            let height = frame.height();
             self.layout_item(
                 engine,
                 FlowItem::Frame { frame, align, sticky, movable: false },
             )?;
+
+            // Keep the content after the block on the baseline grid.
+            let padding = self.grid_padding(height);
+            if padding > Abs::zero() {
+                self.layout_item(engine, FlowItem::Absolute(padding, true))?;
+            }
         }
 
         self.try_handle_footnotes(engine, notes)?;
@@ -363,6 +714,83 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Layout a block at the current position, narrowed to fit between the
+    /// side floats next to it.
+    ///
+    /// Returns the block's fragment and how much it was narrowed on the left
+    /// and right.
+    fn layout_block_wrapped(
+        &self,
+        engine: &mut Engine,
+        block: &Packed<BlockElem>,
+        styles: StyleChain,
+    ) -> SourceResult<(Fragment, (Abs, Abs))> {
+        // Narrowing the block can make it taller, so repeat until it fits
+        // next to the floats it ends up beside.
+        let locator = engine.locator.clone();
+        let mut inset = (Abs::zero(), Abs::zero());
+        let mut fragment = block.layout(engine, styles, self.regions)?;
+        for _ in 0..WRAP_ATTEMPTS {
+            let Some(first) = fragment.as_slice().first() else { break };
+            let top = self.cursor();
+            let next = self.wrap_inset(top, top + first.height());
+            if next == inset {
+                break;
+            }
+
+            inset = next;
+            let mut regions = self.regions;
+            regions.size.x -= inset.0 + inset.1;
+            *engine.locator = locator.clone();
+            fragment = block.layout(engine, styles, regions)?;
+        }
+
+        Ok((fragment, inset))
+    }
+
+    /// Move sticky frames at the end of the current region to the next region
+    /// if an item of the given height does not fit after them.
+    ///
+    /// Returns whether the current region was finished.
+    fn carry_sticky(&mut self, engine: &mut Engine, height: Abs) -> SourceResult<bool> {
+        let mut carried = false;
+        while !self.regions.size.y.fits(height) && !self.regions.in_last() {
+            let mut sticky = self.items.len();
+            for (i, item) in self.items.iter().enumerate().rev() {
+                match *item {
+                    FlowItem::Absolute(_, _) => {}
+                    FlowItem::Frame { sticky: true, .. } => sticky = i,
+                    _ => break,
+                }
+            }
+
+            // Don't leave an empty region behind if everything in it is
+            // sticky.
+            if sticky < self.items.len()
+                && !self.items[..sticky]
+                    .iter()
+                    .any(|item| matches!(item, FlowItem::Frame { .. }))
+            {
+                break;
+            }
+
+            let carry: Vec<_> = self.items.drain(sticky..).collect();
+            self.finish_region(engine, false)?;
+            carried = true;
+            let in_last = self.regions.in_last();
+
+            for item in carry {
+                self.layout_item(engine, item)?;
+            }
+
+            if in_last {
+                break;
+            }
+        }
+
+        Ok(carried)
+    }
+
     /// Layout a placed element.
     fn layout_placed(
         &mut self,
@@ -371,6 +799,20 @@ impl<'a> FlowLayouter<'a> {
         styles: StyleChain,
     ) -> SourceResult<()> {
         let float = placed.float(styles);
+
+        // Page-scoped floats span all columns and are thus placed by the
+        // surrounding columns, which receive them together with the index of
+        // the region they appear in.
+        if self.root
+            && float
+            && placed.scope(styles) == PlacementScope::Page
+            && ColumnsElem::collect_floats_in(styles)
+        {
+            let marker = StyledElem::new(placed.clone().pack(), styles.to_map());
+            self.page_floats.push((self.finished.len(), marker.pack()));
+            return Ok(());
+        }
+
         let clearance = placed.clearance(styles);
         let alignment = placed.alignment(styles);
         let delta = Axes::new(placed.dx(styles), placed.dy(styles)).resolve(styles);
@@ -380,10 +822,38 @@ impl<'a> FlowLayouter<'a> {
         let y_align = alignment.map(|align| align.y().map(|y| y.resolve(styles)));
         let mut frame = placed.layout(engine, styles, self.regions.base())?.into_frame();
         frame.post_process(styles);
-        let item = FlowItem::Placed { frame, x_align, y_align, delta, float, clearance };
+        let item = match placed.side(styles) {
+            Some(side) => {
+                let left = self.resolve_side(side, styles) == FixedAlignment::Start;
+                let exclusion =
+                    Exclusion::new(&frame, left, &placed.wrap(styles), clearance, styles);
+                FlowItem::Side { frame, delta, exclusion }
+            }
+            None => FlowItem::Placed { frame, x_align, y_align, delta, float, clearance },
+        };
         self.layout_item(engine, item)
     }
 
+    /// Resolve the side of a side float to the left (start) or right (end)
+    /// of the region.
+    fn resolve_side(&self, side: FloatSide, styles: StyleChain) -> FixedAlignment {
+        let binding = PageElem::binding_in(styles).unwrap_or_else(|| {
+            match TextElem::dir_in(styles) {
+                Dir::LTR => Binding::Left,
+                _ => Binding::Right,
+            }
+        });
+
+        // The regions of the root flow are the pages (or their columns).
+        let mut number = PageElem::first_number_in(styles);
+        if self.root {
+            let columns = PageElem::columns_in(styles).get();
+            number = number.saturating_add(self.finished.len() / columns);
+        }
+
+        side.resolve(binding, number, styles)
+    }
+
     /// Attach currently pending metadata to the frame.
     fn drain_tag(&mut self, frame: &mut Frame) {
         if !self.pending_tags.is_empty() && !frame.is_empty() {
@@ -416,8 +886,20 @@ impl<'a> FlowLayouter<'a> {
             FlowItem::Fractional(..) => {}
             FlowItem::Frame { ref frame, movable, .. } => {
                 let height = frame.height();
-                while !self.regions.size.y.fits(height) && !self.regions.in_last() {
+                let mut shift =
+                    if movable { self.grid_shift(frame) } else { Abs::zero() };
+                while !self.regions.size.y.fits(height + shift) && !self.regions.in_last()
+                {
                     self.finish_region(engine, false)?;
+                    if movable {
+                        shift = self.grid_shift(frame);
+                    }
+                }
+
+                // Move paragraph lines onto the baseline grid.
+                if shift > Abs::zero() {
+                    self.items.push(FlowItem::Absolute(shift, false));
+                    self.regions.size.y -= shift;
                 }
 
                 let in_last = self.regions.in_last();
@@ -475,6 +957,10 @@ impl<'a> FlowLayouter<'a> {
                 frame.size_mut().y += clearance;
                 if *y_align == Smart::Custom(Some(FixedAlignment::End)) {
                     frame.translate(Point::with_y(clearance));
+                } else {
+                    // Keep the content below the float on the baseline grid.
+                    let padding = self.grid_padding(frame.height());
+                    frame.size_mut().y += padding;
                 }
 
                 self.regions.size.y -= frame.height();
@@ -486,7 +972,27 @@ impl<'a> FlowLayouter<'a> {
                     self.try_handle_footnotes(engine, notes)?;
                 }
             }
-            FlowItem::Footnote(_) => {}
+            FlowItem::Side { ref frame, ref mut exclusion, .. } => {
+                // If there is a queued float in front or if the float doesn't
+                // fit, queue it for the next region.
+                if !self.pending_floats.is_empty()
+                    || (!self.regions.size.y.fits(frame.height())
+                        && !self.regions.in_last())
+                {
+                    self.pending_floats.push(item);
+                    return Ok(());
+                }
+
+                exclusion.top = self.cursor();
+
+                // Find footnotes in the frame.
+                if self.root {
+                    let mut notes = vec![];
+                    find_footnotes(&mut notes, frame);
+                    self.try_handle_footnotes(engine, notes)?;
+                }
+            }
+            FlowItem::Footnote(..) => {}
         }
 
         self.items.push(item);
@@ -518,12 +1024,23 @@ impl<'a> FlowLayouter<'a> {
             self.items.pop();
         }
 
+        // Lay out the compact footnote listings.
+        let listings = std::mem::take(&mut self.footnote_listings);
+        let has_footnotes = !listings.is_empty();
+        for (i, listing) in listings.iter().enumerate() {
+            if !listing.notes.is_empty() {
+                let frame = self.layout_footnote_listing(engine, &listing.notes)?;
+                self.items.push(FlowItem::Footnote(frame, i));
+            }
+        }
+
         // Determine the used size.
         let mut fr = Fr::zero();
         let mut used = Size::zero();
         let mut footnote_height = Abs::zero();
         let mut float_top_height = Abs::zero();
         let mut float_bottom_height = Abs::zero();
+        let mut side_height = Abs::zero();
         let mut first_footnote = true;
         for item in &self.items {
             match item {
@@ -543,7 +1060,11 @@ impl<'a> FlowLayouter<'a> {
                     }
                     _ => {}
                 },
-                FlowItem::Footnote(frame) => {
+                FlowItem::Side { frame, exclusion, .. } => {
+                    side_height.set_max(exclusion.top + frame.height());
+                    used.x.set_max(frame.width());
+                }
+                FlowItem::Footnote(frame, _) => {
                     footnote_height += frame.height();
                     if !first_footnote {
                         footnote_height += self.footnote_config.gap;
@@ -553,13 +1074,14 @@ impl<'a> FlowLayouter<'a> {
                 }
             }
         }
+        used.y.set_max(side_height);
         used.y += footnote_height + float_top_height + float_bottom_height;
 
         // Determine the size of the flow in this region depending on whether
         // the region expands. Also account for fractional spacing and
         // footnotes.
         let mut size = self.expand.select(self.initial, used).min(self.initial);
-        if (fr.get() > 0.0 || self.has_footnotes) && self.initial.y.is_finite() {
+        if (fr.get() > 0.0 || has_footnotes) && self.initial.y.is_finite() {
             size.y = self.initial.y;
         }
 
@@ -569,6 +1091,7 @@ impl<'a> FlowLayouter<'a> {
         let mut offset = float_top_height;
         let mut float_bottom_offset = Abs::zero();
         let mut footnote_offset = Abs::zero();
+        let mut footnotes = vec![];
 
         // Place all frames.
         for item in self.items.drain(..) {
@@ -620,14 +1143,31 @@ impl<'a> FlowLayouter<'a> {
 
                     output.push_frame(pos, frame);
                 }
-                FlowItem::Footnote(frame) => {
-                    let y = size.y - footnote_height + footnote_offset;
-                    footnote_offset += frame.height() + self.footnote_config.gap;
-                    output.push_frame(Point::with_y(y), frame);
+                FlowItem::Side { frame, delta, exclusion } => {
+                    let x =
+                        if exclusion.left { Abs::zero() } else { size.x - frame.width() };
+                    let y = float_top_height
+                        + exclusion.top
+                        + ruler.position(size.y - used.y);
+                    let pos = Point::new(x, y)
+                        + delta.zip_map(size, Rel::relative_to).to_point();
+                    output.push_frame(pos, frame);
+                }
+                FlowItem::Footnote(frame, listing) => {
+                    footnotes.push((listing, frame));
                 }
             }
         }
 
+        // Place the footnotes, listing by listing. The main series comes
+        // first and the others follow in the order they appeared in.
+        footnotes.sort_by_key(|&(i, _)| (listings[i].series.is_some(), i));
+        for (_, frame) in footnotes {
+            let y = size.y - footnote_height + footnote_offset;
+            footnote_offset += frame.height() + self.footnote_config.gap;
+            output.push_frame(Point::with_y(y), frame);
+        }
+
         if force && !self.pending_tags.is_empty() {
             let pos = Point::with_y(offset);
             output.push_multiple(
@@ -639,7 +1179,6 @@ impl<'a> FlowLayouter<'a> {
         self.finished.push(output);
         self.regions.next();
         self.initial = self.regions.size;
-        self.has_footnotes = false;
 
         // Try to place floats into the next region.
         for item in std::mem::take(&mut self.pending_floats) {
@@ -661,8 +1200,12 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
-    /// Finish layouting and return the resulting fragment.
-    fn finish(mut self, engine: &mut Engine) -> SourceResult<Fragment> {
+    /// Finish layouting and return the resulting fragment together with the
+    /// collected page-scoped floats.
+    fn finish(
+        mut self,
+        engine: &mut Engine,
+    ) -> SourceResult<(Fragment, Vec<(usize, Content)>)> {
         if self.expand.y {
             while !self.regions.backlog.is_empty() {
                 self.finish_region(engine, true)?;
@@ -674,7 +1217,7 @@ impl<'a> FlowLayouter<'a> {
             self.finish_region(engine, true)?;
         }
 
-        Ok(Fragment::frames(self.finished))
+        Ok((Fragment::frames(self.finished), self.page_floats))
     }
 }
 
@@ -717,7 +1260,7 @@ impl FlowLayouter<'_> {
         let prev_notes_len = notes.len();
         let prev_items_len = self.items.len();
         let prev_size = self.regions.size;
-        let prev_has_footnotes = self.has_footnotes;
+        let prev_listings = self.footnote_listings.clone();
         let prev_locator = engine.locator.clone();
 
         // Process footnotes one at a time.
@@ -728,41 +1271,73 @@ impl FlowLayouter<'_> {
                 continue;
             }
 
-            if !self.has_footnotes {
-                self.layout_footnote_separator(engine)?;
-            }
+            let series = notes[k].series(StyleChain::default());
+            let listing = self.footnote_listing(engine, series.clone())?;
+            let prev = notes.len();
 
-            self.regions.size.y -= self.footnote_config.gap;
-            let frames = FootnoteEntry::new(notes[k].clone())
-                .pack()
-                .layout(engine, self.styles, self.regions.with_root(false))?
-                .into_frames();
-
-            // If the entries didn't fit, abort (to keep footnote and entry
-            // together).
-            if !force
-                && (k == 0 || movable)
-                && frames.first().is_some_and(Frame::is_empty)
-            {
-                // Undo everything.
-                notes.truncate(prev_notes_len);
-                self.items.truncate(prev_items_len);
-                self.regions.size = prev_size;
-                self.has_footnotes = prev_has_footnotes;
-                *engine.locator = prev_locator;
-                return Ok(false);
-            }
+            if self.footnote_config.compact() {
+                // Lay out the whole listing again with the new entry.
+                let mut listed = self.footnote_listings[listing].notes.clone();
+                listed.push(notes[k].clone());
+                let frame = self.layout_footnote_listing(engine, &listed)?;
+                let mut delta = frame.height() - self.footnote_listings[listing].height;
+                if listed.len() == 1 {
+                    delta += self.footnote_config.gap;
+                }
+
+                // If the listing doesn't fit, abort (to keep footnote and
+                // entry together).
+                if !force && (k == 0 || movable) && !self.regions.size.y.fits(delta) {
+                    notes.truncate(prev_notes_len);
+                    self.items.truncate(prev_items_len);
+                    self.regions.size = prev_size;
+                    self.footnote_listings = prev_listings;
+                    *engine.locator = prev_locator;
+                    return Ok(false);
+                }
 
-            let prev = notes.len();
-            for (i, frame) in frames.into_iter().enumerate() {
                 find_footnotes(notes, &frame);
-                if i > 0 {
-                    self.finish_region(engine, false)?;
-                    self.layout_footnote_separator(engine)?;
-                    self.regions.size.y -= self.footnote_config.gap;
+                self.regions.size.y -= delta;
+                self.footnote_listings[listing].notes = listed;
+                self.footnote_listings[listing].height = frame.height();
+            } else {
+                self.regions.size.y -= self.footnote_config.gap;
+                let leading = FootnoteEntry::leading().wrap();
+                let frames = FootnoteEntry::new(notes[k].clone())
+                    .pack()
+                    .layout(
+                        engine,
+                        self.styles.chain(&leading),
+                        self.regions.with_root(false),
+                    )?
+                    .into_frames();
+
+                // If the entries didn't fit, abort (to keep footnote and entry
+                // together).
+                if !force
+                    && (k == 0 || movable)
+                    && frames.first().is_some_and(Frame::is_empty)
+                {
+                    // Undo everything.
+                    notes.truncate(prev_notes_len);
+                    self.items.truncate(prev_items_len);
+                    self.regions.size = prev_size;
+                    self.footnote_listings = prev_listings;
+                    *engine.locator = prev_locator;
+                    return Ok(false);
+                }
+
+                let mut listing = listing;
+                for (i, frame) in frames.into_iter().enumerate() {
+                    find_footnotes(notes, &frame);
+                    if i > 0 {
+                        self.finish_region(engine, false)?;
+                        listing = self.footnote_listing(engine, series.clone())?;
+                        self.regions.size.y -= self.footnote_config.gap;
+                    }
+                    self.regions.size.y -= frame.height();
+                    self.items.push(FlowItem::Footnote(frame, listing));
                 }
-                self.regions.size.y -= frame.height();
-                self.items.push(FlowItem::Footnote(frame));
             }
 
             k += 1;
@@ -778,22 +1353,138 @@ impl FlowLayouter<'_> {
         Ok(true)
     }
 
-    /// Layout and save the footnote separator, typically a line.
-    fn layout_footnote_separator(&mut self, engine: &mut Engine) -> SourceResult<()> {
+    /// Find the listing of the footnote series in the current region or
+    /// start a new one with its separator.
+    fn footnote_listing(
+        &mut self,
+        engine: &mut Engine,
+        series: Option<EcoString>,
+    ) -> SourceResult<usize> {
+        if let Some(i) = self
+            .footnote_listings
+            .iter()
+            .position(|listing| listing.series == series)
+        {
+            return Ok(i);
+        }
+
+        self.layout_footnote_separator(engine, series.as_ref())?;
+        self.footnote_listings.push(FootnoteListing {
+            series,
+            notes: vec![],
+            height: Abs::zero(),
+        });
+
+        Ok(self.footnote_listings.len() - 1)
+    }
+
+    /// Layout and save the footnote separator of a series, typically a line.
+    fn layout_footnote_separator(
+        &mut self,
+        engine: &mut Engine,
+        series: Option<&EcoString>,
+    ) -> SourceResult<()> {
         let expand = Axes::new(self.regions.expand.x, false);
         let pod = Regions::one(self.regions.base(), expand);
-        let separator = &self.footnote_config.separator;
+        let separator =
+            self.footnote_config.separator.resolve(engine, self.styles, series)?;
 
         let mut frame = separator.layout(engine, self.styles, pod)?.into_frame();
         frame.size_mut().y += self.footnote_config.clearance;
         frame.translate(Point::with_y(self.footnote_config.clearance));
 
-        self.has_footnotes = true;
         self.regions.size.y -= frame.height();
-        self.items.push(FlowItem::Footnote(frame));
+        self.items
+            .push(FlowItem::Footnote(frame, self.footnote_listings.len()));
 
         Ok(())
     }
+
+    /// Layout the entries of footnotes together, either in columns or as a
+    /// run-in paragraph.
+    fn layout_footnote_listing(
+        &self,
+        engine: &mut Engine,
+        notes: &[Packed<FootnoteElem>],
+    ) -> SourceResult<Frame> {
+        let width = self.regions.size.x;
+        let gap = self.footnote_config.gap;
+        let leading = FootnoteEntry::leading().wrap();
+        let styles = self.styles.chain(&leading);
+        let entries = notes.iter().map(|note| FootnoteEntry::new(note.clone()).pack());
+
+        if self.footnote_config.run_in {
+            let mut body = vec![];
+            for (i, entry) in entries.enumerate() {
+                if i > 0 {
+                    body.push(HElem::new(gap.into()).pack());
+                }
+                body.push(entry);
+            }
+
+            let pod = Regions::one(Size::new(width, Abs::inf()), Axes::new(true, false));
+            let frame = Content::sequence(body).layout(engine, styles, pod)?;
+            return Ok(frame.into_frame());
+        }
+
+        // Lay out the entries in columns of about equal height.
+        let columns = self.footnote_config.columns;
+        let gutter = ColumnsElem::gutter_in(self.styles).relative_to(width);
+        let column = (width - gutter * (columns - 1) as f64) / columns as f64;
+        let pod = Regions::one(Size::new(column, Abs::inf()), Axes::new(true, false));
+        let frames = entries
+            .map(|entry| Ok(entry.layout(engine, styles, pod)?.into_frame()))
+            .collect::<SourceResult<Vec<_>>>()?;
+
+        let heights: Vec<_> = frames.iter().map(Frame::height).collect();
+        let height = balanced_height(&heights, gap, columns);
+        let dir = TextElem::dir_in(self.styles);
+        let mut output = Frame::soft(Size::new(width, height));
+        let (mut x, mut y) = (Abs::zero(), Abs::zero());
+        for frame in frames {
+            let size = frame.size();
+            if y > Abs::zero() && y + size.y > height {
+                x += column + gutter;
+                y = Abs::zero();
+            }
+
+            let pos = if dir == Dir::RTL { width - x - column } else { x };
+            output.push_frame(Point::new(pos, y), frame);
+            y += size.y + gap;
+        }
+
+        Ok(output)
+    }
+}
+
+/// The smallest height with which items of the given heights fit into the
+/// given number of columns in order.
+fn balanced_height(heights: &[Abs], gap: Abs, columns: usize) -> Abs {
+    let fits = |limit: Abs| {
+        let (mut used, mut y) = (1, Abs::zero());
+        for &height in heights {
+            if y > Abs::zero() && y + height > limit {
+                used += 1;
+                y = Abs::zero();
+            }
+            y += height + gap;
+        }
+        used <= columns
+    };
+
+    // The height is that of a run of consecutive items. Sum them up the same
+    // way as the check above to compare exactly.
+    let mut candidates = vec![];
+    for i in 0..heights.len() {
+        let mut y = Abs::zero();
+        for &height in &heights[i..] {
+            candidates.push(y + height);
+            y += height + gap;
+        }
+    }
+
+    candidates.sort();
+    candidates.into_iter().find(|&limit| fits(limit)).unwrap_or_default()
 }
 
 /// Finds all footnotes in the frame.
@@ -807,9 +1498,70 @@ fn find_footnotes(notes: &mut Vec<Packed<FootnoteElem>>, frame: &Frame) {
                 let Some(footnote) = elem.to_packed::<FootnoteElem>() else {
                     continue;
                 };
+
+                // Footnotes in the margin are placed by the page.
+                if footnote.margin(StyleChain::default()) {
+                    continue;
+                }
+
                 notes.push(footnote.clone());
             }
             _ => {}
         }
     }
 }
+
+/// Finds the locations of all labelled elements in the frame.
+fn find_labelled(targets: &mut Vec<Location>, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => find_labelled(targets, &group.frame),
+            FrameItem::Tag(elem) if elem.label().is_some() => {
+                targets.extend(elem.location());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Merge the first `orphans` and the last `widows` lines of a paragraph into
+/// single frames so that they are never separated by a region break.
+///
+/// The flow only moves the first baseline of each frame onto the baseline
+/// grid, so with a grid `step`, the gaps between the merged lines are widened
+/// to put their baselines a whole number of steps apart.
+fn keep_together(
+    lines: Vec<Frame>,
+    orphans: usize,
+    widows: usize,
+    leading: Abs,
+    step: Option<Abs>,
+) -> Vec<Frame> {
+    let len = lines.len();
+    let tail = len.saturating_sub(widows);
+    let mut frames: Vec<Frame> = Vec::with_capacity(len);
+    let mut baseline = Abs::zero();
+    for (i, line) in lines.into_iter().enumerate() {
+        match frames.last_mut() {
+            Some(prev)
+                if (i < orphans || i > tail) && !prev.is_empty() && !line.is_empty() =>
+            {
+                let mut offset = prev.height() + leading;
+                if let Some(step) = step {
+                    let distance = offset + line.baseline() - baseline;
+                    let steps = (distance / step - GRID_TOLERANCE).ceil().max(1.0);
+                    offset += step * steps - distance;
+                }
+                baseline = offset + line.baseline();
+                let total = offset + line.height();
+                prev.push_frame(Point::with_y(offset), line);
+                prev.size_mut().y = total;
+            }
+            _ => {
+                baseline = line.baseline();
+                frames.push(line);
+            }
+        }
+    }
+    frames
+}
@@ -8,7 +8,7 @@ use smallvec::SmallVec;
 
 use crate::foundations::{cast, dict, Content, Dict, StyleChain, Value};
 use crate::layout::{
-    Abs, Axes, Corners, FixedAlignment, HideElem, Length, Point, Rel, Sides, Size,
+    Abs, Axes, Corners, FixedAlignment, HideElem, Length, Point, Ratio, Rel, Sides, Size,
     Transform,
 };
 use crate::model::{Destination, LinkElem};
@@ -16,7 +16,8 @@ use crate::syntax::Span;
 use crate::text::TextItem;
 use crate::utils::{LazyHash, Numeric};
 use crate::visualize::{
-    ellipse, styled_rect, Color, FixedStroke, Geometry, Image, Paint, Path, Shape,
+    ellipse, styled_rect, BlendMode, Color, FillRule, FixedShadow, FixedStroke, Geometry,
+    Image, Mask, Paint, Path, Shape,
 };
 
 /// A finished layout with items at fixed positions.
@@ -395,8 +396,41 @@ impl Frame {
     /// rectangular frame. In the case of a frame with rounded corner,
     /// this should be a path that matches the frame's outline.
     pub fn clip(&mut self, clip_path: Path) {
+        self.clip_with_rule(clip_path, FillRule::NonZero);
+    }
+
+    /// Clip the contents of a frame to the inside of a clip path, as
+    /// determined by the given fill rule.
+    pub fn clip_with_rule(&mut self, clip_path: Path, clip_rule: FillRule) {
+        if !self.is_empty() {
+            self.group(|g| {
+                g.clip_path = Some(clip_path);
+                g.clip_rule = clip_rule;
+            });
+        }
+    }
+
+    /// Composite the contents of the frame as a whole onto what is below it,
+    /// with the given opacity, blend mode, and mask.
+    pub fn composite(
+        &mut self,
+        opacity: Ratio,
+        blend_mode: BlendMode,
+        mask: Option<Mask>,
+    ) {
         if !self.is_empty() {
-            self.group(|g| g.clip_path = Some(clip_path));
+            self.group(|g| {
+                g.opacity = opacity;
+                g.blend_mode = blend_mode;
+                g.mask = mask;
+            });
+        }
+    }
+
+    /// Draw a shadow of the frame's contents below them.
+    pub fn shadow(&mut self, shadow: FixedShadow) {
+        if !self.is_empty() {
+            self.group(|g| g.shadow = Some(shadow));
         }
     }
 
@@ -538,7 +572,7 @@ impl Debug for FrameItem {
     }
 }
 
-/// A subframe with optional transformation and clipping.
+/// A subframe with optional transformation, clipping, and compositing.
 #[derive(Clone, Hash)]
 pub struct GroupItem {
     /// The group's frame.
@@ -547,6 +581,16 @@ pub struct GroupItem {
     pub transform: Transform,
     /// Whether the frame should be a clipping boundary.
     pub clip_path: Option<Path>,
+    /// The rule that determines the inside of the clip path.
+    pub clip_rule: FillRule,
+    /// The opacity with which the group is composited.
+    pub opacity: Ratio,
+    /// How the group is blended with what is below it.
+    pub blend_mode: BlendMode,
+    /// A mask that determines which parts of the group are visible.
+    pub mask: Option<Mask>,
+    /// A shadow that the group casts onto what is below it.
+    pub shadow: Option<FixedShadow>,
 }
 
 impl GroupItem {
@@ -556,8 +600,21 @@ impl GroupItem {
             frame,
             transform: Transform::identity(),
             clip_path: None,
+            clip_rule: FillRule::NonZero,
+            opacity: Ratio::one(),
+            blend_mode: BlendMode::Normal,
+            mask: None,
+            shadow: None,
         }
     }
+
+    /// Whether the group must be rendered in isolation and then composited
+    /// as a whole, instead of drawing its items directly.
+    pub fn is_composited(&self) -> bool {
+        self.opacity != Ratio::one()
+            || self.blend_mode != BlendMode::Normal
+            || self.mask.is_some()
+    }
 }
 
 impl Debug for GroupItem {
@@ -36,6 +36,23 @@ static LINEBREAK_DATA: Lazy<CodePointMapData<LineBreak>> = Lazy::new(|| {
     icu_properties::maps::load_line_break(&deser_provider).unwrap()
 });
 
+/// Whether overhanging content like a wide ruby annotation may extend over the
+/// given character, that is, whether it is spacing or punctuation.
+pub(super) fn is_overhangable(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            LINEBREAK_DATA.as_borrowed().get(c),
+            LineBreak::Space
+                | LineBreak::ZWSpace
+                | LineBreak::OpenPunctuation
+                | LineBreak::ClosePunctuation
+                | LineBreak::CloseParenthesis
+                | LineBreak::Exclamation
+                | LineBreak::InfixNumeric
+                | LineBreak::Quotation
+        )
+}
+
 /// A line break opportunity.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub(super) enum Breakpoint {
@@ -113,24 +130,18 @@ pub(super) fn breakpoints<'a>(
                 break 'hyphenate;
             }
 
-            // Extract a hyphenatable "word".
-            let word = &text[last..point].trim_end_matches(|c: char| !c.is_alphabetic());
+            // Extract a hyphenatable "word" without leading and trailing
+            // punctuation like in `"(word),`.
+            let segment = &text[last..point];
+            let trimmed = segment.trim_start_matches(|c: char| !c.is_alphabetic());
+            let start = last + (segment.len() - trimmed.len());
+            let word = trimmed.trim_end_matches(|c: char| !c.is_alphabetic());
             if word.is_empty() {
                 break 'hyphenate;
             }
 
-            let end = last + word.len();
-            let mut offset = last;
-
-            // Determine the language to hyphenate this word in.
-            let Some(lang) = lang_at(p, last) else { break 'hyphenate };
-
-            for syllable in hypher::hyphenate(word, lang) {
-                // Don't hyphenate after the final syllable.
-                offset += syllable.len();
-                if offset == end {
-                    continue;
-                }
+            for split in hyphenation_points(p, start, word) {
+                let offset = start + split;
 
                 // Filter out hyphenation opportunities where hyphenation was
                 // actually disabled.
@@ -140,7 +151,7 @@ pub(super) fn breakpoints<'a>(
 
                 // Filter out forbidden hyphenation opportunities.
                 if matches!(
-                    syllable.chars().next_back().map(|c| lb.get(c)),
+                    word[..split].chars().next_back().map(|c| lb.get(c)),
                     Some(LineBreak::Glue | LineBreak::WordJoiner | LineBreak::ZWJ)
                 ) {
                     continue;
@@ -226,6 +237,47 @@ fn hyphenate_at(p: &Preparation, offset: usize) -> bool {
         .unwrap_or(false)
 }
 
+/// The offsets within the word starting at the given offset at which it may be
+/// hyphenated.
+fn hyphenation_points(p: &Preparation, offset: usize, word: &str) -> Vec<usize> {
+    let styles = p
+        .find(offset)
+        .and_then(|item| item.text())
+        .map(|shaped| shaped.styles);
+
+    // Explicit exceptions take precedence over patterns and limits.
+    if let Some(points) =
+        styles.and_then(|styles| TextElem::hyphenation_exceptions_in(styles).get(word))
+    {
+        return points;
+    }
+
+    // Determine the syllables with custom or built-in patterns. The built-in
+    // ones already respect the language's limits.
+    let (mut points, defaults) = match styles.and_then(TextElem::hyphenation_patterns_in)
+    {
+        Some(patterns) => (patterns.hyphenate(word), (2, 3)),
+        None => {
+            let Some(lang) = lang_at(p, offset) else { return vec![] };
+            let mut points: Vec<usize> = hypher::hyphenate(word, lang)
+                .scan(0, |end, syllable| {
+                    *end += syllable.len();
+                    Some(*end)
+                })
+                .collect();
+
+            // Don't hyphenate after the final syllable.
+            points.pop();
+            (points, (1, 1))
+        }
+    };
+
+    let limits = styles.map(TextElem::hyphenation_limits_in).unwrap_or_default();
+    let len = word.chars().count();
+    points.retain(|&point| limits.allows(word[..point].chars().count(), len, defaults));
+    points
+}
+
 /// The text language at the given offset.
 fn lang_at(p: &Preparation, offset: usize) -> Option<hypher::Lang> {
     let lang = p.lang.or_else(|| {
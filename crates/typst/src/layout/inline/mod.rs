@@ -5,7 +5,7 @@ use comemo::{Tracked, TrackedMut};
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
 
-use self::linebreak::{breakpoints, Breakpoint};
+use self::linebreak::{breakpoints, is_overhangable, Breakpoint};
 use self::shaping::{
     cjk_punct_style, is_of_cj_script, shape, ShapedGlyph, ShapedText, BEGIN_PUNCT_PAT,
     END_PUNCT_PAT,
@@ -17,16 +17,17 @@ use crate::foundations::{Packed, Resolve, Smart, StyleChain};
 use crate::introspection::{Introspector, Locator, TagElem};
 use crate::layout::{
     Abs, AlignElem, BoxElem, Dir, Em, FixedAlignment, Fr, Fragment, Frame, FrameItem,
-    HElem, InlineElem, InlineItem, Point, Size, Sizing, Spacing,
+    HElem, InlineElem, InlineItem, PageElem, Point, Size, Sizing, Spacing,
 };
-use crate::model::{Linebreaks, ParElem};
+use crate::model::{DropcapInitialElem, Linebreaks, ParElem, Protrusion};
 use crate::realize::StyleVec;
 use crate::syntax::Span;
 use crate::text::{
-    Costs, Lang, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem,
-    TextElem,
+    check_variations, Costs, Lang, LinebreakElem, SmartQuoteElem, SmartQuoter,
+    SmartQuotes, SpaceElem, TextElem,
 };
 use crate::utils::Numeric;
+use crate::visualize::FixedShadow;
 use crate::World;
 
 /// Layouts content inline.
@@ -36,6 +37,7 @@ pub(crate) fn layout_inline(
     styles: StyleChain,
     consecutive: bool,
     region: Size,
+    insets: &[(Abs, Abs)],
     expand: bool,
 ) -> SourceResult<Fragment> {
     #[comemo::memoize]
@@ -50,6 +52,7 @@ pub(crate) fn layout_inline(
         styles: StyleChain,
         consecutive: bool,
         region: Size,
+        insets: &[(Abs, Abs)],
         expand: bool,
     ) -> SourceResult<Fragment> {
         let mut locator = Locator::chained(locator);
@@ -62,13 +65,14 @@ pub(crate) fn layout_inline(
         };
 
         // Collect all text into one string for BiDi analysis.
-        let (text, segments, spans) =
+        let (text, segments, spans, dropcap) =
             collect(children, &mut engine, &styles, region, consecutive)?;
 
         // Perform BiDi analysis and then prepare paragraph layout by building a
         // representation on which we can do line breaking without layouting
         // each and every line from scratch.
-        let p = prepare(&mut engine, children, &text, segments, spans, styles)?;
+        let shape = LineShape { dropcap, insets };
+        let p = prepare(&mut engine, children, &text, segments, spans, shape, styles)?;
 
         // Break the paragraph into lines.
         let lines = linebreak(&engine, &p, region.x - p.hang);
@@ -88,6 +92,7 @@ pub(crate) fn layout_inline(
         styles,
         consecutive,
         region,
+        insets,
         expand,
     )?;
 
@@ -137,19 +142,75 @@ struct Preparation<'a> {
     justify: bool,
     /// The paragraph's hanging indent.
     hang: Abs,
+    /// The spacing between the paragraph's lines.
+    leading: Abs,
+    /// How the first lines are shortened.
+    shape: LineShape<'a>,
     /// Whether to add spacing between CJK and Latin characters.
     cjk_latin_spacing: bool,
     /// Whether font fallback is enabled for this paragraph.
     fallback: bool,
-    /// The leading of the paragraph.
-    leading: Abs,
     /// How to determine line breaks.
     linebreaks: Smart<Linebreaks>,
+    /// How far characters protrude into the margins.
+    protrusion: Smart<Protrusion>,
+    /// How much glyphs may be stretched or shrunk for justification.
+    expansion: f64,
+    /// How much of the extra space of justified lines is filled with kashidas.
+    kashida: f64,
     /// The text size.
     size: Abs,
 }
 
+/// How the first lines of a paragraph are shortened.
+struct LineShape<'a> {
+    /// The drop cap at the start of the paragraph.
+    dropcap: Option<Dropcap>,
+    /// How much the first lines are shortened on the left and right, e.g. to
+    /// wrap around side floats.
+    insets: &'a [(Abs, Abs)],
+}
+
+/// A drop cap spanning the first lines of a paragraph.
+struct Dropcap {
+    /// The laid out drop cap. Its baseline is aligned with the baseline of
+    /// the first line.
+    frame: Frame,
+    /// How many lines the drop cap spans.
+    lines: usize,
+    /// How much the spanned lines are indented.
+    indent: Abs,
+}
+
 impl<'a> Preparation<'a> {
+    /// How much the line with the given index is shortened on the left and
+    /// right to make room for the drop cap and side floats.
+    fn insets(&self, index: usize) -> (Abs, Abs) {
+        let (mut left, mut right) =
+            self.shape.insets.get(index).copied().unwrap_or_default();
+        if let Some(dropcap) =
+            self.shape.dropcap.as_ref().filter(|dropcap| index < dropcap.lines)
+        {
+            if self.rtl() {
+                right += dropcap.indent;
+            } else {
+                left += dropcap.indent;
+            }
+        }
+        (left, right)
+    }
+
+    /// How much the line with the given index is shortened in total.
+    fn indent(&self, index: usize) -> Abs {
+        let (left, right) = self.insets(index);
+        left + right
+    }
+
+    /// Whether the paragraph's base direction is right-to-left.
+    fn rtl(&self) -> bool {
+        self.bidi.paragraphs.first().is_some_and(|para| para.level.is_rtl())
+    }
+
     /// Find the item that contains the given `text_offset`.
     fn find(&self, text_offset: usize) -> Option<&Item<'a>> {
         let mut cursor = 0;
@@ -405,6 +466,12 @@ impl<'a> Line<'a> {
         count
     }
 
+    /// How many positions are in the text where kashidas can be inserted
+    /// when justifying the line.
+    fn kashidas(&self) -> usize {
+        self.items().filter_map(Item::text).map(|s| s.kashidas()).sum()
+    }
+
     /// How much can the line stretch
     fn stretchability(&self) -> Abs {
         self.items().filter_map(Item::text).map(|s| s.stretchability()).sum()
@@ -415,6 +482,18 @@ impl<'a> Line<'a> {
         self.items().filter_map(Item::text).map(|s| s.shrinkability()).sum()
     }
 
+    /// How much can the line stretch or shrink through font expansion.
+    fn expandability(&self, expansion: f64) -> Abs {
+        if expansion == 0.0 {
+            return Abs::zero();
+        }
+
+        self.items()
+            .filter_map(Item::text)
+            .map(|s| s.expandability(expansion))
+            .sum()
+    }
+
     /// The sum of fractions in the line.
     fn fr(&self) -> Fr {
         self.items()
@@ -434,13 +513,21 @@ fn collect<'a>(
     styles: &'a StyleChain<'a>,
     region: Size,
     consecutive: bool,
-) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper)> {
+) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper, Option<Dropcap>)> {
     let mut collector = Collector::new(2 + children.len());
     let mut iter = children.chain(styles).peekable();
+    let mut dropcap = None;
+
+    // A paragraph with a drop cap has no first line indent.
+    let starts_with_dropcap = children
+        .chain(styles)
+        .find(|(child, _)| !child.is::<TagElem>())
+        .is_some_and(|(child, _)| child.is::<DropcapInitialElem>());
 
     let first_line_indent = ParElem::first_line_indent_in(*styles);
     if !first_line_indent.is_zero()
         && consecutive
+        && !starts_with_dropcap
         && AlignElem::alignment_in(*styles).resolve(*styles).x
             == TextElem::dir_in(*styles).start().into()
     {
@@ -462,6 +549,15 @@ fn collect<'a>(
         if child.is::<SpaceElem>() {
             collector.push_text(" ", styles);
         } else if let Some(elem) = child.to_packed::<TextElem>() {
+            if TextElem::dir_in(styles) == Dir::TTB && !PageElem::vertical_in(styles) {
+                bail!(
+                    elem.span(), "vertical text must be set for whole pages";
+                    hint: "use `set text(dir: ttb)` before any content of the page"
+                );
+            }
+
+            check_variations(engine, elem.span(), styles)?;
+
             collector.build_text(styles, |full| {
                 let dir = TextElem::dir_in(styles);
                 if dir != outer_dir {
@@ -533,6 +629,25 @@ fn collect<'a>(
                 collector.push_text(if double { "\"" } else { "'" }, styles);
             }
         } else if let Some(elem) = child.to_packed::<InlineElem>() {
+            // The characters around the element, for overhanging items.
+            let prev = collector.full.chars().next_back();
+            let next = iter.peek().and_then(|(child, _)| {
+                if let Some(elem) = child.to_packed::<TextElem>() {
+                    elem.text().chars().next()
+                } else if child.is::<SpaceElem>()
+                    || child.is::<HElem>()
+                    || child.is::<LinebreakElem>()
+                {
+                    Some(' ')
+                } else {
+                    None
+                }
+            });
+            let (left_char, right_char) = match TextElem::dir_in(styles).is_positive() {
+                true => (prev, next),
+                false => (next, prev),
+            };
+
             collector.push_item(Item::Skip(LTR_ISOLATE));
 
             for item in elem.layout(engine, styles, region)? {
@@ -543,6 +658,17 @@ fn collect<'a>(
                     InlineItem::Frame(frame) => {
                         collector.push_item(Item::Frame(frame, styles));
                     }
+                    InlineItem::Overhang(frame, left, right) => {
+                        // Make room for the overhang unless it extends over
+                        // spacing or punctuation.
+                        if !left.is_zero() && !left_char.is_some_and(is_overhangable) {
+                            collector.push_item(Item::Absolute(left, false));
+                        }
+                        collector.push_item(Item::Frame(frame, styles));
+                        if !right.is_zero() && !right_char.is_some_and(is_overhangable) {
+                            collector.push_item(Item::Absolute(right, false));
+                        }
+                    }
                 }
             }
 
@@ -556,6 +682,10 @@ fn collect<'a>(
             }
         } else if let Some(elem) = child.to_packed::<TagElem>() {
             collector.push_item(Item::Tag(elem));
+        } else if let Some(elem) = child.to_packed::<DropcapInitialElem>() {
+            let frame = elem.layout(engine, styles, region)?;
+            let indent = frame.width() + elem.gap().resolve(styles);
+            dropcap = Some(Dropcap { frame, lines: elem.lines().get(), indent });
         } else {
             bail!(child.span(), "unexpected paragraph child");
         };
@@ -564,7 +694,7 @@ fn collect<'a>(
         collector.spans.push(len, child.span());
     }
 
-    Ok((collector.full, collector.segments, collector.spans))
+    Ok((collector.full, collector.segments, collector.spans, dropcap))
 }
 
 /// Collects segments.
@@ -635,12 +765,13 @@ fn prepare<'a>(
     text: &'a str,
     segments: Vec<Segment<'a>>,
     spans: SpanMapper,
+    shape: LineShape<'a>,
     styles: StyleChain<'a>,
 ) -> SourceResult<Preparation<'a>> {
     let bidi = BidiInfo::new(
         text,
         match TextElem::dir_in(styles) {
-            Dir::LTR => Some(BidiLevel::ltr()),
+            Dir::LTR | Dir::TTB => Some(BidiLevel::ltr()),
             Dir::RTL => Some(BidiLevel::rtl()),
             _ => None,
         },
@@ -677,10 +808,14 @@ fn prepare<'a>(
         align: AlignElem::alignment_in(styles).resolve(styles).x,
         justify: ParElem::justify_in(styles),
         hang: ParElem::hanging_indent_in(styles),
+        leading: ParElem::leading_in(styles),
+        shape,
         cjk_latin_spacing,
         fallback: TextElem::fallback_in(styles),
-        leading: ParElem::leading_in(styles),
         linebreaks: ParElem::linebreaks_in(styles),
+        protrusion: ParElem::protrusion_in(styles),
+        expansion: ParElem::expansion_in(styles).get(),
+        kashida: ParElem::kashida_in(styles).get().clamp(0.0, 1.0),
         size: TextElem::size_in(styles),
     })
 }
@@ -841,7 +976,7 @@ fn linebreak_simple<'a>(
         // If the line doesn't fit anymore, we push the last fitting attempt
         // into the stack and rebuild the line from the attempt's end. The
         // resulting line cannot be broken up further.
-        if !width.fits(attempt.width) {
+        if !(width - p.indent(lines.len())).fits(attempt.width) {
             if let Some((last_attempt, last_end)) = last.take() {
                 lines.push(last_attempt);
                 start = last_end;
@@ -852,7 +987,9 @@ fn linebreak_simple<'a>(
         // Finish the current line if there is a mandatory line break (i.e.
         // due to "\n") or if the line doesn't fit horizontally already
         // since then no shorter line will be possible.
-        if breakpoint == Breakpoint::Mandatory || !width.fits(attempt.width) {
+        if breakpoint == Breakpoint::Mandatory
+            || !(width - p.indent(lines.len())).fits(attempt.width)
+        {
             lines.push(attempt);
             start = end;
             last = None;
@@ -898,6 +1035,7 @@ fn linebreak_optimized<'a>(
         pred: usize,
         total: Cost,
         line: Line<'a>,
+        lines: usize,
     }
 
     // Cost parameters.
@@ -916,6 +1054,7 @@ fn linebreak_optimized<'a>(
         pred: 0,
         total: 0.0,
         line: line(engine, p, 0..0, Breakpoint::Mandatory, false),
+        lines: 0,
     }];
 
     let em = p.size;
@@ -934,14 +1073,17 @@ fn linebreak_optimized<'a>(
             let attempt = line(engine, p, start..end, breakpoint, prepend_hyphen);
 
             // Determine how much the line's spaces would need to be stretched
-            // to make it the desired width.
-            let delta = width - attempt.width;
+            // to make it the desired width. Kashidas take over their share of
+            // the stretching.
+            let mut delta = width - p.indent(pred.lines) - attempt.width;
+            let kashidas = attempt.kashidas();
+            delta -= kashida_width(p, kashidas, delta) * kashidas as f64;
             // Determine how much stretch are permitted.
             let adjust = if delta >= Abs::zero() {
                 attempt.stretchability()
             } else {
                 attempt.shrinkability()
-            };
+            } + attempt.expandability(p.expansion);
             // Ideally, the ratio should between -1.0 and 1.0, but sometimes a value above 1.0
             // is possible, in which case the line is underfull.
             let mut ratio = delta / adjust;
@@ -1018,7 +1160,12 @@ fn linebreak_optimized<'a>(
 
             // If this attempt is better than what we had before, take it!
             if best.as_ref().map_or(true, |best| best.total >= total) {
-                best = Some(Entry { pred: i, total, line: attempt });
+                best = Some(Entry {
+                    pred: i,
+                    total,
+                    line: attempt,
+                    lines: pred.lines + 1,
+                });
             }
         }
 
@@ -1270,9 +1417,15 @@ fn finalize(
     let width = if !region.x.is_finite()
         || (!expand && lines.iter().all(|line| line.fr().is_zero()))
     {
-        region
-            .x
-            .min(p.hang + lines.iter().map(|line| line.width).max().unwrap_or_default())
+        region.x.min(
+            p.hang
+                + lines
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| p.indent(i) + line.width)
+                    .max()
+                    .unwrap_or_default(),
+        )
     } else {
         region.x
     };
@@ -1280,49 +1433,40 @@ fn finalize(
     // Stack the lines into one frame per region.
     let mut frames: Vec<Frame> = lines
         .iter()
-        .map(|line| commit(engine, p, line, width, region.y, shrink))
+        .enumerate()
+        .map(|(i, line)| commit(engine, p, line, i, width, region.y, shrink))
         .collect::<SourceResult<_>>()?;
 
-    // Positive ratios enable prevention, while zero and negative ratios disable it.
-    if p.costs.orphan().get() > 0.0 {
-        // Prevent orphans.
-        if frames.len() >= 2 && !frames[1].is_empty() {
-            let second = frames.remove(1);
-            let first = &mut frames[0];
-            merge(first, second, p.leading);
-        }
-    }
-    if p.costs.widow().get() > 0.0 {
-        // Prevent widows.
-        let len = frames.len();
-        if len >= 2 && !frames[len - 2].is_empty() {
-            let second = frames.pop().unwrap();
-            let first = frames.last_mut().unwrap();
-            merge(first, second, p.leading);
+    // Make room for the part of the drop cap that extends below the lines it
+    // spans, e.g. when the paragraph is shorter than the drop cap.
+    if let Some(dropcap) = &p.shape.dropcap {
+        let n = dropcap.lines.min(frames.len());
+        if let Some(first) = frames.first() {
+            let bottom =
+                first.baseline() - dropcap.frame.baseline() + dropcap.frame.height();
+            let covered = frames[..n].iter().map(Frame::height).sum::<Abs>()
+                + p.leading * (n - 1) as f64;
+            if bottom > covered {
+                frames[n - 1].size_mut().y += bottom - covered;
+            }
         }
     }
 
     Ok(Fragment::frames(frames))
 }
 
-/// Merge two line frames
-fn merge(first: &mut Frame, second: Frame, leading: Abs) {
-    let offset = first.height() + leading;
-    let total = offset + second.height();
-    first.push_frame(Point::with_y(offset), second);
-    first.size_mut().y = total;
-}
-
 /// Commit to a line and build its frame.
 fn commit(
     engine: &mut Engine,
     p: &Preparation,
     line: &Line,
+    index: usize,
     width: Abs,
     full: Abs,
     shrink: bool,
 ) -> SourceResult<Frame> {
-    let mut remaining = width - line.width - p.hang;
+    let (left, right) = p.insets(index);
+    let mut remaining = width - line.width - p.hang - left - right;
     let mut offset = Abs::zero();
 
     // Reorder the line from logical to visual order.
@@ -1331,14 +1475,15 @@ fn commit(
         offset += p.hang;
     }
 
-    // Handle hanging punctuation to the left.
+    // Make room for the drop cap and side floats.
+    offset += left;
+
+    // Handle hanging punctuation to the left. The leftmost glyph is at the
+    // start of the line in left-to-right text and at its end otherwise.
     if let Some(Item::Text(text)) = reordered.first() {
         if let Some(glyph) = text.glyphs.first() {
-            if !text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+            if reordered.len() > 1 || text.glyphs.len() > 1 {
+                let amount = protrusion(p, text, glyph, text.dir.is_positive());
                 offset -= amount;
                 remaining += amount;
             }
@@ -1348,11 +1493,8 @@ fn commit(
     // Handle hanging punctuation to the right.
     if let Some(Item::Text(text)) = reordered.last() {
         if let Some(glyph) = text.glyphs.last() {
-            if text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+            if reordered.len() > 1 || text.glyphs.len() > 1 {
+                let amount = protrusion(p, text, glyph, !text.dir.is_positive());
                 remaining += amount;
             }
         }
@@ -1366,14 +1508,23 @@ fn commit(
     let fr = line.fr();
     let mut justification_ratio = 0.0;
     let mut extra_justification = Abs::zero();
+    let mut kashida = Abs::zero();
 
-    let shrinkability = line.shrinkability();
-    let stretch = line.stretchability();
+    // With font expansion, the glyphs stretch and shrink along with the
+    // spaces.
+    let expandability = line.expandability(p.expansion);
+    let shrinkability = line.shrinkability() + expandability;
+    let stretch = line.stretchability() + expandability;
     if remaining < Abs::zero() && shrinkability > Abs::zero() && shrink {
         // Attempt to reduce the length of the line, using shrinkability.
         justification_ratio = (remaining / shrinkability).max(-1.0);
         remaining = (remaining + shrinkability).min(Abs::zero());
     } else if line.justify && fr.is_zero() {
+        // Fill the preferred share of the extra space with kashidas.
+        let kashidas = line.kashidas();
+        kashida = kashida_width(p, kashidas, remaining);
+        remaining -= kashida * kashidas as f64;
+
         // Attempt to increase the length of the line, using stretchability.
         if stretch > Abs::zero() {
             justification_ratio = (remaining / stretch).min(1.0);
@@ -1391,14 +1542,15 @@ fn commit(
     let mut top = Abs::zero();
     let mut bottom = Abs::zero();
 
-    // Build the frames and determine the height and baseline.
+    // Build the frames and determine the height and baseline. Text frames
+    // carry the shadow of their text, which is applied below.
     let mut frames = vec![];
     for item in reordered {
-        let mut push = |offset: &mut Abs, frame: Frame| {
+        let mut push = |offset: &mut Abs, frame: Frame, shadow: Option<FixedShadow>| {
             let width = frame.width();
             top.set_max(frame.baseline());
             bottom.set_max(frame.size().y - frame.baseline());
-            frames.push((*offset, frame));
+            frames.push((*offset, frame, shadow));
             *offset += width;
         };
 
@@ -1413,27 +1565,32 @@ fn commit(
                     let mut frame = elem.layout(engine, *styles, region)?;
                     frame.post_process(*styles);
                     frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
-                    push(&mut offset, frame);
+                    push(&mut offset, frame, None);
                 } else {
                     offset += amount;
                 }
             }
             Item::Text(shaped) => {
-                let mut frame =
-                    shaped.build(engine, justification_ratio, extra_justification);
+                let mut frame = shaped.build(
+                    engine,
+                    justification_ratio,
+                    extra_justification,
+                    1.0 + justification_ratio * p.expansion,
+                    kashida,
+                );
                 frame.post_process(shaped.styles);
-                push(&mut offset, frame);
+                push(&mut offset, frame, TextElem::shadow_in(shaped.styles));
             }
             Item::Frame(frame, styles) => {
                 let mut frame = frame.clone();
                 frame.post_process(*styles);
                 frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
-                push(&mut offset, frame);
+                push(&mut offset, frame, None);
             }
             Item::Tag(tag) => {
                 let mut frame = Frame::soft(Size::zero());
                 frame.push(Point::zero(), FrameItem::Tag(tag.elem.clone()));
-                frames.push((offset, frame));
+                frames.push((offset, frame, None));
             }
             Item::Skip(_) => {}
         }
@@ -1448,11 +1605,49 @@ fn commit(
     let mut output = Frame::soft(size);
     output.set_baseline(top);
 
-    // Construct the line's frame.
-    for (offset, frame) in frames {
+    // Construct the line's frame. All text runs with the same shadow cast it
+    // together and the shadows are drawn first. Otherwise, the shadow of a
+    // run would cover the glyphs of the runs before it.
+    let mut shadowed: Vec<(FixedShadow, Frame)> = vec![];
+    let mut rest = vec![];
+    for (offset, frame, shadow) in frames {
         let x = offset + p.align.position(remaining);
         let y = top - frame.baseline();
-        output.push_frame(Point::new(x, y), frame);
+        let pos = Point::new(x, y);
+        match shadow {
+            Some(shadow) => {
+                let i = match shadowed.iter().position(|(s, _)| *s == shadow) {
+                    Some(i) => i,
+                    None => {
+                        shadowed.push((shadow, Frame::soft(size)));
+                        shadowed.len() - 1
+                    }
+                };
+                shadowed[i].1.push_frame(pos, frame);
+            }
+            None => rest.push((pos, frame)),
+        }
+    }
+
+    for (shadow, mut frame) in shadowed {
+        frame.shadow(shadow);
+        output.push_frame(Point::zero(), frame);
+    }
+
+    for (pos, frame) in rest {
+        output.push_frame(pos, frame);
+    }
+
+    // Attach the drop cap to the first line. It extends below the line into
+    // the lines next to it.
+    if let Some(dropcap) = p.shape.dropcap.as_ref().filter(|_| index == 0) {
+        let x = if p.rtl() {
+            width - right + dropcap.indent - dropcap.frame.width()
+        } else {
+            left - dropcap.indent
+        };
+        let y = top - dropcap.frame.baseline();
+        output.push_frame(Point::new(x, y), dropcap.frame.clone());
     }
 
     Ok(output)
@@ -1498,11 +1693,50 @@ fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
     (reordered, starts_rtl)
 }
 
-/// How much a character should hang into the end margin.
+/// How wide each kashida is in a line with `count` kashida positions that
+/// needs to grow by `extra`.
+///
+/// The kashidas fill their preferred share of the extra space, but none is
+/// wider than half an em. The rest of the space is left to the spaces.
+fn kashida_width(p: &Preparation, count: usize, extra: Abs) -> Abs {
+    if count == 0 || extra <= Abs::zero() || p.kashida <= 0.0 {
+        return Abs::zero();
+    }
+
+    (extra * p.kashida / count as f64).min(Em::new(0.5).at(p.size))
+}
+
+/// How far a glyph at the start or end of a line protrudes into the margin.
+fn protrusion(
+    p: &Preparation,
+    text: &ShapedText,
+    glyph: &ShapedGlyph,
+    start: bool,
+) -> Abs {
+    if !TextElem::overhang_in(text.styles) {
+        return Abs::zero();
+    }
+
+    let custom = match &p.protrusion {
+        Smart::Custom(protrusion) => protrusion.get(glyph.c),
+        Smart::Auto => None,
+    };
+
+    let ratio = match custom {
+        Some(amount) if start => amount.start.get(),
+        Some(amount) => amount.end.get(),
+        None if start => 0.0,
+        None => overhang(glyph.c, text.lang),
+    };
+
+    ratio * glyph.x_advance.at(text.size)
+}
+
+/// How much a character should hang into the end margin by default.
 ///
 /// For more discussion, see:
 /// <https://recoveringphysicist.com/21/>
-fn overhang(c: char) -> f64 {
+fn overhang(c: char, lang: Lang) -> f64 {
     match c {
         // Dashes.
         '–' | '—' => 0.2,
@@ -1515,6 +1749,15 @@ fn overhang(c: char) -> f64 {
         // Arabic
         '\u{60C}' | '\u{6D4}' => 0.4,
 
+        // Full-width punctuation, whose ink only fills the half of the glyph
+        // next to the preceding character. It's only used like that in Chinese
+        // and Japanese.
+        '、' | '。' | '，' | '．' | '」' | '』' | '）' | '】' | '〉' | '》'
+            if matches!(lang, Lang::CHINESE | Lang::JAPANESE) =>
+        {
+            0.5
+        }
+
         _ => 0.0,
     }
 }
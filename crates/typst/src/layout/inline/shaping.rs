@@ -13,14 +13,16 @@ use unicode_script::{Script, UnicodeScript};
 use super::SpanMapper;
 use crate::engine::Engine;
 use crate::foundations::StyleChain;
-use crate::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Size};
+use crate::layout::{
+    Abs, Angle, Dir, Em, Frame, FrameItem, Point, Ratio, Size, Transform,
+};
 use crate::syntax::Span;
 use crate::text::{
-    decorate, families, features, variant, Font, FontVariant, Glyph, Lang, Region,
-    TextElem, TextItem,
+    decorate, families, features, select_fallback_font, select_font, variant, variations,
+    Font, FontStyle, FontVariant, Glyph, Lang, Region, TextElem, TextItem,
 };
 use crate::utils::SliceExt;
-use crate::World;
+use crate::visualize::{FixedStroke, LineJoin};
 
 /// The result of shaping text.
 ///
@@ -87,6 +89,44 @@ pub(super) struct ShapedGlyph {
     pub is_justifiable: bool,
     /// The script of the glyph.
     pub script: Script,
+    /// The glyph's size relative to the text size. This is less than one for
+    /// synthesized small capitals.
+    pub scale: f64,
+    /// How the glyph is placed if it is set upright in vertical text.
+    pub upright: Option<Upright>,
+    /// Whether a kashida may be inserted visually to the left of this glyph
+    /// when justifying right-to-left text.
+    pub kashida: bool,
+}
+
+/// The placement of a glyph that is set upright in vertical text.
+///
+/// In vertical text, lines are laid out horizontally and the page is turned
+/// afterwards. Upright glyphs are thus turned the other way around, one by
+/// one, and centered in their slot on the line. The glyph's `x_advance` is
+/// the size of that slot.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(super) struct Upright {
+    /// The glyph's horizontal advance.
+    pub width: Em,
+    /// The offset of the glyph's origin from the center of its slot across
+    /// the line.
+    pub dx: Em,
+    /// The distance from the glyph's position on the line to the center of
+    /// its slot.
+    pub center: Em,
+}
+
+/// How a run of vertical text is oriented.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Orientation {
+    /// Rotated sideways with the line, like Latin or Mongolian text.
+    Sideways,
+    /// Each glyph stands upright, like Chinese or Japanese text.
+    Upright,
+    /// The glyphs are set horizontally in a single upright slot
+    /// (tate-chu-yoko), like short numbers in Japanese text.
+    Combined,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -211,12 +251,17 @@ impl<'a> ShapedText<'a> {
     /// Build the shaped text's frame.
     ///
     /// The `justification` defines how much extra advance width each
-    /// [justifiable glyph](ShapedGlyph::is_justifiable) will get.
+    /// [justifiable glyph](ShapedGlyph::is_justifiable) will get. The
+    /// `expansion` is the factor by which glyphs other than spaces are
+    /// horizontally scaled and `kashida` is the width of the kashida inserted
+    /// at each [kashida position](ShapedGlyph::kashida).
     pub fn build(
         &self,
         engine: &Engine,
         justification_ratio: f64,
         extra_justification: Abs,
+        expansion: f64,
+        kashida: Abs,
     ) -> Frame {
         let (top, bottom) = self.measure(engine);
         let size = Size::new(self.width, top + bottom);
@@ -231,8 +276,10 @@ impl<'a> ShapedText<'a> {
         let stroke = TextElem::stroke_in(self.styles);
         let span_offset = TextElem::span_offset_in(self.styles);
 
-        for ((font, y_offset), group) in
-            self.glyphs.as_ref().group_by_key(|g| (g.font.clone(), g.y_offset))
+        for ((font, y_offset, scale, upright), group) in self
+            .glyphs
+            .as_ref()
+            .group_by_key(|g| (g.font.clone(), g.y_offset, g.scale, g.upright.is_some()))
         {
             let mut range = group[0].range.clone();
             for glyph in group {
@@ -240,86 +287,146 @@ impl<'a> ShapedText<'a> {
                 range.end = range.end.max(glyph.range.end);
             }
 
+            // Upright glyphs in vertical text are not expanded.
+            let stretch = if upright { 1.0 } else { expansion };
             let pos = Point::new(offset, top + shift - y_offset.at(self.size));
-            let glyphs: Vec<Glyph> = group
-                .iter()
-                .map(|shaped: &ShapedGlyph| {
-                    let adjustability_left = if justification_ratio < 0.0 {
-                        shaped.shrinkability().0
-                    } else {
-                        shaped.stretchability().0
-                    };
-                    let adjustability_right = if justification_ratio < 0.0 {
-                        shaped.shrinkability().1
-                    } else {
-                        shaped.stretchability().1
-                    };
-
-                    let justification_left = adjustability_left * justification_ratio;
-                    let mut justification_right =
-                        adjustability_right * justification_ratio;
-                    if shaped.is_justifiable() {
-                        justification_right +=
-                            Em::from_length(extra_justification, self.size)
+            let tatweel = font
+                .ttf()
+                .glyph_index(TATWEEL)
+                .and_then(|id| Some((id.0, font.advance(id.0)?)))
+                .filter(|&(_, advance)| advance > Em::zero());
+            let mut glyphs: Vec<Glyph> = Vec::with_capacity(group.len());
+            for shaped in group {
+                let adjustability_left = if justification_ratio < 0.0 {
+                    shaped.shrinkability().0
+                } else {
+                    shaped.stretchability().0
+                };
+                let adjustability_right = if justification_ratio < 0.0 {
+                    shaped.shrinkability().1
+                } else {
+                    shaped.stretchability().1
+                };
+
+                let justification_left = adjustability_left * justification_ratio;
+                let mut justification_right = adjustability_right * justification_ratio;
+                if shaped.is_justifiable() {
+                    justification_right += Em::from_length(extra_justification, self.size)
+                }
+
+                let expand = if shaped.is_space() { 1.0 } else { stretch };
+                frame.size_mut().x += justification_left.at(self.size)
+                    + justification_right.at(self.size)
+                    + (shaped.x_advance * (expand - 1.0)).at(self.size);
+
+                // We may not be able to reach the offset completely if
+                // it exceeds u16, but better to have a roughly correct
+                // span offset than nothing.
+                let mut span = shaped.span;
+                span.1 = span.1.saturating_add(span_offset.saturating_as());
+                let range = (shaped.range.start - range.start).saturating_as()
+                    ..(shaped.range.end - range.start).saturating_as();
+
+                // Insert a kashida made of as many overlapping tatweels as
+                // needed to fill its width.
+                if let Some((id, advance)) =
+                    tatweel.filter(|_| shaped.kashida && kashida > Abs::zero())
+                {
+                    let width = Em::from_length(kashida, self.size) / scale;
+                    let count = (width / advance).ceil().max(1.0);
+                    for _ in 0..count as usize {
+                        glyphs.push(Glyph {
+                            id,
+                            x_advance: width / (count * stretch),
+                            x_offset: Em::zero(),
+                            range: range.clone(),
+                            span,
+                        });
                     }
+                    frame.size_mut().x += kashida;
+                }
 
-                    frame.size_mut().x += justification_left.at(self.size)
-                        + justification_right.at(self.size);
-
-                    // We may not be able to reach the offset completely if
-                    // it exceeds u16, but better to have a roughly correct
-                    // span offset than nothing.
-                    let mut span = shaped.span;
-                    span.1 = span.1.saturating_add(span_offset.saturating_as());
-
-                    // |<---- a Glyph ---->|
-                    //  -->|ShapedGlyph|<--
-                    // +---+-----------+---+
-                    // |   |  *********|   |
-                    // |   |  *        |   |
-                    // |   |  *    ****|   |
-                    // |   |  *       *|   |
-                    // |   |  *********|   |
-                    // +---+--+--------+---+
-                    //   A   B     C     D
-                    // Note A, B, D could be positive, zero, or negative.
-                    // A: justification_left
-                    // B: ShapedGlyph's x_offset
-                    //    (though a small part of the glyph may go inside B)
-                    // B+C: ShapedGlyph's x_advance
-                    // D: justification_right
-                    // A+B: Glyph's x_offset
-                    // A+B+C+D: Glyph's x_advance
-                    Glyph {
-                        id: shaped.glyph_id,
-                        x_advance: shaped.x_advance
-                            + justification_left
-                            + justification_right,
-                        x_offset: shaped.x_offset + justification_left,
-                        range: (shaped.range.start - range.start).saturating_as()
-                            ..(shaped.range.end - range.start).saturating_as(),
-                        span,
+                // |<---- a Glyph ---->|
+                //  -->|ShapedGlyph|<--
+                // +---+-----------+---+
+                // |   |  *********|   |
+                // |   |  *        |   |
+                // |   |  *    ****|   |
+                // |   |  *       *|   |
+                // |   |  *********|   |
+                // +---+--+--------+---+
+                //   A   B     C     D
+                // Note A, B, D could be positive, zero, or negative.
+                // A: justification_left
+                // B: ShapedGlyph's x_offset
+                //    (though a small part of the glyph may go inside B)
+                // B+C: ShapedGlyph's x_advance
+                // D: justification_right
+                // A+B: Glyph's x_offset
+                // A+B+C+D: Glyph's x_advance
+                // The glyph's metrics are relative to the text size, but
+                // scaled glyphs are emitted in an item of their own size.
+                // Expanded items are stretched as a whole, so spaces are
+                // narrowed in the item to compensate.
+                glyphs.push(Glyph {
+                    id: shaped.glyph_id,
+                    x_advance: (shaped.x_advance * expand
+                        + justification_left
+                        + justification_right)
+                        / (scale * stretch),
+                    x_offset: (shaped.x_offset * expand + justification_left)
+                        / (scale * stretch),
+                    range,
+                    span,
+                });
+            }
+
+            let size = self.size * scale;
+            let mut stroke = stroke.clone().map(|s| s.unwrap_or_default());
+            if let Some(thickness) = synthesized_bold(&font, self.variant) {
+                // Embolden the glyphs by stroking them with their fill.
+                let thickness = thickness.at(size);
+                stroke = Some(match stroke {
+                    Some(stroke) => {
+                        FixedStroke { thickness: stroke.thickness + thickness, ..stroke }
                     }
-                })
-                .collect();
+                    None => FixedStroke {
+                        join: LineJoin::Round,
+                        ..FixedStroke::from_pair(fill.clone(), thickness)
+                    },
+                });
+            }
 
+            // Oblique and expanded glyphs are transformed as a whole.
+            let oblique = synthesized_oblique(&font, self.variant);
+            let skew = Ratio::new(if oblique { -OBLIQUE_SKEW } else { 0.0 });
+            let ts = Transform {
+                sx: Ratio::new(stretch),
+                kx: skew,
+                ..Transform::identity()
+            };
             let item = TextItem {
                 font,
-                size: self.size,
+                size,
                 lang: self.lang,
                 region: self.region,
                 fill: fill.clone(),
-                stroke: stroke.clone().map(|s| s.unwrap_or_default()),
+                stroke,
                 text: self.text[range.start - self.base..range.end - self.base].into(),
                 glyphs,
             };
 
-            let width = item.width();
-            if decos.is_empty() {
-                frame.push(pos, FrameItem::Text(item));
+            let width = item.width() * stretch;
+            if upright {
+                push_upright(&mut frame, pos, &item, group, ts);
+                for deco in &decos {
+                    decorate(&mut frame, deco, &item, width, shift, pos);
+                }
+            } else if decos.is_empty() {
+                push_text(&mut frame, pos, item, ts);
             } else {
                 // Apply line decorations.
-                frame.push(pos, FrameItem::Text(item.clone()));
+                push_text(&mut frame, pos, item.clone(), ts);
                 for deco in &decos {
                     decorate(&mut frame, deco, &item, width, shift, pos);
                 }
@@ -348,12 +455,10 @@ impl<'a> ShapedText<'a> {
         if self.glyphs.is_empty() {
             // When there are no glyphs, we just use the vertical metrics of the
             // first available font.
-            let world = engine.world;
+            let variations = variations(self.styles);
             for family in families(self.styles) {
-                if let Some(font) = world
-                    .book()
-                    .select(family, self.variant)
-                    .and_then(|id| world.font(id))
+                if let Some(font) = select_font(engine, self.styles, family, self.variant)
+                    .map(|font| font.with_variations(&variations))
                 {
                     expand(&font, None);
                     break;
@@ -379,6 +484,11 @@ impl<'a> ShapedText<'a> {
         self.glyphs.iter().filter(|g| g.is_justifiable()).count()
     }
 
+    /// How many positions are in the text where kashidas can be inserted.
+    pub fn kashidas(&self) -> usize {
+        self.glyphs.iter().filter(|g| g.kashida).count()
+    }
+
     /// Whether the last glyph is a CJK character which should not be justified
     /// on line end.
     pub fn cjk_justifiable_at_last(&self) -> bool {
@@ -406,6 +516,18 @@ impl<'a> ShapedText<'a> {
             .at(self.size)
     }
 
+    /// How much the text's glyphs can stretch or shrink if they may be
+    /// scaled horizontally by the given ratio.
+    pub fn expandability(&self, expansion: f64) -> Abs {
+        self.glyphs
+            .iter()
+            .filter(|g| !g.is_space() && g.upright.is_none())
+            .map(|g| g.x_advance)
+            .sum::<Em>()
+            .at(self.size)
+            * expansion
+    }
+
     /// Reshape a range of the shaped text, reusing information from this
     /// shaping process if possible.
     ///
@@ -457,20 +579,19 @@ impl<'a> ShapedText<'a> {
     }
 
     fn insert_hyphen(&mut self, engine: &Engine, fallback: bool, side: Side) {
-        let world = engine.world;
-        let book = world.book();
         let fallback_func = if fallback {
-            Some(|| book.select_fallback(None, self.variant, "-"))
+            Some(|| select_fallback_font(engine, self.styles, None, self.variant, "-"))
         } else {
             None
         };
         let mut chain = families(self.styles)
-            .map(|family| book.select(family, self.variant))
+            .map(|family| select_font(engine, self.styles, family, self.variant))
             .chain(fallback_func.iter().map(|f| f()))
             .flatten();
 
-        chain.find_map(|id| {
-            let font = world.font(id)?;
+        let variations = variations(self.styles);
+        chain.find_map(|font| {
+            let font = font.with_variations(&variations);
             let ttf = font.ttf();
             let glyph_id = ttf.glyph_index('-')?;
             let x_advance = font.to_em(ttf.glyph_hor_advance(glyph_id)?);
@@ -497,6 +618,9 @@ impl<'a> ShapedText<'a> {
                 span: (Span::detached(), 0),
                 is_justifiable: false,
                 script: Script::Common,
+                scale: 1.0,
+                upright: None,
+                kashida: false,
             };
             match side {
                 Side::Left => self.glyphs.to_mut().insert(0, glyph),
@@ -598,9 +722,12 @@ struct ShapingContext<'a, 'v> {
     styles: StyleChain<'a>,
     size: Abs,
     variant: FontVariant,
+    variations: Vec<(Tag, f32)>,
     features: Vec<rustybuzz::Feature>,
+    smallcaps: bool,
     fallback: bool,
     dir: Dir,
+    orientation: Orientation,
 }
 
 /// Shape text into [`ShapedText`].
@@ -624,17 +751,27 @@ pub(super) fn shape<'a>(
         used: vec![],
         styles,
         variant: variant(styles),
+        variations: variations(styles),
         features: features(styles),
+        smallcaps: TextElem::smallcaps_in(styles),
         fallback: TextElem::fallback_in(styles),
         dir,
+        orientation: Orientation::Sideways,
     };
 
-    if !text.is_empty() {
+    if TextElem::dir_in(styles) == Dir::TTB {
+        // In vertical text, upright and sideways runs are shaped separately.
+        for (range, orientation) in orientation_runs(text) {
+            ctx.orientation = orientation;
+            shape_segment(&mut ctx, base + range.start, &text[range], families(styles));
+        }
+    } else if !text.is_empty() {
         shape_segment(&mut ctx, base, text, families(styles));
     }
 
     track_and_space(&mut ctx);
     calculate_adjustability(&mut ctx, lang, region);
+    mark_kashidas(&mut ctx.glyphs, ctx.dir, base, text);
 
     #[cfg(debug_assertions)]
     assert_all_glyphs_in_range(&ctx.glyphs, text, base..(base + text.len()));
@@ -668,21 +805,19 @@ fn shape_segment<'a>(
     }
 
     // Find the next available family.
-    let world = ctx.engine.world;
-    let book = world.book();
     let mut selection = families.find_map(|family| {
-        book.select(family, ctx.variant)
-            .and_then(|id| world.font(id))
+        select_font(ctx.engine, ctx.styles, family, ctx.variant)
+            .map(|font| font.with_variations(&ctx.variations))
             .filter(|font| !ctx.used.contains(font))
     });
 
     // Do font fallback if the families are exhausted and fallback is enabled.
     if selection.is_none() && ctx.fallback {
         let first = ctx.used.first().map(Font::info);
-        selection = book
-            .select_fallback(first, ctx.variant, text)
-            .and_then(|id| world.font(id))
-            .filter(|font| !ctx.used.contains(font));
+        selection =
+            select_fallback_font(ctx.engine, ctx.styles, first, ctx.variant, text)
+                .map(|font| font.with_variations(&ctx.variations))
+                .filter(|font| !ctx.used.contains(font));
     }
 
     // Extract the font id or shape notdef glyphs if we couldn't find any font.
@@ -695,9 +830,23 @@ fn shape_segment<'a>(
 
     ctx.used.push(font.clone());
 
+    // If the font has no small capitals, we synthesize them from scaled-down
+    // capital letters.
+    let synthesize_smallcaps = ctx.smallcaps && !has_smallcaps(&font);
+
     // Fill the buffer with our text.
     let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
+    if synthesize_smallcaps {
+        for (i, c) in text.char_indices() {
+            if c.is_lowercase() {
+                c.to_uppercase().for_each(|upper| buffer.add(upper, i as u32));
+            } else {
+                buffer.add(c, i as u32);
+            }
+        }
+    } else {
+        buffer.push_str(text);
+    }
     buffer.set_language(language(ctx.styles));
     if let Some(script) = TextElem::script_in(ctx.styles).custom().and_then(|script| {
         rustybuzz::Script::from_iso15924_tag(Tag::from_bytes(script.as_bytes()))
@@ -711,6 +860,14 @@ fn shape_segment<'a>(
     });
     buffer.guess_segment_properties();
 
+    // Upright glyphs use their vertical alternates.
+    let mut features = Cow::Borrowed(&ctx.features);
+    if ctx.orientation == Orientation::Upright {
+        let features = features.to_mut();
+        features.push(rustybuzz::Feature::new(Tag::from_bytes(b"vert"), 1, ..));
+        features.push(rustybuzz::Feature::new(Tag::from_bytes(b"vrt2"), 1, ..));
+    }
+
     // Prepare the shape plan. This plan depends on direction, script, language,
     // and features, but is independent from the text and can thus be
     // memoized.
@@ -719,7 +876,7 @@ fn shape_segment<'a>(
         buffer.direction(),
         buffer.script(),
         buffer.language().as_ref(),
-        &ctx.features,
+        &features,
     );
 
     // Shape!
@@ -728,6 +885,10 @@ fn shape_segment<'a>(
     let pos = buffer.glyph_positions();
     let ltr = ctx.dir.is_positive();
 
+    // A combined upright run occupies a single slot of one em.
+    let combined_width: Em = pos.iter().map(|p| font.to_em(p.x_advance)).sum();
+    let mut combined_offset = Em::zero();
+
     // Collect the shaped glyphs, doing fallback and shaping parts again with
     // the next font if necessary.
     let mut i = 0;
@@ -746,14 +907,43 @@ fn shape_segment<'a>(
 
             let c = text[cluster..].chars().next().unwrap();
             let script = c.script();
-            let x_advance = font.to_em(pos[i].x_advance);
+            let scale = if synthesize_smallcaps && c.is_lowercase() {
+                SMALLCAPS_SCALE
+            } else {
+                1.0
+            };
+            let mut x_advance = font.to_em(pos[i].x_advance) * scale;
+            let mut x_offset = font.to_em(pos[i].x_offset) * scale;
+            let mut y_offset = font.to_em(pos[i].y_offset) * scale;
+            let upright = match ctx.orientation {
+                Orientation::Sideways => None,
+                Orientation::Upright => {
+                    let width = x_advance;
+                    x_advance = vertical_advance(&font, info.glyph_id as u16);
+                    Some(Upright { width, dx: -width / 2.0, center: x_advance / 2.0 })
+                }
+                Orientation::Combined => {
+                    let width = x_advance;
+                    let first = combined_offset == Em::zero();
+                    let dx = combined_offset - combined_width / 2.0;
+                    combined_offset += width;
+                    x_advance = if first { Em::one() } else { Em::zero() };
+                    let center = Em::new(if first { 0.5 } else { -0.5 });
+                    Some(Upright { width, dx, center })
+                }
+            };
+            if upright.is_some() {
+                x_offset = Em::zero();
+                y_offset = Em::zero();
+            }
+
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
                 glyph_id: info.glyph_id as u16,
                 // TODO: Don't ignore y_advance.
                 x_advance,
-                x_offset: font.to_em(pos[i].x_offset),
-                y_offset: font.to_em(pos[i].y_offset),
+                x_offset,
+                y_offset,
                 adjustability: Adjustability::default(),
                 range: start..end,
                 safe_to_break: !info.unsafe_to_break(),
@@ -766,6 +956,9 @@ fn shape_segment<'a>(
                     Adjustability::default().stretchability,
                 ),
                 script,
+                scale,
+                upright,
+                kashida: false,
             });
         } else {
             // First, search for the end of the tofu sequence.
@@ -814,6 +1007,164 @@ fn shape_segment<'a>(
     ctx.used.pop();
 }
 
+/// The size of synthesized small capitals relative to the text size.
+const SMALLCAPS_SCALE: f64 = 0.7;
+
+/// The horizontal skew of synthesized oblique text, roughly 11 degrees.
+const OBLIQUE_SKEW: f64 = 0.2;
+
+/// The Arabic tatweel, which is inserted as a kashida.
+const TATWEEL: char = '\u{0640}';
+
+/// Whether the font provides small capitals through the `smcp` feature.
+fn has_smallcaps(font: &Font) -> bool {
+    let smcp = Tag::from_bytes(b"smcp");
+    font.ttf().tables().gsub.is_some_and(|gsub| {
+        (0..gsub.features.len())
+            .filter_map(|i| gsub.features.get(i))
+            .any(|feature| feature.tag == smcp)
+    })
+}
+
+/// The stroke thickness with which to embolden text if the font is
+/// considerably lighter than the requested weight.
+fn synthesized_bold(font: &Font, variant: FontVariant) -> Option<Em> {
+    let info = font.info();
+    let requested = variant.weight.to_number();
+    let actual = info.variant.weight.to_number();
+    if requested < 600 || actual >= 600 || info.axis(Tag::from_bytes(b"wght")).is_some() {
+        return None;
+    }
+
+    // Thicken the stems by a hundredth of an em per weight step of 100, e.g.
+    // by three hundredths of an em from regular (400) to bold (700).
+    let steps = f64::from(requested - actual) / 100.0;
+    Some(Em::new(steps / 100.0))
+}
+
+/// Whether text should be obliqued because the font has no italic or oblique
+/// style although one was requested.
+fn synthesized_oblique(font: &Font, variant: FontVariant) -> bool {
+    let info = font.info();
+    variant.style != FontStyle::Normal
+        && info.variant.style == FontStyle::Normal
+        && info.axis(Tag::from_bytes(b"ital")).is_none()
+        && info.axis(Tag::from_bytes(b"slnt")).is_none()
+}
+
+/// Push a text item into the frame, transforming it around its baseline
+/// origin if it is obliqued or expanded.
+fn push_text(frame: &mut Frame, pos: Point, item: TextItem, ts: Transform) {
+    if ts.is_identity() {
+        frame.push(pos, FrameItem::Text(item));
+        return;
+    }
+
+    let mut transformed = Frame::soft(Size::zero());
+    transformed.push(Point::zero(), FrameItem::Text(item));
+    transformed.transform(ts);
+    frame.push_frame(pos, transformed);
+}
+
+/// Push each glyph of an upright run in vertical text as a text item of its
+/// own, turned against the line and centered in its slot.
+fn push_upright(
+    frame: &mut Frame,
+    pos: Point,
+    item: &TextItem,
+    group: &[ShapedGlyph],
+    ts: Transform,
+) {
+    let metrics = item.font.metrics();
+    let middle = ((metrics.ascender + metrics.descender) / 2.0).at(item.size);
+
+    let mut x = pos.x;
+    for (glyph, shaped) in item.glyphs.iter().zip(group) {
+        let Some(upright) = shaped.upright else { continue };
+        let start = usize::from(glyph.range.start);
+        let end = usize::from(glyph.range.end);
+        let single = TextItem {
+            font: item.font.clone(),
+            size: item.size,
+            fill: item.fill.clone(),
+            stroke: item.stroke.clone(),
+            lang: item.lang,
+            region: item.region,
+            text: item.text[start..end].into(),
+            glyphs: vec![Glyph {
+                id: glyph.id,
+                x_advance: upright.width,
+                x_offset: Em::zero(),
+                range: 0..glyph.range.end - glyph.range.start,
+                span: glyph.span,
+            }],
+        };
+
+        let mut turned = Frame::soft(Size::zero());
+        push_text(&mut turned, Point::new(upright.dx.at(item.size), middle), single, ts);
+        turned.transform(Transform::rotate(Angle::deg(-90.0)));
+
+        let center = x + (glyph.x_offset + upright.center).at(item.size);
+        frame.push_frame(Point::new(center, pos.y - middle), turned);
+        x += glyph.x_advance.at(item.size);
+    }
+}
+
+/// Split vertical text into runs of sideways, upright, and combined
+/// (tate-chu-yoko) text.
+fn orientation_runs(text: &str) -> Vec<(Range<usize>, Orientation)> {
+    let mut runs: Vec<(Range<usize>, Orientation)> = vec![];
+    for (i, c) in text.char_indices() {
+        let orientation = if is_upright(c) {
+            Orientation::Upright
+        } else if c.is_ascii_digit() {
+            Orientation::Combined
+        } else {
+            Orientation::Sideways
+        };
+
+        match runs.last_mut() {
+            Some((range, last)) if *last == orientation => range.end = i + c.len_utf8(),
+            _ => runs.push((i..i + c.len_utf8(), orientation)),
+        }
+    }
+
+    // Only short numbers are combined, longer ones are set sideways.
+    for (range, orientation) in &mut runs {
+        if *orientation == Orientation::Combined && range.len() > 2 {
+            *orientation = Orientation::Sideways;
+        }
+    }
+
+    runs
+}
+
+/// Whether a character stands upright in vertical text.
+fn is_upright(c: char) -> bool {
+    matches!(
+        c.script(),
+        Script::Han
+            | Script::Hiragana
+            | Script::Katakana
+            | Script::Hangul
+            | Script::Bopomofo
+    ) || matches!(c,
+        '\u{1100}'..='\u{11FF}'
+        | '\u{2E80}'..='\u{33FF}'
+        | '\u{FE10}'..='\u{FE1F}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/// The vertical advance of a glyph, which is one em if the font has no
+/// vertical metrics.
+fn vertical_advance(font: &Font, id: u16) -> Em {
+    font.ttf()
+        .glyph_ver_advance(ttf_parser::GlyphId(id))
+        .map_or(Em::one(), |advance| font.to_em(advance))
+}
+
 /// Create a shape plan.
 #[comemo::memoize]
 fn create_shape_plan(
@@ -857,6 +1208,9 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
                 Adjustability::default().stretchability,
             ),
             script,
+            scale: 1.0,
+            upright: None,
+            kashida: false,
         });
     };
     if ctx.dir.is_positive() {
@@ -925,6 +1279,148 @@ fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<
     }
 }
 
+/// Mark the glyphs next to which a kashida may be inserted.
+///
+/// A kashida elongates the connection between two joined Arabic letters. At
+/// most one is inserted per word, at the joint with the highest
+/// [priority](kashida_priority). Among equally good joints, the logically last
+/// one wins.
+fn mark_kashidas(glyphs: &mut [ShapedGlyph], dir: Dir, base: usize, text: &str) {
+    if dir.is_positive() {
+        return;
+    }
+
+    // Right-to-left glyphs are in visual order, i.e. from the logical end of
+    // the text towards its start. Each word is thus visited from its last
+    // letter and a joint only replaces the best one so far if it is strictly
+    // better. The first glyph of a cluster is its leftmost one.
+    let mut best: Option<(u8, usize)> = None;
+    for i in 0..glyphs.len() {
+        let glyph = &glyphs[i];
+        if glyph.is_space() {
+            if let Some((_, k)) = best.take() {
+                glyphs[k].kashida = true;
+            }
+            continue;
+        }
+
+        if glyph.upright.is_some() || (i > 0 && glyphs[i - 1].range == glyph.range) {
+            continue;
+        }
+
+        // Marks are transparent to joining, so the letters after them decide
+        // whether and how the glyph joins to the left.
+        let mut letters =
+            text[glyph.range.end - base..].chars().filter(|&c| !is_arabic_mark(c));
+        let Some(next) = letters.next() else { continue };
+        if !is_dual_joining(glyph.c)
+            || !(is_dual_joining(next) || is_right_joining(next))
+            || glyph.font.ttf().glyph_index(TATWEEL).is_none()
+        {
+            continue;
+        }
+
+        let priority = kashida_priority(glyph.c, next, letters.next());
+        if best.map_or(true, |(p, _)| priority < p) {
+            best = Some((priority, i));
+        }
+    }
+
+    if let Some((_, k)) = best {
+        glyphs[k].kashida = true;
+    }
+}
+
+/// How suitable the joint between the letters `c` and `next` is for a kashida.
+/// Lower values are better. `after` is the letter following `next`, if any.
+///
+/// This follows the order that is traditionally used for justification with
+/// kashidas:
+/// 0. After a seen or sad.
+/// 1. Before a final teh marbuta, heh, or dal.
+/// 2. Before a final alef, tah, lam, kaf, or gaf.
+/// 3. Before a medial beh followed by a reh, yeh, or alef maksura.
+/// 4. Before a final waw, ain, qaf, or feh.
+/// 5. Between any other joined letters.
+fn kashida_priority(c: char, next: char, after: Option<char>) -> u8 {
+    let is_final = !is_dual_joining(next)
+        || !after.is_some_and(|c| is_dual_joining(c) || is_right_joining(c));
+    let before_reh =
+        after.is_some_and(|c| matches!(c, '\u{0631}' | '\u{0649}' | '\u{064A}'));
+    match next {
+        _ if matches!(c, '\u{0633}'..='\u{0636}') => 0,
+        '\u{0629}' | '\u{062F}' | '\u{0647}' if is_final => 1,
+        '\u{0622}' | '\u{0623}' | '\u{0625}' | '\u{0627}' | '\u{0637}' | '\u{0643}'
+        | '\u{0644}' | '\u{06A9}' | '\u{06AF}'
+            if is_final =>
+        {
+            2
+        }
+        '\u{0628}' | '\u{062A}' | '\u{062B}' | '\u{0646}' | '\u{064A}' if before_reh => 3,
+        '\u{0639}' | '\u{0641}' | '\u{0642}' | '\u{0648}' if is_final => 4,
+        _ => 5,
+    }
+}
+
+/// Whether an Arabic letter joins with the letters on both of its sides.
+fn is_dual_joining(c: char) -> bool {
+    matches!(c, '\u{0620}'..='\u{064A}' | '\u{066E}'..='\u{06D3}' | '\u{06FA}'..='\u{06FC}'
+        | '\u{06FF}' | '\u{0750}'..='\u{077F}')
+        && !matches!(c, '\u{0621}' | '\u{0640}' | '\u{0674}')
+        && !is_right_joining(c)
+        && !is_arabic_mark(c)
+}
+
+/// Whether a character is an Arabic combining mark, like a vowel sign or the
+/// superscript alef.
+fn is_arabic_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0610}'..='\u{061A}'
+            | '\u{064B}'..='\u{065F}'
+            | '\u{0670}'
+            | '\u{06D6}'..='\u{06DC}'
+            | '\u{06DF}'..='\u{06E4}'
+            | '\u{06E7}'
+            | '\u{06E8}'
+            | '\u{06EA}'..='\u{06ED}'
+            | '\u{08D3}'..='\u{08E1}'
+            | '\u{08E3}'..='\u{08FF}'
+    )
+}
+
+/// Whether an Arabic letter only joins with the letter before it.
+fn is_right_joining(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0622}'..='\u{0625}'
+            | '\u{0627}'
+            | '\u{0629}'
+            | '\u{062F}'..='\u{0632}'
+            | '\u{0648}'
+            | '\u{0671}'..='\u{0673}'
+            | '\u{0675}'..='\u{0677}'
+            | '\u{0688}'..='\u{0699}'
+            | '\u{06C0}'
+            | '\u{06C3}'..='\u{06CB}'
+            | '\u{06CD}'
+            | '\u{06CF}'
+            | '\u{06D2}'
+            | '\u{06D3}'
+            | '\u{06D5}'
+            | '\u{06EE}'
+            | '\u{06EF}'
+            | '\u{0759}'..='\u{075B}'
+            | '\u{076B}'
+            | '\u{076C}'
+            | '\u{0771}'
+            | '\u{0773}'
+            | '\u{0774}'
+            | '\u{0778}'
+            | '\u{0779}'
+    )
+}
+
 /// Difference between non-breaking and normal space.
 fn nbsp_delta(font: &Font) -> Option<Em> {
     let space = font.ttf().glyph_index(' ')?.0;
@@ -1105,3 +1601,97 @@ fn is_justifiable(
         || is_cjk_right_aligned_punctuation(c, x_advance, stretchability)
         || is_cjk_center_aligned_punctuation(c, style)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foundations::Bytes;
+    use crate::text::{FontStretch, FontWeight};
+
+    fn regular() -> Font {
+        let data = typst_dev_assets::get("fonts/Roboto-Regular.ttf").unwrap();
+        Font::new(Bytes::from_static(data), 0).unwrap()
+    }
+
+    fn arabic() -> Font {
+        let data = typst_dev_assets::get("fonts/NotoSansArabic-Regular.ttf").unwrap();
+        Font::new(Bytes::from_static(data), 0).unwrap()
+    }
+
+    fn request(style: FontStyle, weight: u16) -> FontVariant {
+        FontVariant::new(style, FontWeight::from_number(weight), FontStretch::NORMAL)
+    }
+
+    #[test]
+    fn test_synthesized_bold() {
+        let font = regular();
+        assert_eq!(font.info().variant.weight, FontWeight::REGULAR);
+        assert_eq!(synthesized_bold(&font, request(FontStyle::Normal, 400)), None);
+        assert_eq!(synthesized_bold(&font, request(FontStyle::Normal, 500)), None);
+        assert_eq!(
+            synthesized_bold(&font, request(FontStyle::Normal, 700)),
+            Some(Em::new(0.03))
+        );
+        assert_eq!(
+            synthesized_bold(&font, request(FontStyle::Normal, 900)),
+            Some(Em::new(0.05))
+        );
+    }
+
+    #[test]
+    fn test_synthesized_oblique() {
+        let font = regular();
+        assert!(!synthesized_oblique(&font, request(FontStyle::Normal, 400)));
+        assert!(synthesized_oblique(&font, request(FontStyle::Italic, 400)));
+        assert!(synthesized_oblique(&font, request(FontStyle::Oblique, 700)));
+    }
+
+    #[test]
+    fn test_mark_kashidas() {
+        // Shape the glyphs of a text in visual order, one per character.
+        let font = arabic();
+        let shape = |text: &str| {
+            let mut glyphs: Vec<_> = text
+                .char_indices()
+                .map(|(i, c)| ShapedGlyph {
+                    font: font.clone(),
+                    glyph_id: 0,
+                    x_advance: Em::zero(),
+                    x_offset: Em::zero(),
+                    y_offset: Em::zero(),
+                    adjustability: Adjustability::default(),
+                    range: i..i + c.len_utf8(),
+                    safe_to_break: true,
+                    c,
+                    span: (Span::detached(), 0),
+                    is_justifiable: false,
+                    script: Script::Arabic,
+                    scale: 1.0,
+                    upright: None,
+                    kashida: false,
+                })
+                .collect();
+            glyphs.reverse();
+            mark_kashidas(&mut glyphs, Dir::RTL, 0, text);
+            glyphs.iter().rev().map(|glyph| glyph.kashida).collect::<Vec<_>>()
+        };
+
+        // In "بيت", both the beh and the yeh join to the next letter. Only the
+        // logically last joint, after the yeh, gets the kashida.
+        assert_eq!(shape("بيت"), [false, true, false]);
+
+        // The alef doesn't join to the next letter, so the beh's joint is the
+        // last one. Each word gets its own kashida.
+        assert_eq!(shape("بات بيت"), [true, false, false, false, false, true, false]);
+
+        // The joint after a seen beats the one before the final alef.
+        assert_eq!(shape("سلام"), [true, false, false, false]);
+
+        // The joint before a final heh beats the others.
+        assert_eq!(shape("كتبه"), [false, false, true, false]);
+
+        // The joint before a medial beh followed by a reh beats the one
+        // before the reh itself.
+        assert_eq!(shape("تبر"), [true, false, false]);
+    }
+}
@@ -14,13 +14,13 @@ use crate::foundations::{
 };
 use crate::introspection::{Counter, CounterDisplayElem, CounterKey, ManualPageCounter};
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Frame, HAlignment, Length,
-    OuterVAlignment, Point, Ratio, Regions, Rel, Sides, Size, SpecificAlignment,
-    VAlignment,
+    Abs, AlignElem, Alignment, Angle, Axes, Axis, ColumnsElem, Dir, Frame, HAlignment,
+    Length, OuterVAlignment, Point, Ratio, Regions, Rel, Sides, Size, SpecificAlignment,
+    Transform, VAlignment,
 };
 
-use crate::model::Numbering;
-use crate::text::TextElem;
+use crate::model::{MarginLayouter, Numbering};
+use crate::text::{TextDir, TextElem};
 use crate::utils::{NonZeroExt, Numeric, Scalar};
 use crate::visualize::Paint;
 
@@ -176,6 +176,34 @@ pub struct PageElem {
     #[default(NonZeroUsize::ONE)]
     pub columns: NonZeroUsize,
 
+    /// A baseline grid that the lines of paragraphs on the page snap to.
+    ///
+    /// Can be a length, which is the distance between two lines of the grid,
+    /// or a dictionary with the keys `step` for the distance and `offset`
+    /// for the position of the first grid line below the top of the page's
+    /// content area. The offset is zero by default.
+    ///
+    /// Each line of a paragraph is moved down such that its baseline lies on
+    /// the next grid line. The heights of other blocks like headings,
+    /// equations, and figures as well as of floats are rounded up to whole
+    /// grid steps, so that the lines following them stay on the grid. This
+    /// keeps the lines in columns and on facing pages aligned with each
+    /// other.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 120pt,
+    ///   columns: 2,
+    ///   baseline-grid: 12pt,
+    /// )
+    ///
+    /// = Grid
+    /// #lorem(12)
+    ///
+    /// #lorem(8)
+    /// ```
+    pub baseline_grid: Option<BaselineGrid>,
+
     /// The page's background color.
     ///
     /// This instructs the printer to color the complete page with the given
@@ -336,6 +364,21 @@ pub struct PageElem {
     #[internal]
     #[synthesized]
     pub clear_to: Option<Parity>,
+
+    /// The physical number of the first page of this run of pages.
+    ///
+    /// Side floats use it to determine on which side the binding is.
+    #[internal]
+    #[ghost]
+    #[default(NonZeroUsize::ONE)]
+    pub first_number: NonZeroUsize,
+
+    /// Whether the content of the page is laid out for vertical text, which
+    /// may only be set for whole pages.
+    #[internal]
+    #[ghost]
+    #[default(false)]
+    pub vertical: bool,
 }
 
 impl Packed<PageElem> {
@@ -395,23 +438,37 @@ impl Packed<PageElem> {
                 .spanned(self.span());
         }
 
-        let area = size - margin.sum_by_axis();
+        // Vertical text is laid out in horizontal lines on a page that is
+        // turned by a quarter afterwards.
+        let vertical = TextElem::dir_in(styles).axis() == Axis::Y;
+        let mut area = size - margin.sum_by_axis();
+        if vertical {
+            std::mem::swap(&mut area.x, &mut area.y);
+        }
+
         let mut regions = Regions::repeat(area, area.map(Abs::is_finite));
         regions.root = true;
 
-        // Layout the child.
-        let mut frames = child.layout(engine, styles, regions)?.into_frames();
-
-        // Align the child to the pagebreak's parity.
-        // Check for page count after adding the pending frames
-        if extend_to
-            .is_some_and(|p| !p.matches(page_counter.physical().get() + frames.len()))
-        {
-            // Insert empty page after the current pages.
-            let size = area.map(Abs::is_finite).select(area, Size::zero());
-            frames.push(Frame::hard(size));
+        // Layout the child. The body may already be a realized flow, so the
+        // number of the first page and whether the page is vertical are
+        // passed through the style chain instead of being wrapped around the
+        // body.
+        let local = [
+            PageElem::set_first_number(page_counter.physical()).wrap(),
+            PageElem::set_vertical(vertical).wrap(),
+        ];
+        let mut frames =
+            child.layout(engine, styles.chain(&local), regions)?.into_frames();
+
+        if vertical {
+            frames = frames.into_iter().map(turn_vertical).collect();
         }
 
+        // The size of pages without content, which are added for margin notes
+        // that don't fit onto the last page and for the pagebreak's parity.
+        let empty = size - margin.sum_by_axis();
+        let empty = empty.map(Abs::is_finite).select(empty, Size::zero());
+
         let fill = self.fill(styles);
         let foreground = self.foreground(styles);
         let background = self.background(styles);
@@ -458,9 +515,29 @@ impl Packed<PageElem> {
             )
         };
 
+        // The marginals are not turned with the content of vertical pages, so
+        // they are laid out horizontally.
+        let horizontal = TextElem::set_dir(TextDir(Smart::Auto)).wrap();
+        let marginal_styles = if vertical { styles.chain(&horizontal) } else { styles };
+
         // Post-process pages.
         let mut pages = Vec::with_capacity(frames.len());
-        for mut frame in frames {
+        let mut margin_notes = MarginLayouter::new(binding);
+        let mut frames = frames.into_iter();
+        loop {
+            let mut frame = match frames.next() {
+                Some(frame) => frame,
+                // Continue the margin notes on new pages.
+                None if margin_notes.has_carried() => Frame::hard(empty),
+                // Align the child to the pagebreak's parity.
+                None if extend_to
+                    .is_some_and(|p| !p.matches(page_counter.physical().get())) =>
+                {
+                    Frame::hard(empty)
+                }
+                None => break,
+            };
+
             // The padded width of the page's content without margins.
             let pw = frame.width();
 
@@ -476,6 +553,9 @@ impl Packed<PageElem> {
             frame.set_size(frame.size() + margin.sum_by_axis());
             frame.translate(Point::new(margin.left, margin.top));
 
+            // Place margin notes next to their lines.
+            margin_notes.layout(engine, &mut frame, margin, page_counter.physical())?;
+
             // The page size with margins.
             let size = frame.size();
 
@@ -504,7 +584,7 @@ impl Packed<PageElem> {
                 let sub = content
                     .clone()
                     .styled(AlignElem::set_alignment(align))
-                    .layout(engine, styles, pod)?
+                    .layout(engine, marginal_styles, pod)?
                     .into_frame();
 
                 if ptr::eq(marginal, header) || ptr::eq(marginal, background) {
@@ -532,6 +612,16 @@ impl Packed<PageElem> {
     }
 }
 
+/// Turn a frame laid out for vertical text clockwise, so that its lines run
+/// from top to bottom and follow each other from right to left.
+fn turn_vertical(mut frame: Frame) -> Frame {
+    let size = frame.size();
+    let mut turned = Frame::hard(Size::new(size.y, size.x));
+    frame.transform(Transform::rotate(Angle::deg(90.0)));
+    turned.push_frame(Point::with_x(size.y), frame);
+    turned
+}
+
 /// A finished page.
 #[derive(Debug, Clone)]
 pub struct Page {
@@ -665,7 +755,7 @@ pub enum Binding {
 
 impl Binding {
     /// Whether to swap left and right margin for the page with this number.
-    fn swap(self, number: NonZeroUsize) -> bool {
+    pub(crate) fn swap(self, number: NonZeroUsize) -> bool {
         match self {
             // Left-bound must swap on even pages
             // (because it is correct on the first page).
@@ -690,6 +780,41 @@ cast! {
     },
 }
 
+/// A grid of equally spaced lines that the baselines of lines snap to.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct BaselineGrid {
+    /// The distance between two lines of the grid.
+    pub step: Length,
+    /// The position of the first line of the grid below the top of the
+    /// content area.
+    pub offset: Length,
+}
+
+impl Resolve for BaselineGrid {
+    type Output = (Abs, Abs);
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        (self.step.resolve(styles), self.offset.resolve(styles))
+    }
+}
+
+cast! {
+    BaselineGrid,
+    self => {
+        let mut dict = Dict::new();
+        dict.insert("step".into(), self.step.into_value());
+        dict.insert("offset".into(), self.offset.into_value());
+        Value::Dict(dict)
+    },
+    step: Length => Self { step, offset: Length::zero() },
+    mut dict: Dict => {
+        let step = dict.take("step")?.cast()?;
+        let offset = dict.take("offset").ok().map(Value::cast).transpose()?;
+        dict.finish(&["step", "offset"])?;
+        Self { step, offset: offset.unwrap_or_default() }
+    },
+}
+
 /// A header, footer, foreground or background definition.
 #[derive(Debug, Clone, Hash)]
 pub enum Marginal {
@@ -1,8 +1,13 @@
+use std::num::NonZeroUsize;
+
 use crate::diag::{bail, At, Hint, SourceResult};
 use crate::engine::Engine;
-use crate::foundations::{elem, scope, Content, Packed, Smart, StyleChain, Unlabellable};
+use crate::foundations::{
+    cast, elem, scope, Cast, Content, Packed, Resolve, Smart, StyleChain, Unlabellable,
+};
 use crate::layout::{
-    Alignment, Axes, Em, Fragment, Length, Regions, Rel, Size, VAlignment,
+    Alignment, Axes, Binding, Em, FixedAlignment, Fragment, Length, OuterHAlignment,
+    Regions, Rel, Size, VAlignment,
 };
 use crate::realize::{Behave, Behaviour};
 
@@ -32,6 +37,7 @@ pub struct PlaceElem {
     ///
     /// - If `float` is `{false}`, then this can be any alignment other than `{auto}`.
     /// - If `float` is `{true}`, then this must be `{auto}`, `{top}`, or `{bottom}`.
+    ///   It is ignored for floats with a [`side`]($place.side).
     ///
     /// When an axis of the page is `{auto}` sized, all alignments relative to
     /// that axis will be ignored, instead, the item will be placed in the
@@ -65,6 +71,74 @@ pub struct PlaceElem {
     /// ```
     pub float: bool,
 
+    /// At which side of the region to place a floating element with the
+    /// following text wrapping around it.
+    ///
+    /// Instead of moving to the top or bottom of the region, a side float is
+    /// placed at the current position in the flow, at the `{left}`,
+    /// `{right}`, `{start}`, or `{end}` of the region. With `{"inside"}` and
+    /// `{"outside"}`, it is placed at the side towards or away from the
+    /// page's [binding]($page.binding). The lines of paragraphs next to the
+    /// float are shortened to make room for it. Other blocks like lists and
+    /// tables are narrowed as a whole to fit next to it.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #set par(justify: true)
+    /// #place(
+    ///   float: true,
+    ///   side: right,
+    ///   clearance: 6pt,
+    ///   rect(width: 50pt, height: 60pt),
+    /// )
+    /// #lorem(40)
+    /// ```
+    pub side: Option<FloatSide>,
+
+    /// The shape that the text next to a side float wraps around.
+    ///
+    /// - `{"rect"}`: The bounding box of the float.
+    /// - `{"ellipse"}`: The ellipse inscribed into the bounding box.
+    /// - An array of lengths: The widths of horizontal slices of equal height
+    ///   from top to bottom, relative to the float's width.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #set par(justify: true)
+    /// #place(
+    ///   float: true,
+    ///   side: left,
+    ///   wrap: "ellipse",
+    ///   circle(radius: 35pt, fill: aqua),
+    /// )
+    /// #lorem(40)
+    /// ```
+    #[default(WrapShape::Rect)]
+    pub wrap: WrapShape,
+
+    /// Relative to which container a floating element is placed.
+    ///
+    /// - `{"column"}`: The float is placed at the top or bottom of the column
+    ///   it appears in.
+    /// - `{"page"}`: The float spans all columns of the page or of the
+    ///   surrounding [`columns`] element and is placed at the top or bottom
+    ///   of the region it appears in. Outside of columns, it behaves like a
+    ///   column-scoped float.
+    ///
+    /// ```example
+    /// #set page(height: 150pt, columns: 2)
+    /// #place(
+    ///   top + center,
+    ///   float: true,
+    ///   scope: "page",
+    ///   clearance: 6pt,
+    ///   rect(width: 100%)[Spanning both columns],
+    /// )
+    /// #lorem(40)
+    /// ```
+    #[default(PlacementScope::Column)]
+    pub scope: PlacementScope,
+
     /// The amount of clearance the placed element has in a floating layout.
     #[default(Em::new(1.5).into())]
     #[resolve]
@@ -116,7 +190,16 @@ impl Packed<PlaceElem> {
         let float = self.float(styles);
         let alignment = self.alignment(styles);
 
-        if float
+        if !float && self.side(styles).is_some() {
+            return Err("side placement is only available for floating placement")
+                .hint("you can enable floating placement with `place(float: true, ..)`")
+                .at(self.span());
+        } else if !float && self.scope(styles) == PlacementScope::Page {
+            return Err("page-scoped placement is only available for floating placement")
+                .hint("you can enable floating placement with `place(float: true, ..)`")
+                .at(self.span());
+        } else if float
+            && self.side(styles).is_none()
             && alignment.is_custom_and(|align| {
                 matches!(align.y(), None | Some(VAlignment::Horizon))
             })
@@ -145,6 +228,90 @@ impl Behave for Packed<PlaceElem> {
     }
 }
 
+/// The side of a region at which a floating element is placed with text
+/// wrapping around it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FloatSide {
+    /// The side given by a horizontal alignment.
+    Align(OuterHAlignment),
+    /// The side towards the page's binding.
+    Inside,
+    /// The side away from the page's binding.
+    Outside,
+}
+
+impl FloatSide {
+    /// Resolve the side to the left (start) or right (end) side of the page
+    /// with the given binding and physical page number.
+    pub(crate) fn resolve(
+        self,
+        binding: Binding,
+        number: NonZeroUsize,
+        styles: StyleChain,
+    ) -> FixedAlignment {
+        let inside = match self {
+            Self::Align(align) => return align.resolve(styles),
+            Self::Inside => true,
+            Self::Outside => false,
+        };
+
+        if inside != binding.swap(number) {
+            FixedAlignment::Start
+        } else {
+            FixedAlignment::End
+        }
+    }
+}
+
+cast! {
+    FloatSide,
+    self => match self {
+        Self::Align(align) => align.into_value(),
+        Self::Inside => "inside".into_value(),
+        Self::Outside => "outside".into_value(),
+    },
+    align: OuterHAlignment => Self::Align(align),
+    "inside" => Self::Inside,
+    "outside" => Self::Outside,
+}
+
+/// Relative to which container a floating element is placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PlacementScope {
+    /// The column the float appears in.
+    Column,
+    /// All columns of the page or the surrounding columns element.
+    Page,
+}
+
+/// The shape that the text next to a side float wraps around.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum WrapShape {
+    /// The bounding box of the float.
+    Rect,
+    /// The ellipse inscribed into the bounding box of the float.
+    Ellipse,
+    /// The widths of horizontal slices of equal height from top to bottom.
+    Profile(Vec<Rel<Length>>),
+}
+
+cast! {
+    WrapShape,
+    self => match self {
+        Self::Rect => "rect".into_value(),
+        Self::Ellipse => "ellipse".into_value(),
+        Self::Profile(widths) => widths.into_value(),
+    },
+    "rect" => Self::Rect,
+    "ellipse" => Self::Ellipse,
+    v: Vec<Rel<Length>> => {
+        if v.is_empty() {
+            bail!("wrap profile must not be empty");
+        }
+        Self::Profile(v)
+    },
+}
+
 /// Asks the layout algorithm to place pending floating elements before
 /// continuing with the content.
 ///
@@ -74,7 +74,7 @@ use crate::layout::{Alignment, Dir};
 use crate::model::Document;
 use crate::syntax::package::PackageSpec;
 use crate::syntax::{FileId, Source, Span};
-use crate::text::{Font, FontBook};
+use crate::text::{Font, FontBook, TextElem};
 use crate::utils::LazyHash;
 use crate::visualize::Color;
 
@@ -115,7 +115,11 @@ fn typeset(
         &["typeset (1)", "typeset (2)", "typeset (3)", "typeset (4)", "typeset (5)"];
 
     let library = world.library();
-    let styles = StyleChain::new(&library.styles);
+
+    // Make the fonts declared by imported packages available.
+    let fonts = Styles::from(TextElem::set_package_fonts(tracer.fonts().to_vec()));
+    let base = StyleChain::new(&library.styles);
+    let styles = base.chain(&fonts);
 
     let mut iter = 0;
     let mut document = Document::default();
@@ -290,7 +290,7 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
         let par = ParElem::new(StyleVec::wrap(eco_vec![text]));
         let frame = Packed::new(par)
             .spanned(span)
-            .layout(self.engine, styles, false, Size::splat(Abs::inf()), false)?
+            .layout(self.engine, styles, false, Size::splat(Abs::inf()), &[], false)?
             .into_frame();
 
         Ok(FrameFragment::new(self, styles, frame)
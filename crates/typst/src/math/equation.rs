@@ -20,10 +20,10 @@ use crate::math::{
 use crate::model::{Numbering, Outlinable, ParElem, Refable, Supplement};
 use crate::syntax::Span;
 use crate::text::{
-    families, variant, Font, FontFamily, FontList, FontWeight, LocalName, TextElem,
+    families, select_font, variant, Font, FontFamily, FontList, FontWeight, LocalName,
+    TextElem,
 };
 use crate::utils::{NonZeroExt, Numeric};
-use crate::World;
 
 /// A mathematical equation.
 ///
@@ -431,10 +431,8 @@ fn find_math_font(
     span: Span,
 ) -> SourceResult<Font> {
     let variant = variant(styles);
-    let world = engine.world;
     let Some(font) = families(styles).find_map(|family| {
-        let id = world.book().select(family, variant)?;
-        let font = world.font(id)?;
+        let font = select_font(engine, styles, family, variant)?;
         let _ = font.ttf().tables().math?.constants?;
         Some(font)
     }) else {
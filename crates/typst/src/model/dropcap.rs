@@ -0,0 +1,214 @@
+use std::num::NonZeroUsize;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Content, NativeElement, Packed, SequenceElem, Show, Smart, StyleChain,
+    StyledElem,
+};
+use crate::layout::{Abs, Axes, Em, Frame, FrameItem, Length, Regions, Size};
+use crate::model::{ParElem, ParbreakElem};
+use crate::text::{
+    BottomEdge, BottomEdgeMetric, SmartQuoteElem, SpaceElem, TextElem, TextSize, TopEdge,
+    TopEdgeMetric,
+};
+
+/// An enlarged initial letter at the start of a paragraph.
+///
+/// A drop cap spans the first few lines of a paragraph. Its top is aligned
+/// with the top edge of the first line and its baseline with the baseline of
+/// the last line it spans. The lines next to it are shortened to make room
+/// for it.
+///
+/// The drop cap always starts a new paragraph. By default, the first letter
+/// of the body (together with any punctuation in front of it) becomes the
+/// drop cap.
+///
+/// # Example
+/// ```example
+/// #set par(justify: true)
+/// #dropcap(lines: 3)[
+///   Typst is a markup-based typesetting
+///   system for the sciences. It is
+///   designed to be an alternative both
+///   to advanced tools like LaTeX and
+///   simpler tools like Word.
+/// ]
+/// ```
+#[elem(Show)]
+pub struct DropcapElem {
+    /// How many lines the drop cap spans.
+    #[default(NonZeroUsize::new(3).unwrap())]
+    pub lines: NonZeroUsize,
+
+    /// The gap between the drop cap and the text next to it.
+    #[resolve]
+    #[default(Em::new(0.25).into())]
+    pub gap: Length,
+
+    /// The drop cap. When `{auto}`, the first letter is taken from the body.
+    ///
+    /// ```example
+    /// #dropcap(
+    ///   initial: text(red)[W],
+    ///   lines: 2,
+    /// )[hen the initial is given explicitly, the body is left as is.]
+    /// ```
+    pub initial: Smart<Content>,
+
+    /// The paragraph that starts with the drop cap.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<DropcapElem> {
+    #[typst_macros::time(name = "dropcap", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let (initial, body) = match self.initial(styles) {
+            Smart::Custom(initial) => (initial, self.body().clone()),
+            Smart::Auto => match split_initial(self.body()) {
+                Some(split) => split,
+                None => bail!(
+                    self.span(),
+                    "failed to find the first letter of the body";
+                    hint: "specify the drop cap with the `initial` parameter"
+                ),
+            },
+        };
+
+        let gap = self.gap(styles);
+        Ok(Content::sequence([
+            ParbreakElem::new().pack(),
+            DropcapInitialElem::new(initial, self.lines(styles), gap.into())
+                .pack()
+                .spanned(self.span()),
+            body,
+        ]))
+    }
+}
+
+/// The drop cap at the start of a paragraph, as seen by the inline layouter.
+#[elem]
+pub struct DropcapInitialElem {
+    /// The drop cap.
+    #[required]
+    pub body: Content,
+
+    /// How many lines the drop cap spans.
+    #[required]
+    pub lines: NonZeroUsize,
+
+    /// The gap between the drop cap and the text next to it.
+    #[required]
+    pub gap: Length,
+}
+
+impl Packed<DropcapInitialElem> {
+    /// Lay out the drop cap, scaled so that it spans its lines.
+    ///
+    /// The returned frame's baseline is where the baseline of the first line
+    /// goes.
+    pub fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        region: Size,
+    ) -> SourceResult<Frame> {
+        let pod = Regions::one(region, Axes::splat(false));
+        let lines = self.lines().get();
+
+        // Measure the distance from the top edge of a line to its baseline
+        // and the distance between two lines.
+        let line = TextElem::packed("X").layout(engine, styles, pod)?.into_frame();
+        let top = first_text_baseline(&line).unwrap_or(line.height());
+        let pitch = line.height() + ParElem::leading_in(styles);
+        let height = top + pitch * (lines - 1) as f64;
+
+        // Scale the drop cap so that its cap height covers the lines.
+        let body = self
+            .body()
+            .clone()
+            .styled(TextElem::set_top_edge(TopEdge::Metric(TopEdgeMetric::CapHeight)))
+            .styled(TextElem::set_bottom_edge(BottomEdge::Metric(
+                BottomEdgeMetric::Baseline,
+            )));
+        let natural = body.clone().layout(engine, styles, pod)?.into_frame();
+        let mut frame = if natural.height() > Abs::zero() {
+            let size = TextElem::size_in(styles) * (height / natural.height());
+            body.styled(TextElem::set_size(TextSize(size.into())))
+                .layout(engine, styles, pod)?
+                .into_frame()
+        } else {
+            natural
+        };
+
+        frame.set_baseline(top);
+        Ok(frame)
+    }
+}
+
+/// The vertical position of the baseline of the first text in the frame,
+/// including untransformed nested frames.
+///
+/// Unlike the frame's baseline, this finds the baseline of the first line of
+/// laid out block-level content.
+pub(super) fn first_text_baseline(frame: &Frame) -> Option<Abs> {
+    frame.items().find_map(|(pos, item)| match item {
+        FrameItem::Text(_) => Some(pos.y),
+        FrameItem::Group(group) if group.transform.is_identity() => {
+            first_text_baseline(&group.frame).map(|y| pos.y + y)
+        }
+        _ => None,
+    })
+}
+
+/// Split off the first letter of the content together with any punctuation in
+/// front of it.
+fn split_initial(content: &Content) -> Option<(Content, Content)> {
+    if let Some(elem) = content.to_packed::<TextElem>() {
+        let text = elem.text();
+        let mut end = 0;
+        for grapheme in text.graphemes(true) {
+            end += grapheme.len();
+            if grapheme.chars().any(char::is_alphanumeric) {
+                break;
+            }
+        }
+
+        // Without a letter, there is nothing to take as the drop cap.
+        if !text[..end].chars().any(char::is_alphanumeric) {
+            return None;
+        }
+
+        let span = elem.span();
+        let initial = TextElem::packed(&text[..end]).spanned(span);
+        let rest = TextElem::packed(&text[end..]).spanned(span);
+        Some((initial, rest))
+    } else if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        let children = &sequence.children;
+        let start = children
+            .iter()
+            .position(|child| !child.is::<SpaceElem>() && !child.is::<ParbreakElem>())?;
+
+        // Leading quotes are part of the drop cap.
+        let quotes = children[start..]
+            .iter()
+            .take_while(|child| child.is::<SmartQuoteElem>())
+            .count();
+        let i = start + quotes;
+        let (initial, rest) = split_initial(children.get(i)?)?;
+        let initial = children[start..i].iter().cloned().chain(std::iter::once(initial));
+        let rest = std::iter::once(rest).chain(children[i + 1..].iter().cloned());
+        Some((Content::sequence(initial), Content::sequence(rest)))
+    } else if let Some(styled) = content.to_packed::<StyledElem>() {
+        let (initial, rest) = split_initial(&styled.child)?;
+        Some((
+            initial.styled_with_map(styled.styles.clone()),
+            rest.styled_with_map(styled.styles.clone()),
+        ))
+    } else {
+        None
+    }
+}
@@ -15,9 +15,11 @@ use crate::introspection::{
 };
 use crate::layout::{
     AlignElem, Alignment, BlockChild, BlockElem, Em, HAlignment, Length, OuterVAlignment,
-    PlaceElem, VAlignment, VElem,
+    PlaceElem, PlacementScope, VAlignment, VElem,
+};
+use crate::model::{
+    Numbering, NumberingPattern, Outlinable, ParLine, Refable, Supplement,
 };
-use crate::model::{Numbering, NumberingPattern, Outlinable, Refable, Supplement};
 use crate::text::{Lang, Region, TextElem};
 use crate::utils::NonZeroExt;
 use crate::visualize::ImageElem;
@@ -133,6 +135,23 @@ pub struct FigureElem {
     /// ```
     pub placement: Option<Smart<VAlignment>>,
 
+    /// Relative to which container a floating figure is placed. With
+    /// `{"page"}`, the figure spans all columns. See the `place` function's
+    /// [`scope`]($place.scope) parameter for details.
+    ///
+    /// ```example
+    /// #set page(height: 200pt, columns: 2)
+    /// #figure(
+    ///   placement: top,
+    ///   scope: "page",
+    ///   caption: [A glacier],
+    ///   image("glacier.jpg", width: 60%),
+    /// )
+    /// #lorem(60)
+    /// ```
+    #[default(PlacementScope::Column)]
+    pub scope: PlacementScope,
+
     /// The figure's caption.
     pub caption: Option<Packed<FigureCaption>>,
 
@@ -326,6 +345,7 @@ impl Show for Packed<FigureElem> {
         if let Some(align) = self.placement(styles) {
             realized = PlaceElem::new(realized)
                 .with_float(true)
+                .with_scope(self.scope(styles))
                 .with_alignment(align.map(|align| HAlignment::Center + align))
                 .pack()
                 .spanned(self.span());
@@ -342,6 +362,8 @@ impl ShowSet for Packed<FigureElem> {
         let mut map = Styles::new();
         map.set(BlockElem::set_breakable(false));
         map.set(AlignElem::set_alignment(Alignment::CENTER));
+        // Neither the body nor the caption has numbered lines.
+        map.set(ParLine::set_numbering(None));
         map
     }
 }
@@ -1,15 +1,20 @@
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
+use comemo::Track;
+use ecow::EcoString;
+
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Label, NativeElement, Packed, Show, ShowSet, Smart,
-    StyleChain, Styles,
+    cast, elem, scope, Content, Context, Func, Label, NativeElement, Packed, Property,
+    Show, ShowSet, Smart, StyleChain, Styles, Synthesize,
 };
 use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
 use crate::layout::{Abs, Em, HElem, Length, Ratio};
-use crate::model::{Destination, Numbering, NumberingPattern, ParElem};
+use crate::model::{
+    Destination, Numbering, NumberingPattern, NumberingScope, ParElem, ParLine,
+};
 use crate::text::{SuperElem, TextElem, TextSize};
 use crate::utils::NonZeroExt;
 use crate::visualize::{LineElem, Stroke};
@@ -50,14 +55,13 @@ use crate::visualize::{LineElem, Stroke};
 /// apply to the footnote's content. See [here][issue] for more information.
 ///
 /// [issue]: https://github.com/typst/typst/issues/1467#issuecomment-1588799440
-#[elem(scope, Locatable, Show, Count)]
+#[elem(scope, Locatable, Synthesize, Show, Count)]
 pub struct FootnoteElem {
     /// How to number footnotes.
     ///
     /// By default, the footnote numbering continues throughout your document.
-    /// If you prefer per-page footnote numbering, you can reset the footnote
-    /// [counter] in the page [header]($page.header). In the future, there might
-    /// be a simpler way to achieve this.
+    /// If you prefer per-page footnote numbering, you can set the
+    /// [`numbering-scope`]($footnote.numbering-scope) to `{"page"}`.
     ///
     /// ```example
     /// #set footnote(numbering: "*")
@@ -70,10 +74,66 @@ pub struct FootnoteElem {
     #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
     pub numbering: Numbering,
 
+    /// Whether footnote numbers count through the whole document or restart
+    /// on each page.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #set footnote(numbering-scope: "page")
+    ///
+    /// A#footnote[First] B#footnote[Second]
+    /// #pagebreak()
+    /// C#footnote[First again]
+    /// ```
+    #[default(NumberingScope::Document)]
+    pub numbering_scope: NumberingScope,
+
+    /// The series the footnote belongs to.
+    ///
+    /// Footnotes of different series are numbered independently of each
+    /// other and listed separately, each below its own
+    /// [separator]($footnote.entry.separator). Footnotes without a series form
+    /// the main series, which is listed first. This is useful for critical
+    /// editions that distinguish between a critical apparatus and regular
+    /// notes.
+    ///
+    /// ```example
+    /// #let variant = footnote.with(
+    ///   series: "apparatus",
+    ///   numbering: "a",
+    /// )
+    ///
+    /// A note#footnote[Regular note.]
+    /// and a variant#variant[_variant_ B.]
+    /// ```
+    pub series: Option<EcoString>,
+
+    /// Whether to place the footnote's entry in the page's margin next to the
+    /// line it is referenced in instead of at the bottom of the page.
+    ///
+    /// The entry is placed like a [margin note]($marginnote), so the margin
+    /// note's settings also apply to it.
+    ///
+    /// ```example
+    /// #set page(margin: (right: 90pt))
+    /// #set footnote(margin: true)
+    ///
+    /// Check the docs for more details.
+    /// #footnote[https://typst.app/docs]
+    /// ```
+    #[default(false)]
+    pub margin: bool,
+
     /// The content to put into the footnote. Can also be the label of another
     /// footnote this one should point to.
     #[required]
     pub body: FootnoteBody,
+
+    /// The styles at the footnote, with which its entry is laid out in the
+    /// margin.
+    #[internal]
+    #[synthesized]
+    pub styles: Styles,
 }
 
 #[scope]
@@ -121,6 +181,69 @@ impl Packed<FootnoteElem> {
             _ => Ok(self.location().unwrap()),
         }
     }
+
+    /// Display the number of the footnote, which was declared at the given
+    /// location.
+    pub fn display_number(
+        &self,
+        engine: &mut Engine,
+        loc: Location,
+        styles: StyleChain,
+        numbering: &Numbering,
+    ) -> SourceResult<Content> {
+        let notes = engine.introspector.query(&FootnoteElem::elem().select());
+        let declared = |note: &&Packed<FootnoteElem>| note.location() == Some(loc);
+
+        // A reference is numbered like the footnote it refers to.
+        let elem = match self.is_ref() {
+            true => notes
+                .iter()
+                .filter_map(|note| note.to_packed::<FootnoteElem>())
+                .find(declared)
+                .unwrap_or(self),
+            false => self,
+        };
+
+        let series = elem.series(styles);
+        let scope = elem.numbering_scope(styles);
+        if series.is_none() && scope == NumberingScope::Document {
+            return Counter::of(FootnoteElem::elem())
+                .display_at_loc(engine, loc, styles, numbering);
+        }
+
+        // Count the footnotes of the same series (and page) up to this one.
+        let page = engine.introspector.page(loc);
+        let mut number = 0;
+        for note in notes.iter().filter_map(|note| note.to_packed::<FootnoteElem>()) {
+            let Some(other) = note.location() else { continue };
+            if note.is_ref()
+                || note.series(StyleChain::default()) != series
+                || (scope == NumberingScope::Page
+                    && engine.introspector.page(other) != page)
+            {
+                continue;
+            }
+
+            number += 1;
+            if other == loc {
+                break;
+            }
+        }
+
+        let context = Context::new(Some(loc), Some(styles));
+        Ok(numbering.apply(engine, context.track(), &[number.max(1)])?.display())
+    }
+}
+
+impl Synthesize for Packed<FootnoteElem> {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        // Entries at the bottom of the page are laid out by the flow with its
+        // styles, but the page places entries in the margin.
+        if self.margin(styles) {
+            self.push_styles(styles.to_map());
+        }
+        Ok(())
+    }
 }
 
 impl Show for Packed<FootnoteElem> {
@@ -129,8 +252,7 @@ impl Show for Packed<FootnoteElem> {
         let span = self.span();
         let loc = self.declaration_location(engine).at(span)?;
         let numbering = self.numbering(styles);
-        let counter = Counter::of(FootnoteElem::elem());
-        let num = counter.display_at_loc(engine, loc, styles, numbering)?;
+        let num = self.display_number(engine, loc, styles, numbering)?;
         let sup = SuperElem::new(num).pack().spanned(span);
         let loc = loc.variant(1);
         // Add zero-width weak spacing to make the footnote "sticky".
@@ -140,7 +262,9 @@ impl Show for Packed<FootnoteElem> {
 
 impl Count for Packed<FootnoteElem> {
     fn update(&self) -> Option<CounterUpdate> {
-        (!self.is_ref()).then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        // Only the main series is counted by the footnote counter.
+        (!self.is_ref() && self.series(StyleChain::default()).is_none())
+            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
     }
 }
 
@@ -203,6 +327,11 @@ pub struct FootnoteEntry {
 
     /// The separator between the document body and the footnote listing.
     ///
+    /// Each [series]($footnote.series) of footnotes is listed below its own
+    /// separator. To use different separators for them, you can pass a
+    /// function that receives the series (or `{none}` for the main series)
+    /// and returns the separator.
+    ///
     /// ```example
     /// #set footnote.entry(
     ///   separator: repeat[.]
@@ -222,8 +351,9 @@ pub struct FootnoteEntry {
                 ..Default::default()
             })
             .pack()
+            .into()
     )]
-    pub separator: Content,
+    pub separator: FootnoteSeparator,
 
     /// The amount of clearance between the document body and the separator.
     ///
@@ -263,6 +393,35 @@ pub struct FootnoteEntry {
     /// ```
     #[default(Em::new(1.0).into())]
     pub indent: Length,
+
+    /// In how many columns to lay out the footnote listing.
+    ///
+    /// Entries in multiple columns don't break across pages.
+    ///
+    /// ```example
+    /// #set footnote.entry(columns: 2)
+    ///
+    /// Footnotes:
+    /// #footnote[Left],
+    /// #footnote[Right]
+    /// ```
+    #[default(NonZeroUsize::ONE)]
+    pub columns: NonZeroUsize,
+
+    /// Whether to set the entries one after another in a single paragraph
+    /// instead of starting each on its own line. The entries are separated
+    /// by the [gap]($footnote.entry.gap).
+    ///
+    /// ```example
+    /// #set footnote.entry(run-in: true, gap: 1em)
+    ///
+    /// Footnotes:
+    /// #footnote[One],
+    /// #footnote[Two],
+    /// #footnote[Three]
+    /// ```
+    #[default(false)]
+    pub run_in: bool,
 }
 
 impl Show for Packed<FootnoteEntry> {
@@ -273,7 +432,6 @@ impl Show for Packed<FootnoteEntry> {
         let number_gap = Em::new(0.05);
         let default = StyleChain::default();
         let numbering = note.numbering(default);
-        let counter = Counter::of(FootnoteElem::elem());
         let Some(loc) = note.location() else {
             bail!(
                 span, "footnote entry must have a location";
@@ -281,7 +439,7 @@ impl Show for Packed<FootnoteEntry> {
             );
         };
 
-        let num = counter.display_at_loc(engine, loc, styles, numbering)?;
+        let num = note.display_number(engine, loc, styles, numbering)?;
         let sup = SuperElem::new(num)
             .pack()
             .spanned(span)
@@ -291,22 +449,81 @@ impl Show for Packed<FootnoteEntry> {
             HElem::new(self.indent(styles).into()).pack(),
             sup,
             HElem::new(number_gap.into()).with_weak(true).pack(),
+            // Keep the number on the same line as the body in run-in
+            // listings.
+            TextElem::packed('\u{2060}'),
             note.body_content().unwrap().clone(),
         ]))
     }
 }
 
+impl FootnoteEntry {
+    /// The leading of the lines of footnote entries.
+    ///
+    /// This is applied around the listing instead of in the show-set rule
+    /// because a paragraph property on each entry would put the entries of a
+    /// run-in listing into separate paragraphs.
+    pub(crate) fn leading() -> Property {
+        ParElem::set_leading(Em::new(0.5).into())
+    }
+}
+
 impl ShowSet for Packed<FootnoteEntry> {
     fn show_set(&self, _: StyleChain) -> Styles {
         let text_size = Em::new(0.85);
-        let leading = Em::new(0.5);
         let mut out = Styles::new();
-        out.set(ParElem::set_leading(leading.into()));
         out.set(TextElem::set_size(TextSize(text_size.into())));
+        out.set(ParLine::set_numbering(None));
         out
     }
 }
 
+/// The separator above a footnote listing.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum FootnoteSeparator {
+    /// The same separator for all series.
+    Content(Content),
+    /// A function that returns the separator for a series.
+    Func(Func),
+}
+
+impl FootnoteSeparator {
+    /// Resolve the separator for the given series.
+    pub fn resolve(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        series: Option<&EcoString>,
+    ) -> SourceResult<Content> {
+        Ok(match self {
+            Self::Content(content) => content.clone(),
+            Self::Func(func) => func
+                .call(
+                    engine,
+                    Context::new(None, Some(styles)).track(),
+                    [series.cloned()],
+                )?
+                .display(),
+        })
+    }
+}
+
+impl From<Content> for FootnoteSeparator {
+    fn from(content: Content) -> Self {
+        Self::Content(content)
+    }
+}
+
+cast! {
+    FootnoteSeparator,
+    self => match self {
+        Self::Content(v) => v.into_value(),
+        Self::Func(v) => v.into_value(),
+    },
+    v: Content => Self::Content(v),
+    v: Func => Self::Func(v),
+}
+
 cast! {
     FootnoteElem,
     v: Content => v.unpack::<Self>().unwrap_or_else(Self::with_content)
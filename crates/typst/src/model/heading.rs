@@ -10,7 +10,7 @@ use crate::introspection::{Count, Counter, CounterUpdate, Locatable};
 use crate::layout::{
     Abs, Axes, BlockChild, BlockElem, Em, HElem, Length, Regions, VElem,
 };
-use crate::model::{Numbering, Outlinable, ParElem, Refable, Supplement};
+use crate::model::{Numbering, Outlinable, ParElem, ParLine, Refable, Supplement};
 use crate::text::{FontWeight, LocalName, SpaceElem, TextElem, TextSize};
 use crate::utils::NonZeroExt;
 
@@ -274,6 +274,7 @@ impl ShowSet for Packed<HeadingElem> {
         out.set(BlockElem::set_above(VElem::block_around(above.into())));
         out.set(BlockElem::set_below(VElem::block_around(below.into())));
         out.set(BlockElem::set_sticky(true));
+        out.set(ParLine::set_numbering(None));
         out
     }
 }
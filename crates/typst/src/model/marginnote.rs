@@ -0,0 +1,225 @@
+use std::num::NonZeroUsize;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Content, NativeElement, Packed, Show, StyleChain, Styles, Synthesize,
+};
+use crate::introspection::Locatable;
+use crate::layout::{
+    Abs, Axes, Binding, Em, FixedAlignment, FloatSide, Frame, FrameItem, Length, Point,
+    Regions, Sides, Size, Transform,
+};
+use crate::model::dropcap::first_text_baseline;
+use crate::model::{FootnoteElem, FootnoteEntry};
+
+/// A note in the margin of the page.
+///
+/// A margin note is placed in the page's margin next to the line it is
+/// attached to, with its first baseline aligned to the baseline of that line.
+/// Notes that would overlap are moved down, and notes that don't fit onto the
+/// page anymore continue at the top of the margin of the next page.
+///
+/// The note spans the margin except for the
+/// [clearance]($marginnote.clearance) on both of its sides, so you should
+/// make the margin wide enough for it.
+///
+/// To place footnotes in the margin instead of at the bottom of the page, use
+/// the footnote's [`margin`]($footnote.margin) parameter. The settings of
+/// margin notes also apply to such footnotes.
+///
+/// # Example
+/// ```example
+/// #set page(margin: (right: 100pt))
+/// #set par(justify: true)
+///
+/// The sun rises in the east.
+/// #marginnote[Mostly.]
+/// #lorem(20)
+/// ```
+#[elem(Locatable, Synthesize, Show)]
+pub struct MarginnoteElem {
+    /// In which margin to place the note.
+    ///
+    /// Can be `{left}`, `{right}`, `{start}`, or `{end}`. With
+    /// `{"outside"}` and `{"inside"}`, the note is placed in the margin away
+    /// from or towards the page's [binding]($page.binding).
+    ///
+    /// ```example
+    /// #set page(margin: (x: 80pt))
+    /// Here.
+    /// #marginnote(side: left)[Left.]
+    /// #marginnote(side: right)[Right.]
+    /// ```
+    #[default(FloatSide::Outside)]
+    pub side: FloatSide,
+
+    /// The distance between the note and the page's content as well as the
+    /// edge of the page.
+    #[resolve]
+    #[default(Em::new(1.0).into())]
+    pub clearance: Length,
+
+    /// The minimum gap between two notes in the same margin.
+    #[resolve]
+    #[default(Em::new(0.5).into())]
+    pub gap: Length,
+
+    /// The content of the note.
+    #[required]
+    pub body: Content,
+
+    /// The styles at the note, with which it is laid out.
+    #[internal]
+    #[synthesized]
+    pub styles: Styles,
+}
+
+impl Synthesize for Packed<MarginnoteElem> {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        self.push_styles(styles.to_map());
+        Ok(())
+    }
+}
+
+impl Show for Packed<MarginnoteElem> {
+    #[typst_macros::time(name = "marginnote", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        // The note itself is placed by the page once the position of its
+        // line is known.
+        Ok(Content::empty())
+    }
+}
+
+/// Places the margin notes of the pages of a page run.
+pub(crate) struct MarginLayouter {
+    /// The binding of the pages.
+    binding: Binding,
+    /// Notes that didn't fit onto the previous page.
+    carried: Vec<Content>,
+}
+
+impl MarginLayouter {
+    /// Create a new layouter for pages with the given binding.
+    pub fn new(binding: Binding) -> Self {
+        Self { binding, carried: vec![] }
+    }
+
+    /// Whether notes didn't fit onto the previous page and need another one.
+    pub fn has_carried(&self) -> bool {
+        !self.carried.is_empty()
+    }
+
+    /// Place the margin notes found in a page frame with realized margins.
+    ///
+    /// Notes that don't fit onto the page are carried over to the next one.
+    pub fn layout(
+        &mut self,
+        engine: &mut Engine,
+        frame: &mut Frame,
+        margin: Sides<Abs>,
+        number: NonZeroUsize,
+    ) -> SourceResult<()> {
+        let mut found = vec![];
+        find_notes(&mut found, frame, Transform::identity());
+        found.sort_by_key(|&(anchor, _)| anchor);
+
+        let mut notes: Vec<_> =
+            self.carried.drain(..).map(|elem| (margin.top, elem)).collect();
+        notes.extend(found);
+        if notes.is_empty() {
+            return Ok(());
+        }
+
+        let bottom = frame.height() - margin.bottom;
+        let mut cursors = (margin.top, margin.top);
+        for (anchor, elem) in notes {
+            let local = note_styles(&elem);
+            let styles = StyleChain::new(&local);
+            let (body, side, clearance, gap) = resolve_note(&elem, styles);
+
+            let left =
+                side.resolve(self.binding, number, styles) == FixedAlignment::Start;
+            let space = if left { margin.left } else { margin.right };
+            let width = (space - 2.0 * clearance).max(Abs::zero());
+            let pod = Regions::one(Size::new(width, Abs::inf()), Axes::new(true, false));
+            let note = body.layout(engine, styles, pod)?.into_frame();
+
+            // Align the note's first baseline with the baseline of its line,
+            // but don't let it overlap the previous note in the same margin. A
+            // note at the top of the margin is placed even if it is too long,
+            // so that every page takes at least one note.
+            let cursor = if left { &mut cursors.0 } else { &mut cursors.1 };
+            let ascent = first_text_baseline(&note).unwrap_or_default();
+            let y = (anchor - ascent).max(*cursor);
+            if y > margin.top && y + note.height() > bottom {
+                // Keep the order of the notes by carrying all following
+                // notes in this margin over as well.
+                *cursor = Abs::inf();
+                self.carried.push(elem);
+                continue;
+            }
+
+            let x =
+                if left { clearance } else { frame.width() - margin.right + clearance };
+            *cursor = y + note.height() + gap;
+            frame.push_frame(Point::new(x, y), note);
+        }
+
+        Ok(())
+    }
+}
+
+/// The styles at a margin note or footnote, with which it is laid out.
+fn note_styles(elem: &Content) -> Styles {
+    let styles = match elem.to_packed::<MarginnoteElem>() {
+        Some(note) => note.styles(),
+        None => elem.to_packed::<FootnoteElem>().unwrap().styles(),
+    };
+    styles.cloned().unwrap_or_default()
+}
+
+/// Determine the content and settings of a margin note or footnote.
+fn resolve_note(elem: &Content, styles: StyleChain) -> (Content, FloatSide, Abs, Abs) {
+    match elem.to_packed::<MarginnoteElem>() {
+        Some(note) => (
+            note.body().clone(),
+            note.side(styles),
+            note.clearance(styles),
+            note.gap(styles),
+        ),
+        None => (
+            FootnoteEntry::new(elem.to_packed::<FootnoteElem>().unwrap().clone()).pack(),
+            MarginnoteElem::side_in(styles),
+            MarginnoteElem::clearance_in(styles),
+            MarginnoteElem::gap_in(styles),
+        ),
+    }
+}
+
+/// Finds the margin notes and the footnotes to be placed in the margin in the
+/// frame together with the vertical position of their line.
+fn find_notes(notes: &mut Vec<(Abs, Content)>, frame: &Frame, ts: Transform) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                find_notes(notes, &group.frame, ts);
+            }
+            FrameItem::Tag(elem) if is_margin_note(elem) => {
+                notes.push((pos.transform(ts).y, elem.clone()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the element is placed in the margin.
+fn is_margin_note(elem: &Content) -> bool {
+    elem.is::<MarginnoteElem>()
+        || elem
+            .to_packed::<FootnoteElem>()
+            .is_some_and(|note| !note.is_ref() && note.margin(StyleChain::default()))
+}
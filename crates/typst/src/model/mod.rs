@@ -3,6 +3,7 @@
 mod bibliography;
 mod cite;
 mod document;
+mod dropcap;
 mod emph;
 #[path = "enum.rs"]
 mod enum_;
@@ -11,6 +12,7 @@ mod footnote;
 mod heading;
 mod link;
 mod list;
+mod marginnote;
 #[path = "numbering.rs"]
 mod numbering_;
 mod outline;
@@ -24,6 +26,7 @@ mod terms;
 pub use self::bibliography::*;
 pub use self::cite::*;
 pub use self::document::*;
+pub use self::dropcap::*;
 pub use self::emph::*;
 pub use self::enum_::*;
 pub use self::figure::*;
@@ -31,6 +34,7 @@ pub use self::footnote::*;
 pub use self::heading::*;
 pub use self::link::*;
 pub use self::list::*;
+pub use self::marginnote::*;
 pub use self::numbering_::*;
 pub use self::outline::*;
 pub use self::par::*;
@@ -60,6 +64,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<HeadingElem>();
     global.define_elem::<FigureElem>();
     global.define_elem::<FootnoteElem>();
+    global.define_elem::<MarginnoteElem>();
     global.define_elem::<QuoteElem>();
     global.define_elem::<CiteElem>();
     global.define_elem::<BibliographyElem>();
@@ -67,6 +72,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<ListElem>();
     global.define_elem::<ParbreakElem>();
     global.define_elem::<ParElem>();
+    global.define_elem::<DropcapElem>();
     global.define_elem::<TableElem>();
     global.define_elem::<TermsElem>();
     global.define_elem::<EmphElem>();
@@ -7,7 +7,7 @@ use smallvec::{smallvec, SmallVec};
 
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{cast, func, Context, Func, Str, Value};
+use crate::foundations::{cast, func, Cast, Context, Func, Str, Value};
 use crate::text::Case;
 
 /// Applies a numbering to a sequence of numbers.
@@ -121,6 +121,15 @@ cast! {
     v: Func => Self::Func(v),
 }
 
+/// Whether numbers count through the whole document or restart on each page.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum NumberingScope {
+    /// Numbers count through the whole document.
+    Document,
+    /// Numbers restart on each page.
+    Page,
+}
+
 /// How to turn a number into text.
 ///
 /// A pattern consists of a prefix, followed by one of
@@ -1,13 +1,24 @@
+use std::collections::HashMap;
 use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
 
-use crate::diag::SourceResult;
+use comemo::{Track, Tracked};
+
+use crate::diag::{bail, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Args, Cast, Construct, Content, NativeElement, Packed, Set, Smart, StyleChain,
-    Unlabellable,
+    cast, dict, elem, scope, Args, Cast, Construct, Content, Context, Dict,
+    NativeElement, Packed, Repr, Set, Show, Smart, StyleChain, Unlabellable,
+};
+use crate::introspection::{
+    Count, Counter, CounterUpdate, Introspector, Locatable, Location,
 };
-use crate::layout::{Em, Fragment, Length, Size};
+use crate::layout::{Abs, Em, Fragment, Length, OuterHAlignment, Ratio, Size};
+use crate::model::{Numbering, NumberingScope};
 use crate::realize::StyleVec;
+use crate::text::LocalName;
+use crate::utils::NonZeroExt;
 
 /// Arranges text, spacing and inline-level elements into a paragraph.
 ///
@@ -34,7 +45,7 @@ use crate::realize::StyleVec;
 /// let $a$ be the smallest of the
 /// three integers. Then, we ...
 /// ```
-#[elem(title = "Paragraph", Debug, Construct)]
+#[elem(scope, title = "Paragraph", Debug, Construct)]
 pub struct ParElem {
     /// The spacing between lines.
     #[resolve]
@@ -97,6 +108,108 @@ pub struct ParElem {
     #[resolve]
     pub hanging_indent: Length,
 
+    /// How far characters at the start and end of lines protrude into the
+    /// margins.
+    ///
+    /// Letting punctuation and hyphens hang slightly into the margin makes the
+    /// edges of a paragraph look straighter (margin kerning). When set to
+    /// `{auto}`, hyphens, dashes, and punctuation at the end of a line
+    /// protrude into the margin. In Chinese and Japanese text, full-width
+    /// commas, periods, and closing brackets additionally protrude by half of
+    /// their width. Quotation marks don't protrude by default since their
+    /// shapes differ between languages, but can be added.
+    ///
+    /// A dictionary maps characters to how far they protrude at the `start`
+    /// and the `end` of a line, relative to their width. Its entries take
+    /// precedence over the defaults. Protrusion can be disabled for parts of
+    /// the text with the text function's [`overhang`]($text.overhang) property.
+    ///
+    /// ```example
+    /// #set par(justify: true, protrusion: (
+    ///   "“": (start: 50%),
+    ///   "”": (end: 50%),
+    /// ))
+    /// “This justified quote hangs
+    /// its quotation marks into the
+    /// margins on both sides of the
+    /// paragraph to straighten it.”
+    /// ```
+    #[ghost]
+    pub protrusion: Smart<Protrusion>,
+
+    /// How much the glyphs' widths may be stretched or shrunk to justify a
+    /// line (font expansion).
+    ///
+    /// With font expansion, the extra space of a justified line is
+    /// distributed to both the spaces and the glyphs, which are scaled
+    /// horizontally up to this ratio. This keeps the word spacing more
+    /// even. The line breaking algorithm takes this additional flexibility
+    /// into account. Values of up to `{2%}` are usually unnoticeable and at
+    /// most `{10%}` is allowed.
+    ///
+    /// ```example
+    /// #set par(justify: true, expansion: 2%)
+    /// Font expansion subtly widens or
+    /// narrows the glyphs to avoid very
+    /// loose or very tight word spacing.
+    /// ```
+    #[ghost]
+    pub expansion: Expansion,
+
+    /// How much of the extra space of a justified line is filled with
+    /// kashidas instead of wider spaces.
+    ///
+    /// In Arabic script, lines are traditionally justified by elongating the
+    /// connections between letters with kashidas (tatweel) rather than by
+    /// stretching the spaces. At most one kashida is inserted per word. It
+    /// goes where it looks most natural, e.g. after a seen or sad or before a
+    /// final heh or teh marbuta. Each kashida is at most half an em long, so
+    /// lines with few kashida positions still stretch their spaces. Otherwise,
+    /// with `{100%}`, the spaces of lines with such positions keep their
+    /// natural width.
+    ///
+    /// ```example
+    /// #set page(width: 140pt)
+    /// #set text(lang: "ar", font: "Noto Sans Arabic")
+    /// #set par(justify: true, kashida: 75%)
+    /// هذا نص عربي مضبوط بالكشيدة بدلا من المسافات الواسعة بين الكلمات.
+    /// ```
+    #[ghost]
+    pub kashida: Ratio,
+
+    /// The minimum number of lines of a paragraph that must remain at the
+    /// bottom of a page or column before a break.
+    ///
+    /// If fewer lines would be left behind, they are moved to the next page
+    /// together with the rest of the paragraph. Prevention of orphans can be
+    /// turned off entirely with the text function's [`costs`]($text.costs).
+    ///
+    /// ```example
+    /// #set page(height: 60pt)
+    /// #set par(orphans: 3)
+    /// #v(16pt)
+    /// #lorem(16)
+    /// ```
+    #[ghost]
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub orphans: NonZeroUsize,
+
+    /// The minimum number of lines of a paragraph that must be carried over to
+    /// the top of the next page or column after a break.
+    ///
+    /// If fewer lines would be carried over, more lines are moved to the next
+    /// page. Prevention of widows can be turned off entirely with the text
+    /// function's [`costs`]($text.costs).
+    ///
+    /// ```example
+    /// #set page(height: 60pt)
+    /// #set par(widows: 3)
+    /// #lorem(16)
+    /// ```
+    #[ghost]
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub widows: NonZeroUsize,
+
     /// Indicates wheter an overflowing line should be shrunk.
     ///
     /// This property is set to `false` on raw blocks, because shrinking a line
@@ -117,6 +230,12 @@ pub struct ParElem {
     pub children: StyleVec,
 }
 
+#[scope]
+impl ParElem {
+    #[elem]
+    type ParLine;
+}
+
 impl Construct for ParElem {
     fn construct(engine: &mut Engine, args: &mut Args) -> SourceResult<Content> {
         // The paragraph constructor is special: It doesn't create a paragraph
@@ -134,6 +253,8 @@ impl Construct for ParElem {
 
 impl Packed<ParElem> {
     /// Layout the paragraph into a collection of lines.
+    ///
+    /// The first lines are shortened by the given left and right `insets`.
     #[typst_macros::time(name = "par", span = self.span())]
     pub fn layout(
         &self,
@@ -141,6 +262,7 @@ impl Packed<ParElem> {
         styles: StyleChain,
         consecutive: bool,
         region: Size,
+        insets: &[(Abs, Abs)],
         expand: bool,
     ) -> SourceResult<Fragment> {
         crate::layout::layout_inline(
@@ -149,6 +271,7 @@ impl Packed<ParElem> {
             styles,
             consecutive,
             region,
+            insets,
             expand,
         )
     }
@@ -161,6 +284,263 @@ impl Debug for ParElem {
     }
 }
 
+/// A line of a paragraph.
+///
+/// This element is used to number the lines of paragraphs with set rules. It
+/// cannot be constructed manually. Line numbers are displayed in the margin
+/// next to each line of a paragraph. The lines of headings, table cells,
+/// figures and their captions, and footnotes are not numbered, and numbering
+/// can be disabled for any content with `{set par.line(numbering: none)}`.
+///
+/// # Example
+/// ```example
+/// #set page(margin: (left: 40pt))
+/// #set par.line(numbering: "1")
+///
+/// Roses are red. \
+/// Violets are blue. \
+/// Typst is there for you.
+/// ```
+///
+/// # Restarting the numbering
+/// With the `{"document"}` [scope]($par.line.numbering-scope), lines are
+/// counted through the whole document with the `{counter(par.line)}`. To
+/// restart the numbering, e.g. for each section, update the counter:
+///
+/// ```example
+/// #set page(margin: (left: 40pt))
+/// #set par.line(numbering: "1")
+/// #show heading: it => {
+///   counter(par.line).update(0)
+///   it
+/// }
+///
+/// = Act I
+/// Enter the ghost.
+///
+/// = Act II
+/// Enter the ghost again.
+/// ```
+///
+/// # Referencing lines
+/// A reference to a label that is attached to an element in a numbered line
+/// displays the line's number.
+///
+/// ```example
+/// #set page(margin: (left: 40pt))
+/// #set par.line(numbering: "1")
+///
+/// Roses are red. \
+/// Violets are blue. #metadata(none) <blue> \
+/// See @blue for the color.
+/// ```
+#[elem(
+    name = "line",
+    title = "Paragraph Line",
+    Construct,
+    Locatable,
+    Count,
+    Show,
+    LocalName
+)]
+pub struct ParLine {
+    /// How to number the lines of paragraphs.
+    ///
+    /// Accepts a [numbering pattern or function]($numbering). If `{none}`,
+    /// lines are not numbered.
+    #[borrowed]
+    pub numbering: Option<Numbering>,
+
+    /// The margin at which line numbers are displayed.
+    ///
+    /// ```example
+    /// #set page(margin: (right: 40pt))
+    /// #set par.line(numbering: "i", number-margin: right)
+    ///
+    /// Numbered \
+    /// on the right.
+    /// ```
+    #[default(OuterHAlignment::Start)]
+    pub number_margin: OuterHAlignment,
+
+    /// The distance between line numbers and the text.
+    #[resolve]
+    #[default(Em::new(1.0).into())]
+    pub number_clearance: Length,
+
+    /// Whether line numbers count through the whole document or restart on
+    /// each page.
+    #[default(NumberingScope::Document)]
+    pub numbering_scope: NumberingScope,
+
+    /// The locations of the labelled elements in the line.
+    #[internal]
+    #[borrowed]
+    pub targets: Vec<Location>,
+}
+
+impl Construct for ParLine {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually");
+    }
+}
+
+impl Show for Packed<ParLine> {
+    #[typst_macros::time(name = "par.line", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        match self.numbering(styles) {
+            Some(numbering) => self.display_number(engine, styles, numbering),
+            None => Ok(Content::empty()),
+        }
+    }
+}
+
+impl Count for Packed<ParLine> {
+    fn update(&self) -> Option<CounterUpdate> {
+        (**self)
+            .numbering(StyleChain::default())
+            .is_some()
+            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+}
+
+impl LocalName for Packed<ParLine> {
+    const KEY: &'static str = "line";
+}
+
+impl Packed<ParLine> {
+    /// Display the number of the line with the given numbering.
+    pub fn display_number(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        numbering: &Numbering,
+    ) -> SourceResult<Content> {
+        let loc = self.location().unwrap();
+        match self.numbering_scope(styles) {
+            NumberingScope::Document => Counter::of(ParLine::elem())
+                .display_at_loc(engine, loc, styles, numbering),
+            NumberingScope::Page => {
+                let numbers = page_line_numbers(engine.introspector);
+                let number = numbers.get(&loc).copied().unwrap_or(1);
+                let context = Context::new(Some(loc), Some(styles));
+                Ok(numbering.apply(engine, context.track(), &[number])?.display())
+            }
+        }
+    }
+
+    /// Find the numbered line that contains the labelled element with the
+    /// given location.
+    pub fn find(engine: &Engine, location: Location) -> Option<Packed<ParLine>> {
+        engine
+            .introspector
+            .query(&ParLine::elem().select())
+            .iter()
+            .filter_map(|line| line.to_packed::<ParLine>())
+            .find(|line| line.targets(StyleChain::default()).contains(&location))
+            .cloned()
+    }
+}
+
+/// Number the lines of all pages, restarting at one on each page.
+///
+/// This is computed once for the whole document instead of counting the
+/// preceding lines on the page for each line.
+#[comemo::memoize]
+fn page_line_numbers(
+    introspector: Tracked<Introspector>,
+) -> Arc<HashMap<Location, usize>> {
+    let mut numbers = HashMap::new();
+    let mut current = None;
+    let mut number = 0;
+    let lines = introspector.query(&ParLine::elem().select());
+    for loc in lines.iter().filter_map(Content::location) {
+        let page = introspector.page(loc);
+        if current != Some(page) {
+            current = Some(page);
+            number = 0;
+        }
+        number += 1;
+        numbers.insert(loc, number);
+    }
+    Arc::new(numbers)
+}
+
+/// How far characters protrude into the margins at the start and end of
+/// lines.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct Protrusion(pub Vec<(char, ProtrusionAmount)>);
+
+impl Protrusion {
+    /// How far the given character protrudes, if it has an entry.
+    pub fn get(&self, c: char) -> Option<ProtrusionAmount> {
+        self.0.iter().find(|&&(k, _)| k == c).map(|&(_, amount)| amount)
+    }
+}
+
+cast! {
+    Protrusion,
+    self => self.0
+        .into_iter()
+        .map(|(c, amount)| (c.into(), amount.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| {
+            let mut chars = k.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                bail!("expected a single character, found {}", k.repr());
+            };
+            Ok((c, v.cast()?))
+        })
+        .collect::<HintedStrResult<_>>()?),
+}
+
+/// How far a character protrudes into the margins, relative to its width.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ProtrusionAmount {
+    /// The protrusion at the start of a line.
+    pub start: Ratio,
+    /// The protrusion at the end of a line.
+    pub end: Ratio,
+}
+
+cast! {
+    ProtrusionAmount,
+    self => dict!["start" => self.start, "end" => self.end].into_value(),
+    v: Ratio => Self { start: v, end: v },
+    mut v: Dict => {
+        let ret = Self {
+            start: v.take("start").ok().map(|v| v.cast()).transpose()?.unwrap_or_default(),
+            end: v.take("end").ok().map(|v| v.cast()).transpose()?.unwrap_or_default(),
+        };
+        v.finish(&["start", "end"])?;
+        ret
+    },
+}
+
+/// How much glyphs may be scaled horizontally to justify a line.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Expansion(Ratio);
+
+impl Expansion {
+    /// The largest scaling, guaranteed to be between 0 and 0.1.
+    pub fn get(self) -> f64 {
+        self.0.get()
+    }
+}
+
+cast! {
+    Expansion,
+    self => self.0.into_value(),
+    v: Ratio => if (0.0..=0.1).contains(&v.get()) {
+        Self(v)
+    } else {
+        bail!("expansion must be between 0% and 10%")
+    },
+}
+
 /// How to determine line breaks in a paragraph.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Linebreaks {
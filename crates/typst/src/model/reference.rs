@@ -10,8 +10,9 @@ use crate::foundations::{
 use crate::introspection::{Counter, Locatable};
 use crate::math::EquationElem;
 use crate::model::{
-    BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, Numbering,
+    BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, Numbering, ParLine,
 };
+use crate::text::LocalName;
 use crate::text::TextElem;
 
 /// A reference to a label or bibliography.
@@ -22,7 +23,9 @@ use crate::text::TextElem;
 /// element. Reference syntax can also be used to [cite] from a bibliography.
 ///
 /// Referenceable elements include [headings]($heading), [figures]($figure),
-/// [equations]($math.equation), and [footnotes]($footnote). To create a custom
+/// [equations]($math.equation), and [footnotes]($footnote). Labelled
+/// elements in [numbered lines]($par.line) are referenced by their line
+/// number. To create a custom
 /// referenceable element like a theorem, you can create a figure of a custom
 /// [`kind`]($figure.kind) and write a show rule for it. In the future, there
 /// might be a more direct way to define a custom referenceable element.
@@ -182,6 +185,23 @@ impl Show for Packed<RefElem> {
         }
 
         let elem = elem.clone();
+
+        // Labelled elements in numbered paragraph lines are referenced by
+        // their line number.
+        let line = (!elem.can::<dyn Refable>())
+            .then(|| elem.location().and_then(|loc| Packed::<ParLine>::find(engine, loc)))
+            .flatten();
+        if let Some(line) = line {
+            let numbering = line.numbering(StyleChain::default()).clone();
+            if let Some(numbering) = numbering {
+                let numbers =
+                    line.display_number(engine, styles, &numbering.trimmed())?;
+                let supplement =
+                    TextElem::packed(Packed::<ParLine>::local_name_in(styles));
+                return self.finish(engine, styles, line.pack(), numbers, supplement);
+            }
+        }
+
         let refable = elem
             .with::<dyn Refable>()
             .ok_or_else(|| {
@@ -220,8 +240,25 @@ impl Show for Packed<RefElem> {
             &numbering.clone().trimmed(),
         )?;
 
+        let supplement = refable.supplement();
+        self.finish(engine, styles, elem, numbers, supplement)
+    }
+}
+
+impl Packed<RefElem> {
+    /// Combine the displayed numbers of the referenced element with the
+    /// supplement and link them to the element.
+    fn finish(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        elem: Content,
+        numbers: Content,
+        default: Content,
+    ) -> SourceResult<Content> {
+        let loc = elem.location().unwrap();
         let supplement = match self.supplement(styles).as_ref() {
-            Smart::Auto => refable.supplement(),
+            Smart::Auto => default,
             Smart::Custom(None) => Content::empty(),
             Smart::Custom(Some(supplement)) => {
                 supplement.resolve(engine, styles, [elem])?
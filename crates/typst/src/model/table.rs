@@ -6,7 +6,8 @@ use ecow::eco_format;
 use crate::diag::{bail, HintedStrResult, HintedString, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Fold, NativeElement, Packed, Show, Smart, StyleChain,
+    cast, elem, scope, Content, Fold, NativeElement, Packed, Show, ShowSet, Smart,
+    StyleChain, Styles,
 };
 use crate::layout::{
     show_grid_cell, Abs, Alignment, Axes, BlockElem, Cell, CellGrid, Celled, Dir,
@@ -14,7 +15,7 @@ use crate::layout::{
     Length, LinePosition, OuterHAlignment, OuterVAlignment, Regions, Rel, ResolvableCell,
     ResolvableGridChild, ResolvableGridItem, Sides, TrackSizings,
 };
-use crate::model::Figurable;
+use crate::model::{Figurable, ParLine};
 use crate::syntax::Span;
 use crate::text::{LocalName, TextElem};
 use crate::utils::NonZeroExt;
@@ -120,7 +121,7 @@ use crate::visualize::{Paint, Stroke};
 ///   [Robert], b, a, b,
 /// )
 /// ```
-#[elem(scope, Show, LocalName, Figurable)]
+#[elem(scope, Show, ShowSet, LocalName, Figurable)]
 pub struct TableElem {
     /// The column sizes. See the [grid documentation]($grid) for more
     /// information on track sizing.
@@ -266,6 +267,15 @@ impl Show for Packed<TableElem> {
     }
 }
 
+impl ShowSet for Packed<TableElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        // Lines in cells are not numbered.
+        let mut out = Styles::new();
+        out.set(ParLine::set_numbering(None));
+        out
+    }
+}
+
 /// Layout the table.
 #[typst_macros::time(span = elem.span())]
 fn layout_table(
@@ -5,7 +5,7 @@ use crate::foundations::{
     Styles,
 };
 use crate::layout::{
-    BlockElem, Dir, Em, HElem, Length, Sides, Spacing, StackChild, StackElem, VElem,
+    BlockElem, Em, HElem, Length, Sides, Spacing, StackChild, StackElem, VElem,
 };
 use crate::model::ParElem;
 use crate::text::TextElem;
@@ -136,7 +136,7 @@ impl Show for Packed<TermsElem> {
         }
 
         let mut padding = Sides::default();
-        if TextElem::dir_in(styles) == Dir::LTR {
+        if TextElem::dir_in(styles).is_positive() {
             padding.left = pad.into();
         } else {
             padding.right = pad.into();
@@ -28,8 +28,8 @@ use crate::layout::{
 };
 use crate::math::{EquationElem, LayoutMath};
 use crate::model::{
-    CiteElem, CiteGroup, DocumentElem, EnumElem, EnumItem, ListElem, ListItem, ParElem,
-    ParbreakElem, TermItem, TermsElem,
+    CiteElem, CiteGroup, DocumentElem, DropcapInitialElem, EnumElem, EnumItem, ListElem,
+    ListItem, ParElem, ParbreakElem, TermItem, TermsElem,
 };
 use crate::syntax::Span;
 use crate::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
@@ -442,6 +442,7 @@ impl<'a> ParBuilder<'a> {
             || content.is::<SmartQuoteElem>()
             || content.is::<InlineElem>()
             || content.is::<BoxElem>()
+            || content.is::<DropcapInitialElem>()
         {
             self.0.push(content, styles);
             return true;
@@ -1,12 +1,15 @@
 use std::cmp::Reverse;
 use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use ttf_parser::{name_id, PlatformId, Tag};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::exceptions::find_exception;
+use super::woff;
+use crate::layout::Ratio;
 use crate::text::{Font, FontStretch, FontStyle, FontVariant, FontWeight};
 
 /// Metadata about a collection of fonts.
@@ -76,7 +79,7 @@ impl FontBook {
     /// The `family` should be all lowercase.
     pub fn select(&self, family: &str, variant: FontVariant) -> Option<usize> {
         let ids = self.families.get(family)?;
-        self.find_best_variant(None, variant, ids.iter().copied())
+        find_best_variant(None, variant, ids.iter().map(|&id| (id, &self.infos[id])))
     }
 
     /// Iterate over all variants of a family.
@@ -101,73 +104,133 @@ impl FontBook {
     ) -> Option<usize> {
         // Find the fonts that contain the text's first non-space char ...
         let c = text.chars().find(|c| !c.is_whitespace())?;
-        let ids = self
+        let infos = self
             .infos
             .iter()
             .enumerate()
-            .filter(|(_, info)| info.coverage.contains(c as u32))
-            .map(|(index, _)| index);
+            .filter(|(_, info)| info.coverage.contains(c as u32));
 
         // ... and find the best variant among them.
-        self.find_best_variant(like, variant, ids)
+        find_best_variant(like, variant, infos)
     }
+}
 
-    /// Find the font in the passed iterator that
-    /// - is closest to the font `like` (if any)
-    /// - is closest to the given `variant`
-    ///
-    /// To do that we compute a key for all variants and select the one with the
-    /// minimal key. This key prioritizes:
-    /// - If `like` is some other font:
-    ///   - Are both fonts (not) monospaced?
-    ///   - Do both fonts (not) have serifs?
-    ///   - How many words do the families share in their prefix? E.g. "Noto
-    ///     Sans" and "Noto Sans Arabic" share two words, whereas "IBM Plex
-    ///     Arabic" shares none with "Noto Sans", so prefer "Noto Sans Arabic"
-    ///     if `like` is "Noto Sans". In case there are two equally good
-    ///     matches, we prefer the shorter one because it is less special (e.g.
-    ///     if `like` is "Noto Sans Arabic", we prefer "Noto Sans" over "Noto
-    ///     Sans CJK HK".)
-    /// - The style (normal / italic / oblique). If we want italic or oblique
-    ///   but it doesn't exist, the other one of the two is still better than
-    ///   normal.
-    /// - The absolute distance to the target stretch.
-    /// - The absolute distance to the target weight.
-    fn find_best_variant(
+/// A font book whose fonts are layered over those of another book.
+///
+/// Indices below the number of fonts in the top book refer to its fonts and
+/// the remaining ones to the fonts of the base book, shifted by that number.
+/// If fonts from both books match equally well, the one from the top book is
+/// selected.
+#[derive(Copy, Clone)]
+pub(crate) struct LayeredFontBook<'a> {
+    /// The fonts that take precedence.
+    pub top: &'a FontBook,
+    /// The fonts below them.
+    pub base: &'a FontBook,
+}
+
+impl LayeredFontBook<'_> {
+    /// Get the font info for the given index.
+    pub fn info(&self, index: usize) -> Option<&FontInfo> {
+        let len = self.top.infos.len();
+        if index < len {
+            self.top.info(index)
+        } else {
+            self.base.info(index - len)
+        }
+    }
+
+    /// Iterate over all variants of a family in both books.
+    pub fn select_family(&self, family: &str) -> impl Iterator<Item = usize> + '_ {
+        let len = self.top.infos.len();
+        self.top
+            .select_family(family)
+            .chain(self.base.select_family(family).map(move |id| id + len))
+    }
+
+    /// Try to find a font from the given `family` in both books. See
+    /// [`FontBook::select`] for details.
+    pub fn select(&self, family: &str, variant: FontVariant) -> Option<usize> {
+        let infos = self.select_family(family).map(|id| (id, self.info(id).unwrap()));
+        find_best_variant(None, variant, infos)
+    }
+
+    /// Try to find a fallback font in both books. See
+    /// [`FontBook::select_fallback`] for details.
+    pub fn select_fallback(
         &self,
         like: Option<&FontInfo>,
         variant: FontVariant,
-        ids: impl IntoIterator<Item = usize>,
+        text: &str,
     ) -> Option<usize> {
-        let mut best = None;
-        let mut best_key = None;
-
-        for id in ids {
-            let current = &self.infos[id];
-            let key = (
-                like.map(|like| {
-                    (
-                        current.flags.contains(FontFlags::MONOSPACE)
-                            != like.flags.contains(FontFlags::MONOSPACE),
-                        current.flags.contains(FontFlags::SERIF)
-                            != like.flags.contains(FontFlags::SERIF),
-                        Reverse(shared_prefix_words(&current.family, &like.family)),
-                        current.family.len(),
-                    )
-                }),
-                current.variant.style.distance(variant.style),
-                current.variant.stretch.distance(variant.stretch),
-                current.variant.weight.distance(variant.weight),
-            );
-
-            if best_key.map_or(true, |b| key < b) {
-                best = Some(id);
-                best_key = Some(key);
-            }
-        }
+        let c = text.chars().find(|c| !c.is_whitespace())?;
+        let infos = self
+            .top
+            .infos
+            .iter()
+            .chain(&self.base.infos)
+            .enumerate()
+            .filter(|(_, info)| info.coverage.contains(c as u32));
+        find_best_variant(like, variant, infos)
+    }
+}
 
-        best
+/// Find the font among the passed ones that
+/// - is closest to the font `like` (if any)
+/// - is closest to the given `variant`
+///
+/// To do that we compute a key for all variants and select the one with the
+/// minimal key. This key prioritizes:
+/// - If `like` is some other font:
+///   - Are both fonts (not) monospaced?
+///   - Do both fonts (not) have serifs?
+///   - How many words do the families share in their prefix? E.g. "Noto
+///     Sans" and "Noto Sans Arabic" share two words, whereas "IBM Plex
+///     Arabic" shares none with "Noto Sans", so prefer "Noto Sans Arabic"
+///     if `like` is "Noto Sans". In case there are two equally good
+///     matches, we prefer the shorter one because it is less special (e.g.
+///     if `like` is "Noto Sans Arabic", we prefer "Noto Sans" over "Noto
+///     Sans CJK HK".)
+/// - The style (normal / italic / oblique). If we want italic or oblique
+///   but it doesn't exist, the other one of the two is still better than
+///   normal.
+/// - The absolute distance to the target stretch.
+/// - The absolute distance to the target weight.
+///
+/// For variable fonts, the distances are measured to the closest value
+/// within the range of the respective axis.
+fn find_best_variant<'a>(
+    like: Option<&FontInfo>,
+    variant: FontVariant,
+    infos: impl IntoIterator<Item = (usize, &'a FontInfo)>,
+) -> Option<usize> {
+    let mut best = None;
+    let mut best_key = None;
+
+    for (id, current) in infos {
+        let key = (
+            like.map(|like| {
+                (
+                    current.flags.contains(FontFlags::MONOSPACE)
+                        != like.flags.contains(FontFlags::MONOSPACE),
+                    current.flags.contains(FontFlags::SERIF)
+                        != like.flags.contains(FontFlags::SERIF),
+                    Reverse(shared_prefix_words(&current.family, &like.family)),
+                    current.family.len(),
+                )
+            }),
+            current.variant.style.distance(variant.style),
+            current.stretch_distance(variant.stretch),
+            current.weight_distance(variant.weight),
+        );
+
+        if best_key.map_or(true, |b| key < b) {
+            best = Some(id);
+            best_key = Some(key);
+        }
     }
+
+    best
 }
 
 /// Properties of a single font.
@@ -182,6 +245,54 @@ pub struct FontInfo {
     pub flags: FontFlags,
     /// The unicode coverage of the font.
     pub coverage: Coverage,
+    /// The font's variation axes. Empty for static fonts.
+    #[serde(default)]
+    pub axes: Vec<FontAxis>,
+}
+
+/// A variation axis of a variable font.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct FontAxis {
+    /// The OpenType tag of the axis, e.g. `wght`.
+    #[serde(serialize_with = "serialize_tag", deserialize_with = "deserialize_tag")]
+    pub tag: Tag,
+    /// The minimum coordinate on the axis.
+    pub min: f32,
+    /// The coordinate of the font's default instance.
+    pub default: f32,
+    /// The maximum coordinate on the axis.
+    pub max: f32,
+}
+
+impl Eq for FontAxis {}
+
+impl PartialEq for FontAxis {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+            && self.min.to_bits() == other.min.to_bits()
+            && self.default.to_bits() == other.default.to_bits()
+            && self.max.to_bits() == other.max.to_bits()
+    }
+}
+
+impl Hash for FontAxis {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+        self.min.to_bits().hash(state);
+        self.default.to_bits().hash(state);
+        self.max.to_bits().hash(state);
+    }
+}
+
+/// Serialize an OpenType tag as a string.
+fn serialize_tag<S: Serializer>(tag: &Tag, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(tag)
+}
+
+/// Deserialize an OpenType tag from a string.
+fn deserialize_tag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Tag, D::Error> {
+    let string = String::deserialize(deserializer)?;
+    Ok(Tag::from_bytes_lossy(string.as_bytes()))
 }
 
 bitflags::bitflags! {
@@ -198,16 +309,63 @@ bitflags::bitflags! {
 }
 
 impl FontInfo {
+    /// The variation axis with the given tag, if the font has one.
+    pub fn axis(&self, tag: Tag) -> Option<&FontAxis> {
+        self.axes.iter().find(|axis| axis.tag == tag)
+    }
+
+    /// The distance of the font's weight to the `target` weight. For fonts with
+    /// a weight axis, this is the distance to the closest weight on the axis.
+    pub fn weight_distance(&self, target: FontWeight) -> u16 {
+        match self.axis(Tag::from_bytes(b"wght")) {
+            Some(axis) => {
+                let number = f32::from(target.to_number()).clamp(axis.min, axis.max);
+                FontWeight::from_number(number as u16).distance(target)
+            }
+            None => self.variant.weight.distance(target),
+        }
+    }
+
+    /// The distance of the font's stretch to the `target` stretch. For fonts
+    /// with a width axis, this is the distance to the closest width on the
+    /// axis.
+    pub fn stretch_distance(&self, target: FontStretch) -> Ratio {
+        match self.axis(Tag::from_bytes(b"wdth")) {
+            Some(axis) => {
+                let percent = (target.to_ratio().get() * 100.0) as f32;
+                let percent = percent.clamp(axis.min, axis.max);
+                FontStretch::from_ratio(Ratio::new(f64::from(percent) / 100.0))
+                    .distance(target)
+            }
+            None => self.variant.stretch.distance(target),
+        }
+    }
+
     /// Compute metadata for font at the `index` of the given data.
+    ///
+    /// WOFF and WOFF2 containers are decompressed automatically.
     pub fn new(data: &[u8], index: u32) -> Option<Self> {
-        let ttf = ttf_parser::Face::parse(data, index).ok()?;
+        let data = woff::decompress(data)?;
+        let ttf = ttf_parser::Face::parse(&data, index).ok()?;
         Self::from_ttf(&ttf)
     }
 
+    /// The number of fonts in the given data, which may also be a collection
+    /// or a WOFF or WOFF2 container.
+    pub fn count(data: &[u8]) -> Option<u32> {
+        let data = woff::decompress(data)?;
+        Some(ttf_parser::fonts_in_collection(&data).unwrap_or(1))
+    }
+
     /// Compute metadata for all fonts in the given data.
     pub fn iter(data: &[u8]) -> impl Iterator<Item = FontInfo> + '_ {
-        let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
-        (0..count).filter_map(move |index| Self::new(data, index))
+        woff::decompress(data).into_iter().flat_map(|data| {
+            let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+            (0..count).filter_map(move |index| {
+                let ttf = ttf_parser::Face::parse(&data, index).ok()?;
+                Self::from_ttf(&ttf)
+            })
+        })
     }
 
     /// Compute metadata for a single ttf-parser face.
@@ -284,11 +442,23 @@ impl FontInfo {
             }
         }
 
+        let axes = ttf
+            .variation_axes()
+            .into_iter()
+            .map(|axis| FontAxis {
+                tag: axis.tag,
+                min: axis.min_value,
+                default: axis.def_value,
+                max: axis.max_value,
+            })
+            .collect();
+
         Some(FontInfo {
             family,
             variant,
             flags,
             coverage: Coverage::from_vec(codepoints),
+            axes,
         })
     }
 }
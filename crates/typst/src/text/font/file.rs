@@ -0,0 +1,115 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{func, repr, scope, ty, Bytes};
+use crate::syntax::Spanned;
+use crate::text::Font;
+use crate::World;
+
+/// Fonts loaded from a file.
+///
+/// This makes fonts that ship alongside a document or a package usable
+/// without having to install them. The path is resolved like other paths in
+/// Typst, so a package can load fonts that are part of the package. A package
+/// can also list font files in the `fonts` array of its manifest. Their
+/// families can then be selected by name anywhere in a document that imports
+/// the package. TrueType and OpenType fonts and collections as well as WOFF
+/// and WOFF2 files are supported. If a collection contains fonts of multiple
+/// families, all of them are made available, in the order in which they
+/// appear in the file.
+///
+/// A font file can be used in place of a family name in the text function's
+/// [`font`]($text.font) parameter. Like for named families, Typst falls back
+/// to the next family in the list if the fonts in the file lack a glyph. The
+/// file's fonts join the installed fonts of the same family, so a file with
+/// just the regular style can be combined with an installed bold style. When
+/// both match equally well, the font from the file is used.
+///
+/// # Example
+/// ```typ
+/// #set text(font: (font-file("fonts/Inter.ttf"), "Linux Libertine"))
+/// ```
+#[ty(scope)]
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct FontFile(Arc<Repr>);
+
+/// The internal representation of a font file.
+#[derive(Eq, PartialEq, Hash)]
+struct Repr {
+    /// The path the file was loaded from.
+    path: EcoString,
+    /// The fonts in the file.
+    fonts: Vec<Font>,
+    /// The distinct lowercased family names of the fonts.
+    families: Vec<EcoString>,
+}
+
+#[scope]
+impl FontFile {
+    /// Loads the fonts from a file.
+    #[func(constructor)]
+    pub fn construct(
+        /// The engine.
+        engine: &mut Engine,
+        /// Path to a font file.
+        path: Spanned<EcoString>,
+    ) -> SourceResult<FontFile> {
+        let Spanned { v: path, span } = path;
+        let id = span.resolve_path(&path).at(span)?;
+        let data = engine.world.file(id).at(span)?;
+        FontFile::new(path, data).at(span)
+    }
+}
+
+impl FontFile {
+    /// Load the fonts from the raw data of a file at the given path.
+    #[comemo::memoize]
+    pub fn new(path: EcoString, data: Bytes) -> StrResult<FontFile> {
+        let fonts: Vec<Font> = Font::iter(data).collect();
+        if fonts.is_empty() {
+            bail!("failed to load font file (unknown or malformed format)");
+        }
+
+        let mut families: Vec<EcoString> = vec![];
+        for font in &fonts {
+            let family = font.info().family.to_lowercase().into();
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        }
+
+        Ok(Self(Arc::new(Repr { path, fonts, families })))
+    }
+
+    /// The path the file was loaded from.
+    pub fn path(&self) -> &EcoString {
+        &self.0.path
+    }
+
+    /// The fonts in the file. Contains at least one font.
+    pub fn fonts(&self) -> &[Font] {
+        &self.0.fonts
+    }
+
+    /// The distinct lowercased family names of the fonts in the file, in the
+    /// order in which they first appear. Contains at least one family.
+    pub fn families(&self) -> &[EcoString] {
+        &self.0.families
+    }
+}
+
+impl Debug for FontFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "FontFile({:?})", self.0.path)
+    }
+}
+
+impl repr::Repr for FontFile {
+    fn repr(&self) -> EcoString {
+        eco_format!("font-file({})", repr::Repr::repr(&self.0.path))
+    }
+}
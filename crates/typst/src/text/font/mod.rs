@@ -4,20 +4,26 @@ pub mod color;
 
 mod book;
 mod exceptions;
+mod file;
 mod variant;
+mod woff;
 
-pub use self::book::{Coverage, FontBook, FontFlags, FontInfo};
+pub(crate) use self::book::LayeredFontBook;
+pub use self::book::{Coverage, FontAxis, FontBook, FontFlags, FontInfo};
+pub use self::file::FontFile;
 pub use self::variant::{FontStretch, FontStyle, FontVariant, FontWeight};
 
+use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use ttf_parser::GlyphId;
+use ttf_parser::{GlyphId, Tag};
 
 use self::book::find_name;
 use crate::foundations::{Bytes, Cast};
 use crate::layout::Em;
+use crate::utils::Scalar;
 
 /// An OpenType font.
 ///
@@ -35,6 +41,8 @@ struct Repr {
     index: u32,
     /// Metadata about the font.
     info: FontInfo,
+    /// The coordinates on the variation axes this font is instantiated at.
+    variations: Vec<(Tag, f32)>,
     /// The font's metrics.
     metrics: FontMetrics,
     /// The underlying ttf-parser face.
@@ -45,7 +53,11 @@ struct Repr {
 
 impl Font {
     /// Parse a font from data and collection index.
+    ///
+    /// WOFF and WOFF2 containers are decompressed automatically.
     pub fn new(data: Bytes, index: u32) -> Option<Self> {
+        let data = decompress(data)?;
+
         // Safety:
         // - The slices's location is stable in memory:
         //   - We don't move the underlying vector
@@ -60,13 +72,68 @@ impl Font {
         let metrics = FontMetrics::from_ttf(&ttf);
         let info = FontInfo::from_ttf(&ttf)?;
 
-        Some(Self(Arc::new(Repr { data, index, info, metrics, ttf, rusty })))
+        Some(Self(Arc::new(Repr {
+            data,
+            index,
+            info,
+            variations: vec![],
+            metrics,
+            ttf,
+            rusty,
+        })))
+    }
+
+    /// Instantiate this font at the given coordinates on its variation axes.
+    ///
+    /// Coordinates for axes the font does not have are ignored and the others
+    /// are clamped to the axes' ranges. If an axis is given multiple times, the
+    /// last coordinate wins. Axes that are not given are set to their default
+    /// value. For static fonts, this returns the font itself.
+    pub fn with_variations(&self, variations: &[(Tag, f32)]) -> Self {
+        let mut coords = vec![];
+        for axis in self.info().axes.iter() {
+            let Some(&(_, value)) =
+                variations.iter().rev().find(|(tag, _)| *tag == axis.tag)
+            else {
+                continue;
+            };
+
+            let value = value.clamp(axis.min, axis.max);
+            if value != axis.default {
+                coords.push((axis.tag, Scalar::new(value.into())));
+            }
+        }
+
+        if coords.len() == self.0.variations.len()
+            && coords
+                .iter()
+                .zip(&self.0.variations)
+                .all(|(&(a, x), &(b, y))| a == b && x.get() as f32 == y)
+        {
+            return self.clone();
+        }
+
+        instantiate(&self.0.data, self.0.index, &self.0.info, &coords)
+    }
+
+    /// The default instance of this font.
+    ///
+    /// Instances share the font program of their default instance, which is
+    /// the font itself for static fonts.
+    pub fn default_instance(&self) -> Self {
+        if self.0.variations.is_empty() {
+            self.clone()
+        } else {
+            self.with_variations(&[])
+        }
     }
 
     /// Parse all fonts in the given data.
     pub fn iter(data: Bytes) -> impl Iterator<Item = Self> {
-        let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
-        (0..count).filter_map(move |index| Self::new(data.clone(), index))
+        decompress(data).into_iter().flat_map(|data| {
+            let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+            (0..count).filter_map(move |index| Self::new(data.clone(), index))
+        })
     }
 
     /// The underlying buffer.
@@ -84,6 +151,14 @@ impl Font {
         &self.0.info
     }
 
+    /// The coordinates on the variation axes this font is instantiated at.
+    ///
+    /// This is empty for static fonts and for variable fonts at their default
+    /// instance.
+    pub fn variations(&self) -> &[(Tag, f32)] {
+        &self.0.variations
+    }
+
     /// The font's metrics.
     pub fn metrics(&self) -> &FontMetrics {
         &self.0.metrics
@@ -127,16 +202,76 @@ impl Font {
     }
 }
 
+/// Decompress the data if it is a WOFF or WOFF2 container.
+fn decompress(data: Bytes) -> Option<Bytes> {
+    match woff::decompress(&data)? {
+        Cow::Borrowed(_) => Some(data),
+        Cow::Owned(sfnt) => Some(sfnt.into()),
+    }
+}
+
+/// Instantiate a variable font at non-default coordinates on its axes.
+#[comemo::memoize]
+fn instantiate(
+    data: &Bytes,
+    index: u32,
+    info: &FontInfo,
+    coords: &[(Tag, Scalar)],
+) -> Font {
+    // Safety: See `Font::new`.
+    let data = data.clone();
+    let slice: &'static [u8] =
+        unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
+
+    // Parsing can't fail since we already parsed the same data successfully
+    // when creating the default instance.
+    let mut ttf = ttf_parser::Face::parse(slice, index).unwrap();
+    let mut rusty = rustybuzz::Face::from_slice(slice, index).unwrap();
+
+    let mut variations = vec![];
+    for &(tag, value) in coords {
+        let value = value.get() as f32;
+        ttf.set_variation(tag, value);
+        variations.push((tag, value));
+    }
+
+    rusty.set_variations(
+        &variations
+            .iter()
+            .map(|&(tag, value)| rustybuzz::Variation { tag, value })
+            .collect::<Vec<_>>(),
+    );
+
+    let metrics = FontMetrics::from_ttf(&ttf);
+    Font(Arc::new(Repr {
+        data,
+        index,
+        info: info.clone(),
+        variations,
+        metrics,
+        ttf,
+        rusty,
+    }))
+}
+
 impl Hash for Font {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.data.hash(state);
         self.0.index.hash(state);
+        for (tag, value) in &self.0.variations {
+            tag.0.hash(state);
+            value.to_bits().hash(state);
+        }
     }
 }
 
 impl Debug for Font {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "Font({}, {:?})", self.info().family, self.info().variant)
+        write!(f, "Font({}, {:?}", self.info().family, self.info().variant)?;
+        for (tag, value) in self.variations() {
+            write!(f, ", {tag}={value}")?;
+        }
+        write!(f, ")")
     }
 }
 
@@ -144,7 +279,9 @@ impl Eq for Font {}
 
 impl PartialEq for Font {
     fn eq(&self, other: &Self) -> bool {
-        self.0.data == other.0.data && self.0.index == other.0.index
+        self.0.data == other.0.data
+            && self.0.index == other.0.index
+            && self.0.variations == other.0.variations
     }
 }
 
@@ -249,3 +386,162 @@ pub enum VerticalFontMetric {
     /// The font's ascender, which typically exceeds the depth of all glyphs.
     Descender,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_variations_static() {
+        let data = typst_dev_assets::get("fonts/Roboto-Regular.ttf").unwrap();
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        assert!(font.info().axes.is_empty());
+
+        let instance = font.with_variations(&[
+            (Tag::from_bytes(b"wght"), 700.0),
+            (Tag::from_bytes(b"opsz"), 12.0),
+        ]);
+        assert!(instance.variations().is_empty());
+        assert_eq!(instance, font);
+    }
+
+    #[test]
+    fn test_with_variations() {
+        let font = Font::new(Bytes::from(variable_font()), 0).unwrap();
+        let wght = Tag::from_bytes(b"wght");
+        assert_eq!(font.info().axes.len(), 1);
+        assert_eq!(font.info().axes[0].tag, wght);
+
+        let glyph = font.ttf().glyph_index('I').unwrap();
+        let instance = |weight: f32| font.with_variations(&[(wght, weight)]);
+        let advance = |font: &Font| font.advance(glyph.0).unwrap();
+        let bbox = |font: &Font| font.ttf().glyph_bounding_box(glyph).unwrap();
+        let shaped = |font: &Font| {
+            let mut buffer = rustybuzz::UnicodeBuffer::new();
+            buffer.push_str("I");
+            rustybuzz::shape(font.rusty(), &[], buffer).glyph_positions()[0].x_advance
+        };
+
+        // The default instance is the font itself.
+        assert_eq!(instance(400.0), font);
+        assert_eq!(advance(&font), Em::new(0.3));
+        assert_eq!(shaped(&font), 300);
+
+        // Advances and outlines change with the weight.
+        let bold = instance(900.0);
+        assert_ne!(bold, font);
+        assert_eq!(bold.variations(), &[(wght, 900.0)]);
+        assert_eq!(advance(&bold), Em::new(0.5));
+        assert_eq!(shaped(&bold), 500);
+        assert_eq!((bbox(&font).x_max, bbox(&bold).x_max), (200, 400));
+
+        let medium = instance(650.0);
+        assert_eq!(advance(&medium), Em::new(0.4));
+        assert_eq!(shaped(&medium), 400);
+        assert_eq!(bbox(&medium).x_max, 300);
+
+        // Coordinates are clamped to the axis.
+        assert_eq!(instance(2000.0), bold);
+
+        // All instances share the font program.
+        assert_eq!(bold.default_instance(), font);
+        assert_eq!(font.default_instance(), font);
+    }
+
+    /// Build a variable font with a `wght` axis from 100 to 900 (default 400)
+    /// and a single glyph for `I`: a stem whose width grows from 100 to 300
+    /// units and whose advance grows from 300 to 500 units at the heaviest
+    /// weight.
+    fn variable_font() -> Vec<u8> {
+        let cat = |parts: &[&[u8]]| parts.concat();
+        let u16s = |values: &[u16]| -> Vec<u8> {
+            values.iter().flat_map(|v| v.to_be_bytes()).collect()
+        };
+        let i16s = |values: &[i16]| -> Vec<u8> {
+            values.iter().flat_map(|v| v.to_be_bytes()).collect()
+        };
+
+        // The stem's outline, with its right edge at points 2 and 3.
+        let glyph = cat(&[
+            &i16s(&[1, 100, 0, 200, 700]),
+            &u16s(&[3, 0]),
+            &[0x01; 4],
+            &i16s(&[100, 0, 100, 0, 0, 700, 0, -700]),
+            &[0; 2],
+        ]);
+
+        // Moves the right edge and the advance by 200 units at the peak.
+        let x_deltas = i16s(&[0, 0, 200, 200, 0, 200, 0, 0]);
+        let deltas = cat(&[&[0x47], &x_deltas, &[0x87]]);
+        let variation =
+            cat(&[&u16s(&[1, 10, deltas.len() as u16, 0x8000, 0x4000]), &deltas]);
+        let gvar = cat(&[
+            &u16s(&[1, 0, 1, 0, 0, 26, 2, 0, 0, 26]),
+            &u16s(&[0, 0, variation.len() as u16 / 2]),
+            &variation,
+        ]);
+
+        // The same change of the advance as an item variation store.
+        let hvar = cat(&[
+            &u16s(&[1, 0, 0, 20, 0, 0, 0, 0, 0, 0]),
+            &u16s(&[1, 0, 12, 1, 0, 22]),
+            &u16s(&[1, 1, 0, 0x4000, 0x4000]),
+            &i16s(&[2, 1, 1, 0, 0, 200]),
+        ]);
+
+        let fvar = cat(&[
+            &u16s(&[1, 0, 16, 2, 1, 20, 0, 8]),
+            b"wght",
+            &u16s(&[100, 0, 400, 0, 900, 0, 0, 256]),
+        ]);
+
+        let family: Vec<u8> =
+            "Variable Test".encode_utf16().flat_map(|c| c.to_be_bytes()).collect();
+        let name =
+            cat(&[&u16s(&[0, 1, 18, 3, 1, 0x409, 1, family.len() as u16, 0]), &family]);
+
+        let cmap = cat(&[
+            &u16s(&[0, 1, 3, 10, 0, 12, 12, 0, 0, 28, 0, 0, 0, 1]),
+            &u16s(&[0, 0x49, 0, 0x49, 0, 1]),
+        ]);
+
+        let mut head =
+            cat(&[&u16s(&[1, 0, 1, 0, 0, 0, 0x5F0F, 0x3CF5, 0, 1000]), &[0; 16]]);
+        head.extend(i16s(&[100, 0, 200, 700, 0, 8, 2, 0, 0]));
+        let hhea = cat(&[
+            &u16s(&[1, 0]),
+            &i16s(&[800, -200, 0, 500, 0, 0, 200, 1, 0, 0]),
+            &[0; 10],
+            &u16s(&[2]),
+        ]);
+
+        let tables: [(&[u8; 4], Vec<u8>); 11] = [
+            (b"HVAR", hvar),
+            (b"cmap", cmap),
+            (b"fvar", fvar),
+            (b"glyf", glyph.clone()),
+            (b"gvar", gvar),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", u16s(&[500, 0, 300, 100])),
+            (b"loca", u16s(&[0, 0, glyph.len() as u16 / 2])),
+            (b"maxp", u16s(&[0, 0x5000, 2])),
+            (b"name", name),
+        ];
+
+        let mut data = cat(&[&u16s(&[1, 0, tables.len() as u16]), &[0; 6]]);
+        let mut offset = 12 + 16 * tables.len();
+        let mut body = vec![];
+        for (tag, table) in &tables {
+            data.extend(*tag);
+            data.extend([0; 4]);
+            data.extend((offset as u32).to_be_bytes());
+            data.extend((table.len() as u32).to_be_bytes());
+            body.extend(table);
+            body.resize(body.len().next_multiple_of(4), 0);
+            offset = 12 + 16 * tables.len() + body.len();
+        }
+        data.extend(body);
+        data
+    }
+}
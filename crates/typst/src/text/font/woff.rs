@@ -0,0 +1,1314 @@
+//! Decompression of WOFF and WOFF2 font containers into raw sfnt data.
+//!
+//! See <https://www.w3.org/TR/WOFF/> and <https://www.w3.org/TR/WOFF2/>.
+
+use std::borrow::Cow;
+use std::io::Read;
+
+/// The signature of a WOFF container.
+const WOFF: &[u8; 4] = b"wOFF";
+
+/// The signature of a WOFF2 container.
+const WOFF2: &[u8; 4] = b"wOF2";
+
+/// The flavor of font collections.
+const TTCF: u32 = u32::from_be_bytes(*b"ttcf");
+
+/// The tags that WOFF2 can encode by their index in this table.
+#[rustfmt::skip]
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post",
+    b"cvt ", b"fpgm", b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT",
+    b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea",
+    b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH",
+    b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar",
+    b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop",
+    b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill",
+];
+
+/// Decompress the data if it is a WOFF or WOFF2 container and return it
+/// unchanged otherwise.
+///
+/// Returns `None` if the data is a malformed container.
+pub fn decompress(data: &[u8]) -> Option<Cow<'_, [u8]>> {
+    match data.get(..4) {
+        Some(signature) if signature == WOFF => decompress_woff(data).map(Cow::Owned),
+        Some(signature) if signature == WOFF2 => decompress_woff2(data).map(Cow::Owned),
+        _ => Some(Cow::Borrowed(data)),
+    }
+}
+
+/// A font table extracted from a container.
+struct Table {
+    tag: [u8; 4],
+    data: Vec<u8>,
+}
+
+/// A font in a container, consisting of the indices of its tables.
+struct Face {
+    flavor: u32,
+    tables: Vec<usize>,
+}
+
+/// Decompress a WOFF container.
+fn decompress_woff(data: &[u8]) -> Option<Vec<u8>> {
+    let mut header = Reader::new(data);
+    header.skip(4)?;
+    let flavor = header.u32()?;
+    header.skip(4)?;
+    let num_tables = header.u16()?;
+    header.skip(30)?;
+    if num_tables == 0 {
+        return None;
+    }
+
+    let mut tables = vec![];
+    for _ in 0..num_tables {
+        let tag = header.tag()?;
+        let offset = header.u32()? as usize;
+        let comp_length = header.u32()? as usize;
+        let orig_length = header.u32()? as usize;
+        header.skip(4)?;
+
+        let compressed = data.get(offset..offset.checked_add(comp_length)?)?;
+        let data = if comp_length < orig_length {
+            // The declared length is untrusted, so the output is grown as it is
+            // decompressed and cut off one byte after the declared length.
+            let mut decoded = vec![];
+            flate2::read::ZlibDecoder::new(compressed)
+                .take(orig_length as u64 + 1)
+                .read_to_end(&mut decoded)
+                .ok()?;
+            decoded
+        } else {
+            compressed.to_vec()
+        };
+
+        if data.len() != orig_length {
+            return None;
+        }
+
+        tables.push(Table { tag, data });
+    }
+
+    let face = Face { flavor, tables: (0..tables.len()).collect() };
+    Some(write_sfnt(&[face], &tables))
+}
+
+/// A table directory entry of a WOFF2 container.
+struct Entry {
+    tag: [u8; 4],
+    transformed: bool,
+    orig_length: usize,
+    length: usize,
+}
+
+/// Decompress a WOFF2 container.
+fn decompress_woff2(data: &[u8]) -> Option<Vec<u8>> {
+    let mut r = Reader::new(data);
+    r.skip(4)?;
+    let flavor = r.u32()?;
+    r.skip(4)?;
+    let num_tables = r.u16()?;
+    r.skip(6)?;
+    let compressed_length = r.u32()? as usize;
+    r.skip(24)?;
+    if num_tables == 0 {
+        return None;
+    }
+
+    // Read the table directory.
+    let mut entries = vec![];
+    for _ in 0..num_tables {
+        let flags = r.u8()?;
+        let tag = match flags & 0x3f {
+            63 => r.tag()?,
+            index => *KNOWN_TAGS[usize::from(index)],
+        };
+
+        // The null transform has version 3 for `glyf` and `loca` and version 0
+        // for all other tables.
+        let version = flags >> 6;
+        let transformed = match &tag {
+            b"glyf" | b"loca" => version != 3,
+            _ => version != 0,
+        };
+
+        let orig_length = r.base128()? as usize;
+        let length = if transformed { r.base128()? as usize } else { orig_length };
+        entries.push(Entry { tag, transformed, orig_length, length });
+    }
+
+    // Read the collection directory, if any.
+    let faces = if flavor == TTCF {
+        r.skip(4)?;
+        let num_fonts = r.u255()?;
+        if num_fonts == 0 {
+            return None;
+        }
+        let mut faces = vec![];
+        for _ in 0..num_fonts {
+            let num_tables = r.u255()?;
+            let flavor = r.u32()?;
+            let tables: Vec<_> = (0..num_tables)
+                .map(|_| r.u255().map(usize::from).filter(|&i| i < entries.len()))
+                .collect::<Option<_>>()?;
+            if tables.is_empty() {
+                return None;
+            }
+            faces.push(Face { flavor, tables });
+        }
+        faces
+    } else {
+        vec![Face { flavor, tables: (0..entries.len()).collect() }]
+    };
+
+    // Decompress the table data, which is stored as one Brotli stream. The
+    // stream must not be longer than the tables it contains.
+    let compressed = data.get(r.offset..r.offset.checked_add(compressed_length)?)?;
+    let total = entries.iter().try_fold(0usize, |sum, e| sum.checked_add(e.length))?;
+    let mut stream = vec![];
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .take(total as u64 + 1)
+        .read_to_end(&mut stream)
+        .ok()?;
+    if stream.len() > total {
+        return None;
+    }
+
+    let mut offset = 0usize;
+    let mut tables = vec![];
+    for entry in &entries {
+        let end = offset.checked_add(entry.length)?;
+        tables.push(Table {
+            tag: entry.tag,
+            data: stream.get(offset..end)?.to_vec(),
+        });
+        offset = end;
+    }
+
+    // Reverse the transforms. The `glyf` table must be reconstructed before
+    // `hmtx` because the latter may need the bounding boxes of the glyphs.
+    let mut x_mins = vec![None; entries.len()];
+    for i in 0..entries.len() {
+        if entries[i].tag != *b"glyf" || !entries[i].transformed {
+            continue;
+        }
+
+        // The transformed `loca` table must directly follow `glyf`.
+        let loca = entries.get(i + 1).filter(|e| e.tag == *b"loca" && e.transformed)?;
+        let glyphs = reconstruct_glyf(&tables[i].data)?;
+        if glyphs.loca.len() != loca.orig_length {
+            return None;
+        }
+
+        tables[i].data = glyphs.glyf;
+        tables[i + 1].data = glyphs.loca;
+        x_mins[i] = Some(glyphs.x_mins);
+    }
+
+    for i in 0..entries.len() {
+        if entries[i].tag != *b"hmtx" || !entries[i].transformed {
+            continue;
+        }
+
+        // Find the `hhea` and `glyf` tables of the face that uses this table.
+        let face = faces.iter().find(|face| face.tables.contains(&i))?;
+        let find =
+            |tag: &[u8; 4]| face.tables.iter().copied().find(|&j| tables[j].tag == *tag);
+        let hhea = find(b"hhea")?;
+        let glyph_x_mins = x_mins[find(b"glyf")?].as_deref()?;
+        let num_h_metrics = Reader::new(&tables[hhea].data).at(34)?.u16()?;
+        tables[i].data = reconstruct_hmtx(&tables[i].data, num_h_metrics, glyph_x_mins)?;
+    }
+
+    Some(write_sfnt(&faces, &tables))
+}
+
+/// The reconstructed `glyf` and `loca` tables.
+struct Glyphs {
+    glyf: Vec<u8>,
+    loca: Vec<u8>,
+    /// The minimum x coordinate of each glyph.
+    x_mins: Vec<i16>,
+}
+
+/// Reconstruct the `glyf` and `loca` tables from a transformed `glyf` table.
+fn reconstruct_glyf(data: &[u8]) -> Option<Glyphs> {
+    let mut header = Reader::new(data);
+    header.skip(2)?;
+    let option_flags = header.u16()?;
+    let num_glyphs = usize::from(header.u16()?);
+    let index_format = header.u16()?;
+
+    // Split the data into its substreams.
+    let mut offset = 36usize;
+    let mut stream = |length: usize| {
+        let end = offset.checked_add(length)?;
+        let reader = Reader::new(data.get(offset..end)?);
+        offset = end;
+        Some(reader)
+    };
+
+    let mut n_contours = stream(header.u32()? as usize)?;
+    let mut n_points = stream(header.u32()? as usize)?;
+    let mut flags = stream(header.u32()? as usize)?;
+    let mut glyphs = stream(header.u32()? as usize)?;
+    let mut composites = stream(header.u32()? as usize)?;
+    let bbox_length = header.u32()? as usize;
+    let bitmap_length = num_glyphs.div_ceil(32) * 4;
+    let bbox_bitmap = stream(bitmap_length)?.data;
+    let mut bboxes = stream(bbox_length.checked_sub(bitmap_length)?)?;
+    let mut instructions = stream(header.u32()? as usize)?;
+    let overlap_bitmap = if option_flags & 1 != 0 {
+        Some(stream(num_glyphs.div_ceil(8))?.data)
+    } else {
+        None
+    };
+
+    let has_bit = |bitmap: &[u8], i: usize| bitmap[i / 8] & (0x80 >> (i % 8)) != 0;
+
+    let mut glyf = vec![];
+    let mut loca = vec![];
+    let mut x_mins = vec![];
+    let write_loca = |loca: &mut Vec<u8>, offset: usize| {
+        if index_format == 0 {
+            loca.extend(((offset / 2) as u16).to_be_bytes());
+        } else {
+            loca.extend((offset as u32).to_be_bytes());
+        }
+    };
+
+    for i in 0..num_glyphs {
+        write_loca(&mut loca, glyf.len());
+
+        let contours = n_contours.i16()?;
+        let explicit_bbox = has_bit(bbox_bitmap, i);
+        let mut bbox = if explicit_bbox {
+            Some([bboxes.i16()?, bboxes.i16()?, bboxes.i16()?, bboxes.i16()?])
+        } else {
+            None
+        };
+
+        if contours == 0 {
+            // An empty glyph must not have a bounding box.
+            if explicit_bbox {
+                return None;
+            }
+            x_mins.push(0);
+            continue;
+        }
+
+        let start = glyf.len();
+        glyf.extend(contours.to_be_bytes());
+        glyf.extend([0; 8]);
+
+        if contours > 0 {
+            // A simple glyph.
+            let mut end_points = vec![];
+            let mut total = 0u16;
+            for _ in 0..contours {
+                total = total.checked_add(n_points.u255()?)?;
+                end_points.push(total.checked_sub(1)?);
+            }
+
+            let points = decode_triplets(&mut flags, &mut glyphs, usize::from(total))?;
+            if bbox.is_none() {
+                bbox = Some(compute_bbox(&points));
+            }
+
+            let overlap = overlap_bitmap.is_some_and(|bitmap| has_bit(bitmap, i));
+
+            let instruction_length = glyphs.u255()?;
+            for end_point in end_points {
+                glyf.extend(end_point.to_be_bytes());
+            }
+            glyf.extend(instruction_length.to_be_bytes());
+            glyf.extend(instructions.take(usize::from(instruction_length))?);
+            encode_points(&mut glyf, &points, overlap);
+        } else {
+            // A composite glyph, which must have an explicit bounding box.
+            if !explicit_bbox {
+                return None;
+            }
+
+            let mut have_instructions = false;
+            loop {
+                // The component's flags and glyph index, followed by its
+                // arguments and transformation whose sizes depend on the flags.
+                let component_flags = composites.u16()?;
+                let mut length = 2;
+                length += if component_flags & 0x0001 != 0 { 4 } else { 2 };
+                if component_flags & 0x0008 != 0 {
+                    length += 2;
+                } else if component_flags & 0x0040 != 0 {
+                    length += 4;
+                } else if component_flags & 0x0080 != 0 {
+                    length += 8;
+                }
+
+                glyf.extend(component_flags.to_be_bytes());
+                glyf.extend(composites.take(length)?);
+                have_instructions |= component_flags & 0x0100 != 0;
+                if component_flags & 0x0020 == 0 {
+                    break;
+                }
+            }
+
+            if have_instructions {
+                let instruction_length = glyphs.u255()?;
+                glyf.extend(instruction_length.to_be_bytes());
+                glyf.extend(instructions.take(usize::from(instruction_length))?);
+            }
+        }
+
+        let bbox = bbox?;
+        for (k, value) in bbox.iter().enumerate() {
+            glyf[start + 2 + 2 * k..start + 4 + 2 * k]
+                .copy_from_slice(&value.to_be_bytes());
+        }
+        x_mins.push(bbox[0]);
+
+        // Pad glyphs to four bytes.
+        glyf.resize(glyf.len().next_multiple_of(4), 0);
+    }
+
+    write_loca(&mut loca, glyf.len());
+
+    Some(Glyphs { glyf, loca, x_mins })
+}
+
+/// A point of a simple glyph.
+struct Point {
+    x: i32,
+    y: i32,
+    on_curve: bool,
+}
+
+/// Decode the triplet-encoded points of a simple glyph.
+fn decode_triplets(
+    flags: &mut Reader,
+    data: &mut Reader,
+    count: usize,
+) -> Option<Vec<Point>> {
+    let with_sign = |flag: u8, value: i32| if flag & 1 != 0 { value } else { -value };
+
+    let mut points = Vec::with_capacity(count);
+    let (mut x, mut y) = (0i32, 0i32);
+    for _ in 0..count {
+        let flag = flags.u8()?;
+        let on_curve = flag >> 7 == 0;
+        let flag = flag & 0x7f;
+
+        let (dx, dy) = if flag < 10 {
+            let b0 = i32::from(data.u8()?);
+            (0, with_sign(flag, (i32::from(flag & 14) << 7) + b0))
+        } else if flag < 20 {
+            let b0 = i32::from(data.u8()?);
+            (with_sign(flag, (i32::from((flag - 10) & 14) << 7) + b0), 0)
+        } else if flag < 84 {
+            let b0 = i32::from(flag - 20);
+            let b1 = i32::from(data.u8()?);
+            (
+                with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+                with_sign(flag >> 1, 1 + ((b0 & 0x0c) << 2) + (b1 & 0x0f)),
+            )
+        } else if flag < 120 {
+            let b0 = i32::from(flag - 84);
+            let (b1, b2) = (i32::from(data.u8()?), i32::from(data.u8()?));
+            (
+                with_sign(flag, 1 + ((b0 / 12) << 8) + b1),
+                with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2),
+            )
+        } else if flag < 124 {
+            let (b1, b2, b3) =
+                (i32::from(data.u8()?), i32::from(data.u8()?), i32::from(data.u8()?));
+            (
+                with_sign(flag, (b1 << 4) + (b2 >> 4)),
+                with_sign(flag >> 1, ((b2 & 0x0f) << 8) + b3),
+            )
+        } else {
+            let (b1, b2) = (i32::from(data.u16()?), i32::from(data.u16()?));
+            (with_sign(flag, b1), with_sign(flag >> 1, b2))
+        };
+
+        x = x.checked_add(dx)?;
+        y = y.checked_add(dy)?;
+        points.push(Point { x, y, on_curve });
+    }
+
+    Some(points)
+}
+
+/// Compute the bounding box of a simple glyph's (non-empty) points.
+fn compute_bbox(points: &[Point]) -> [i16; 4] {
+    let mut bbox = [i16::MAX, i16::MAX, i16::MIN, i16::MIN];
+    for point in points {
+        let (x, y) = (point.x as i16, point.y as i16);
+        bbox = [bbox[0].min(x), bbox[1].min(y), bbox[2].max(x), bbox[3].max(y)];
+    }
+    bbox
+}
+
+/// Encode the flags and coordinates of a simple glyph's points.
+fn encode_points(glyf: &mut Vec<u8>, points: &[Point], overlap: bool) {
+    const ON_CURVE: u8 = 0x01;
+    const X_SHORT: u8 = 0x02;
+    const Y_SHORT: u8 = 0x04;
+    const X_SAME_OR_POSITIVE: u8 = 0x10;
+    const Y_SAME_OR_POSITIVE: u8 = 0x20;
+    const OVERLAP_SIMPLE: u8 = 0x40;
+
+    let mut flags = vec![];
+    let mut xs = vec![];
+    let mut ys = vec![];
+    let (mut last_x, mut last_y) = (0, 0);
+
+    for (i, point) in points.iter().enumerate() {
+        let mut flag = if point.on_curve { ON_CURVE } else { 0 };
+        if overlap && i == 0 {
+            flag |= OVERLAP_SIMPLE;
+        }
+
+        let dx = point.x - last_x;
+        if dx == 0 {
+            flag |= X_SAME_OR_POSITIVE;
+        } else if dx.abs() < 256 {
+            flag |= X_SHORT | if dx > 0 { X_SAME_OR_POSITIVE } else { 0 };
+            xs.push(dx.unsigned_abs() as u8);
+        } else {
+            xs.extend((dx as i16).to_be_bytes());
+        }
+
+        let dy = point.y - last_y;
+        if dy == 0 {
+            flag |= Y_SAME_OR_POSITIVE;
+        } else if dy.abs() < 256 {
+            flag |= Y_SHORT | if dy > 0 { Y_SAME_OR_POSITIVE } else { 0 };
+            ys.push(dy.unsigned_abs() as u8);
+        } else {
+            ys.extend((dy as i16).to_be_bytes());
+        }
+
+        flags.push(flag);
+        last_x = point.x;
+        last_y = point.y;
+    }
+
+    glyf.extend(flags);
+    glyf.extend(xs);
+    glyf.extend(ys);
+}
+
+/// Reconstruct the `hmtx` table from a transformed one.
+fn reconstruct_hmtx(data: &[u8], num_h_metrics: u16, x_mins: &[i16]) -> Option<Vec<u8>> {
+    let num_h_metrics = usize::from(num_h_metrics);
+    let num_glyphs = x_mins.len();
+    if num_h_metrics == 0 || num_h_metrics > num_glyphs {
+        return None;
+    }
+
+    let mut r = Reader::new(data);
+    let flags = r.u8()?;
+    let advances = (0..num_h_metrics).map(|_| r.u16()).collect::<Option<Vec<_>>>()?;
+
+    let mut lsbs = vec![];
+    for (i, &x_min) in x_mins.iter().enumerate() {
+        let explicit = if i < num_h_metrics { flags & 1 == 0 } else { flags & 2 == 0 };
+        lsbs.push(if explicit { r.i16()? } else { x_min });
+    }
+
+    let mut hmtx = vec![];
+    for (i, lsb) in lsbs.into_iter().enumerate() {
+        if let Some(advance) = advances.get(i) {
+            hmtx.extend(advance.to_be_bytes());
+        }
+        hmtx.extend(lsb.to_be_bytes());
+    }
+
+    Some(hmtx)
+}
+
+/// Assemble a font or font collection from its tables.
+fn write_sfnt(faces: &[Face], tables: &[Table]) -> Vec<u8> {
+    let directory_size = |face: &Face| 12 + 16 * face.tables.len();
+    let collection = faces.len() > 1;
+
+    // Lay out the headers, followed by the table data.
+    let mut offset = if collection { 12 + 4 * faces.len() } else { 0 };
+    let mut face_offsets = vec![];
+    for face in faces {
+        face_offsets.push(offset);
+        offset += directory_size(face);
+    }
+
+    let mut table_offsets = vec![];
+    for table in tables {
+        table_offsets.push(offset);
+        offset += table.data.len().next_multiple_of(4);
+    }
+
+    let mut out = Vec::with_capacity(offset);
+    if collection {
+        out.extend(TTCF.to_be_bytes());
+        out.extend(0x0001_0000_u32.to_be_bytes());
+        out.extend((faces.len() as u32).to_be_bytes());
+        for &face_offset in &face_offsets {
+            out.extend((face_offset as u32).to_be_bytes());
+        }
+    }
+
+    for face in faces {
+        // The table records must be sorted by tag.
+        let mut indices = face.tables.clone();
+        indices.sort_by_key(|&i| tables[i].tag);
+
+        let num_tables = indices.len() as u32;
+        let entry_selector = 31 - num_tables.max(1).leading_zeros();
+        let search_range = (1 << entry_selector) * 16;
+        out.extend(face.flavor.to_be_bytes());
+        out.extend((num_tables as u16).to_be_bytes());
+        out.extend((search_range as u16).to_be_bytes());
+        out.extend((entry_selector as u16).to_be_bytes());
+        out.extend(((num_tables * 16).saturating_sub(search_range) as u16).to_be_bytes());
+
+        for i in indices {
+            let table = &tables[i];
+            out.extend(table.tag);
+            out.extend(checksum(&table.data).to_be_bytes());
+            out.extend((table_offsets[i] as u32).to_be_bytes());
+            out.extend((table.data.len() as u32).to_be_bytes());
+        }
+    }
+
+    for table in tables {
+        out.extend(&table.data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    out
+}
+
+/// Compute the checksum of a font table.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut bytes = [0; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
+
+/// Reads big-endian values from a byte slice.
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn at(mut self, offset: usize) -> Option<Self> {
+        (offset <= self.data.len()).then(|| {
+            self.offset = offset;
+            self
+        })
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(n)?;
+        let bytes = self.data.get(self.offset..end)?;
+        self.offset = end;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        self.take(n).map(|_| ())
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.array().map(u8::from_be_bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        self.array().map(i16::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn tag(&mut self) -> Option<[u8; 4]> {
+        self.array()
+    }
+
+    /// Read a `255UInt16` value.
+    fn u255(&mut self) -> Option<u16> {
+        match self.u8()? {
+            253 => self.u16(),
+            254 => Some(u16::from(self.u8()?) + 506),
+            255 => Some(u16::from(self.u8()?) + 253),
+            code => Some(u16::from(code)),
+        }
+    }
+
+    /// Read a `UIntBase128` value.
+    fn base128(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            // Leading zeros are not allowed.
+            if i == 0 && byte == 0x80 {
+                return None;
+            }
+            if value & 0xfe00_0000 != 0 {
+                return None;
+            }
+            value = (value << 7) | u32::from(byte & 0x7f);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn ttf() -> &'static [u8] {
+        typst_dev_assets::get("fonts/Roboto-Regular.ttf").unwrap()
+    }
+
+    /// The flavor of a font and its tagged tables.
+    type Directory<'a> = (u32, Vec<([u8; 4], &'a [u8])>);
+
+    /// The flavor and the tables of a font, in the order of its directory.
+    fn tables(data: &[u8]) -> Directory<'_> {
+        let mut r = Reader::new(data);
+        let flavor = r.u32().unwrap();
+        let num_tables = r.u16().unwrap();
+        r.skip(6).unwrap();
+        let tables = (0..num_tables)
+            .map(|_| {
+                let tag = r.tag().unwrap();
+                r.skip(4).unwrap();
+                let offset = r.u32().unwrap() as usize;
+                let length = r.u32().unwrap() as usize;
+                (tag, &data[offset..offset + length])
+            })
+            .collect();
+        (flavor, tables)
+    }
+
+    /// Wrap a font into a WOFF container, compressing its tables.
+    fn encode_woff(data: &[u8]) -> Vec<u8> {
+        let (flavor, tables) = tables(data);
+        let mut directory = vec![];
+        let mut body = vec![];
+        let start = 44 + 20 * tables.len();
+        for (tag, table) in &tables {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(vec![], flate2::Compression::best());
+            encoder.write_all(table).unwrap();
+            let compressed = encoder.finish().unwrap();
+            let stored: &[u8] =
+                if compressed.len() < table.len() { &compressed } else { table };
+            directory.extend(tag);
+            directory.extend(((start + body.len()) as u32).to_be_bytes());
+            directory.extend((stored.len() as u32).to_be_bytes());
+            directory.extend((table.len() as u32).to_be_bytes());
+            directory.extend(checksum(table).to_be_bytes());
+            body.extend(stored.iter());
+            body.resize(body.len().next_multiple_of(4), 0);
+        }
+
+        let mut out = vec![];
+        out.extend(WOFF);
+        out.extend(flavor.to_be_bytes());
+        out.extend(((start + body.len()) as u32).to_be_bytes());
+        out.extend((tables.len() as u16).to_be_bytes());
+        out.extend([0; 30]);
+        out.extend(directory);
+        out.extend(body);
+        out
+    }
+
+    /// Wrap a font into a WOFF2 container, optionally transforming its `glyf`
+    /// and `loca` tables. The table data is stored in uncompressed Brotli
+    /// meta-blocks.
+    fn encode_woff2(data: &[u8], transform: bool) -> Vec<u8> {
+        let (flavor, mut tables) = tables(data);
+        let find = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).map(|t| t.1);
+        let glyphs = match (find(b"glyf"), find(b"loca")) {
+            (Some(glyf), Some(loca)) if transform => {
+                let long = Reader::new(find(b"head").unwrap()).at(50).unwrap().i16();
+                let num_glyphs = Reader::new(find(b"maxp").unwrap()).at(4).unwrap().u16();
+                Some(transform_glyf(glyf, loca, long == Some(1), num_glyphs.unwrap()))
+            }
+            _ => None,
+        };
+
+        // The transformed `loca` table must directly follow `glyf`.
+        if glyphs.is_some() {
+            let loca = tables.iter().position(|(tag, _)| tag == b"loca").unwrap();
+            let loca = tables.remove(loca);
+            let glyf = tables.iter().position(|(tag, _)| tag == b"glyf").unwrap();
+            tables.insert(glyf + 1, loca);
+        }
+
+        let mut directory = vec![];
+        let mut stream = vec![];
+        for (tag, table) in &tables {
+            let known = KNOWN_TAGS.iter().position(|known| *known == tag);
+            let transformed = glyphs.is_some() && matches!(tag, b"glyf" | b"loca");
+            let version =
+                if matches!(tag, b"glyf" | b"loca") && !transformed { 3 } else { 0 };
+            directory.push(known.unwrap_or(63) as u8 | version << 6);
+            if known.is_none() {
+                directory.extend(tag);
+            }
+            directory.extend(base128(table.len() as u32));
+            match (tag, &glyphs) {
+                (b"glyf", Some(glyf)) => {
+                    directory.extend(base128(glyf.len() as u32));
+                    stream.extend(glyf);
+                }
+                (b"loca", Some(_)) => directory.extend(base128(0)),
+                _ => stream.extend(table.iter()),
+            }
+        }
+
+        let compressed = brotli_stored(&stream);
+        let mut out = vec![];
+        out.extend(WOFF2);
+        out.extend(flavor.to_be_bytes());
+        out.extend(((48 + directory.len() + compressed.len()) as u32).to_be_bytes());
+        out.extend((tables.len() as u16).to_be_bytes());
+        out.extend([0; 6]);
+        out.extend((compressed.len() as u32).to_be_bytes());
+        out.extend([0; 24]);
+        out.extend(directory);
+        out.extend(compressed);
+        out
+    }
+
+    /// Apply the WOFF2 transform to a `glyf` table.
+    fn transform_glyf(glyf: &[u8], loca: &[u8], long: bool, num_glyphs: u16) -> Vec<u8> {
+        let offset = |i: usize| {
+            if long {
+                Reader::new(loca).at(4 * i).unwrap().u32().unwrap() as usize
+            } else {
+                2 * usize::from(Reader::new(loca).at(2 * i).unwrap().u16().unwrap())
+            }
+        };
+
+        let num_glyphs = usize::from(num_glyphs);
+        let mut n_contours = vec![];
+        let mut n_points = vec![];
+        let mut flags = vec![];
+        let mut glyphs = vec![];
+        let mut composites = vec![];
+        let mut bbox_bitmap = vec![0; num_glyphs.div_ceil(32) * 4];
+        let mut bboxes: Vec<u8> = vec![];
+        let mut instructions: Vec<u8> = vec![];
+
+        for i in 0..num_glyphs {
+            let data = &glyf[offset(i)..offset(i + 1)];
+            if data.is_empty() {
+                n_contours.extend(0i16.to_be_bytes());
+                continue;
+            }
+
+            let mut r = Reader::new(data);
+            let contours = r.i16().unwrap();
+            let bbox = r.take(8).unwrap();
+            n_contours.extend(contours.to_be_bytes());
+
+            if contours < 0 {
+                // Composite glyphs keep their records and bounding box.
+                bbox_bitmap[i / 8] |= 0x80 >> (i % 8);
+                bboxes.extend(bbox);
+                let start = r.offset;
+                let mut have_instructions = false;
+                loop {
+                    let component_flags = r.u16().unwrap();
+                    let mut length = 2;
+                    length += if component_flags & 0x0001 != 0 { 4 } else { 2 };
+                    if component_flags & 0x0008 != 0 {
+                        length += 2;
+                    } else if component_flags & 0x0040 != 0 {
+                        length += 4;
+                    } else if component_flags & 0x0080 != 0 {
+                        length += 8;
+                    }
+                    r.skip(length).unwrap();
+                    have_instructions |= component_flags & 0x0100 != 0;
+                    if component_flags & 0x0020 == 0 {
+                        break;
+                    }
+                }
+                composites.extend(&data[start..r.offset]);
+                if have_instructions {
+                    let length = r.u16().unwrap();
+                    glyphs.extend(u255(length));
+                    instructions.extend(r.take(usize::from(length)).unwrap());
+                }
+                continue;
+            }
+
+            // Simple glyphs store the number of points per contour.
+            let mut last = -1;
+            for _ in 0..contours {
+                let end_point = i32::from(r.u16().unwrap());
+                n_points.extend(u255((end_point - last) as u16));
+                last = end_point;
+            }
+            let count = (last + 1) as usize;
+            let length = r.u16().unwrap();
+            let program = r.take(usize::from(length)).unwrap();
+
+            // Read the points' flags and coordinates.
+            let mut point_flags = vec![];
+            while point_flags.len() < count {
+                let flag = r.u8().unwrap();
+                let repeat = if flag & 0x08 != 0 { r.u8().unwrap() } else { 0 };
+                point_flags.extend(std::iter::repeat(flag).take(usize::from(repeat) + 1));
+            }
+            let mut deltas = [vec![], vec![]];
+            for (axis, short, same) in [(0, 0x02, 0x10), (1, 0x04, 0x20)] {
+                for &flag in &point_flags {
+                    let delta = match (flag & short != 0, flag & same != 0) {
+                        (true, true) => i32::from(r.u8().unwrap()),
+                        (true, false) => -i32::from(r.u8().unwrap()),
+                        (false, true) => 0,
+                        (false, false) => i32::from(r.i16().unwrap()),
+                    };
+                    deltas[axis].push(delta);
+                }
+            }
+
+            // Write the points as triplets.
+            let (mut x, mut y) = (0, 0);
+            let (mut x_min, mut y_min, mut x_max, mut y_max) =
+                (i32::MAX, i32::MAX, i32::MIN, i32::MIN);
+            for (k, &flag) in point_flags.iter().enumerate() {
+                let (dx, dy) = (deltas[0][k], deltas[1][k]);
+                let (triplet, bytes) = triplet(dx, dy);
+                flags.push(triplet | if flag & 0x01 != 0 { 0 } else { 0x80 });
+                glyphs.extend(bytes);
+                (x, y) = (x + dx, y + dy);
+                (x_min, y_min) = (x_min.min(x), y_min.min(y));
+                (x_max, y_max) = (x_max.max(x), y_max.max(y));
+            }
+
+            // Only store bounding boxes that differ from the computed ones.
+            let computed = [x_min, y_min, x_max, y_max].map(|v| (v as i16).to_be_bytes());
+            if computed.concat() != bbox {
+                bbox_bitmap[i / 8] |= 0x80 >> (i % 8);
+                bboxes.extend(bbox);
+            }
+
+            glyphs.extend(u255(length));
+            instructions.extend(program);
+        }
+
+        let mut out = vec![];
+        out.extend([0; 4]);
+        out.extend((num_glyphs as u16).to_be_bytes());
+        out.extend(u16::from(long).to_be_bytes());
+        let bbox_length = bbox_bitmap.len() + bboxes.len();
+        let streams = [n_contours, n_points, flags, glyphs, composites];
+        for stream in &streams {
+            out.extend((stream.len() as u32).to_be_bytes());
+        }
+        out.extend((bbox_length as u32).to_be_bytes());
+        out.extend((instructions.len() as u32).to_be_bytes());
+        for stream in streams {
+            out.extend(stream);
+        }
+        out.extend(bbox_bitmap);
+        out.extend(bboxes);
+        out.extend(instructions);
+        out
+    }
+
+    /// Encode a point's offset from the previous point as a WOFF2 triplet,
+    /// returning the flag and the data bytes.
+    fn triplet(dx: i32, dy: i32) -> (u8, Vec<u8>) {
+        let (x, y) = (dx.unsigned_abs(), dy.unsigned_abs());
+        let x_sign = u32::from(dx >= 0);
+        let y_sign = u32::from(dy >= 0);
+        let signs = x_sign + 2 * y_sign;
+        let (flag, bytes) = if dx == 0 && y < 1280 {
+            (((y & 0xf00) >> 7) + y_sign, vec![y as u8])
+        } else if dy == 0 && x < 1280 {
+            (10 + ((x & 0xf00) >> 7) + x_sign, vec![x as u8])
+        } else if x < 65 && y < 65 {
+            let flag = 20 + ((x - 1) & 0x30) + (((y - 1) & 0x30) >> 2) + signs;
+            (flag, vec![((((x - 1) & 0xf) << 4) | ((y - 1) & 0xf)) as u8])
+        } else if x < 769 && y < 769 {
+            let flag = 84 + 12 * (((x - 1) & 0x300) >> 8) + (((y - 1) & 0x300) >> 6);
+            (flag + signs, vec![(x - 1) as u8, (y - 1) as u8])
+        } else if x < 4096 && y < 4096 {
+            let bytes = vec![(x >> 4) as u8, ((x & 0xf) << 4 | y >> 8) as u8, y as u8];
+            (120 + signs, bytes)
+        } else {
+            (124 + signs, [(x as u16).to_be_bytes(), (y as u16).to_be_bytes()].concat())
+        };
+        (flag as u8, bytes)
+    }
+
+    /// Encode a `255UInt16` value.
+    fn u255(value: u16) -> Vec<u8> {
+        match value {
+            0..=252 => vec![value as u8],
+            253..=505 => vec![255, (value - 253) as u8],
+            506..=761 => vec![254, (value - 506) as u8],
+            _ => [[253].as_slice(), &value.to_be_bytes()].concat(),
+        }
+    }
+
+    /// Transform the `hmtx` table of a WOFF2 container that does not yet
+    /// have a transformed one, given the glyphs' minimum x coordinates.
+    fn transform_hmtx(woff2: &[u8], x_mins: &[i16]) -> Vec<u8> {
+        let mut r = Reader::new(woff2);
+        r.skip(12).unwrap();
+        let num_tables = r.u16().unwrap();
+        r.skip(34).unwrap();
+
+        let decoded = decompress(woff2).unwrap();
+        let (_, sfnt_tables) = tables(&decoded);
+        let find = |tag: &[u8; 4]| sfnt_tables.iter().find(|(t, _)| t == tag).unwrap().1;
+        let num_h_metrics =
+            usize::from(Reader::new(find(b"hhea")).at(34).unwrap().u16().unwrap());
+
+        // Copy the directory, noting the stored length of each table.
+        let mut directory = vec![];
+        let mut entries = vec![];
+        for _ in 0..num_tables {
+            let start = r.offset;
+            let flags = r.u8().unwrap();
+            let tag = match flags & 0x3f {
+                63 => r.tag().unwrap(),
+                index => *KNOWN_TAGS[usize::from(index)],
+            };
+            let orig_length = r.base128().unwrap() as usize;
+            let length = match &tag {
+                b"glyf" | b"loca" if flags >> 6 != 3 => r.base128().unwrap() as usize,
+                _ => orig_length,
+            };
+            entries.push((tag, directory.len(), length));
+            directory.extend(&woff2[start..r.offset]);
+        }
+
+        let compressed_length = u32::from_be_bytes(woff2[20..24].try_into().unwrap());
+        let compressed = &woff2[r.offset..r.offset + compressed_length as usize];
+        let mut stream = vec![];
+        brotli_decompressor::Decompressor::new(compressed, 4096)
+            .read_to_end(&mut stream)
+            .unwrap();
+
+        // Drop the left side bearings that match the glyphs' minimum x
+        // coordinates, separately for proportional and monospaced glyphs.
+        let hmtx = find(b"hmtx");
+        let lsb = |i: usize| {
+            let offset =
+                if i < num_h_metrics { 4 * i + 2 } else { 2 * i + 2 * num_h_metrics };
+            i16::from_be_bytes(hmtx[offset..offset + 2].try_into().unwrap())
+        };
+        let (proportional, monospaced) =
+            (0..x_mins.len()).partition::<Vec<_>, _>(|&i| i < num_h_metrics);
+        let derived = |glyphs: &[usize]| glyphs.iter().all(|&i| lsb(i) == x_mins[i]);
+        let flags =
+            u8::from(derived(&proportional)) | u8::from(derived(&monospaced)) << 1;
+        let mut transformed = vec![flags];
+        for i in 0..num_h_metrics {
+            transformed.extend(&hmtx[4 * i..4 * i + 2]);
+        }
+        for (bit, glyphs) in [(1, &proportional), (2, &monospaced)] {
+            if flags & bit == 0 {
+                for &i in glyphs {
+                    transformed.extend(lsb(i).to_be_bytes());
+                }
+            }
+        }
+
+        // Swap the table data and its directory entry.
+        let mut new_directory = vec![];
+        let mut new_stream = vec![];
+        let (mut last, mut offset) = (0, 0);
+        for (tag, start, length) in entries {
+            let data = &stream[offset..offset + length];
+            offset += length;
+            if tag != *b"hmtx" {
+                new_stream.extend(data);
+                continue;
+            }
+            new_directory.extend(&directory[last..start]);
+            new_directory.push(3 | 1 << 6);
+            new_directory.extend(base128(hmtx.len() as u32));
+            new_directory.extend(base128(transformed.len() as u32));
+            last = start + 1 + base128(hmtx.len() as u32).len();
+            new_stream.extend(&transformed);
+        }
+        new_directory.extend(&directory[last..]);
+
+        let compressed = brotli_stored(&new_stream);
+        let mut out = woff2[..48].to_vec();
+        let length = 48 + new_directory.len() + compressed.len();
+        out[8..12].copy_from_slice(&(length as u32).to_be_bytes());
+        out[20..24].copy_from_slice(&(compressed.len() as u32).to_be_bytes());
+        out.extend(new_directory);
+        out.extend(compressed);
+        out
+    }
+
+    /// Encode a `UIntBase128` value.
+    fn base128(mut value: u32) -> Vec<u8> {
+        let mut bytes = vec![(value & 0x7f) as u8];
+        value >>= 7;
+        while value > 0 {
+            bytes.insert(0, (value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes
+    }
+
+    /// Write data as a Brotli stream of uncompressed meta-blocks.
+    fn brotli_stored(data: &[u8]) -> Vec<u8> {
+        /// Writes bits starting at the least significant one.
+        #[derive(Default)]
+        struct Bits {
+            out: Vec<u8>,
+            bits: u32,
+            count: u32,
+        }
+
+        impl Bits {
+            fn write(&mut self, value: u32, n: u32) {
+                self.bits |= value << self.count;
+                self.count += n;
+                while self.count >= 8 {
+                    self.out.push(self.bits as u8);
+                    self.bits >>= 8;
+                    self.count -= 8;
+                }
+            }
+
+            fn pad(&mut self) {
+                self.write(0, (8 - self.count % 8) % 8);
+            }
+        }
+
+        // A window of 64 KiB.
+        let mut w = Bits::default();
+        w.write(0, 1);
+        for chunk in data.chunks(1 << 16) {
+            // Not last, four nibbles of length, and uncompressed.
+            w.write(0, 1);
+            w.write(0, 2);
+            w.write(chunk.len() as u32 - 1, 16);
+            w.write(1, 1);
+            w.pad();
+            w.out.extend(chunk);
+        }
+
+        // An empty last meta-block.
+        w.write(0b11, 2);
+        w.pad();
+        w.out
+    }
+
+    /// The number of contours and the bounding box in the header of each glyph
+    /// of a font, if it isn't empty.
+    fn glyph_headers(data: &[u8]) -> Vec<Option<(i16, [i16; 4])>> {
+        let (_, tables) = tables(data);
+        let find = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).unwrap().1;
+        let (glyf, loca, head) = (find(b"glyf"), find(b"loca"), find(b"head"));
+        let long = Reader::new(head).at(50).unwrap().i16().unwrap() == 1;
+        let offset = |i: usize| {
+            if long {
+                Reader::new(loca).at(4 * i).unwrap().u32().unwrap() as usize
+            } else {
+                2 * usize::from(Reader::new(loca).at(2 * i).unwrap().u16().unwrap())
+            }
+        };
+
+        let num_glyphs = Reader::new(find(b"maxp")).at(4).unwrap().u16().unwrap();
+        (0..usize::from(num_glyphs))
+            .map(|i| {
+                let range = offset(i)..offset(i + 1);
+                let mut r = Reader::new(glyf.get(range).filter(|g| !g.is_empty())?);
+                let contours = r.i16()?;
+                Some((contours, [r.i16()?, r.i16()?, r.i16()?, r.i16()?]))
+            })
+            .collect()
+    }
+
+    /// The outline of a glyph as a list of path segments.
+    fn outline(face: &ttf_parser::Face, id: ttf_parser::GlyphId) -> Vec<String> {
+        struct Builder(Vec<String>);
+
+        impl ttf_parser::OutlineBuilder for Builder {
+            fn move_to(&mut self, x: f32, y: f32) {
+                self.0.push(format!("M {x} {y}"));
+            }
+
+            fn line_to(&mut self, x: f32, y: f32) {
+                self.0.push(format!("L {x} {y}"));
+            }
+
+            fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+                self.0.push(format!("Q {x1} {y1} {x} {y}"));
+            }
+
+            fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+                self.0.push(format!("C {x1} {y1} {x2} {y2} {x} {y}"));
+            }
+
+            fn close(&mut self) {
+                self.0.push("Z".into());
+            }
+        }
+
+        let mut builder = Builder(vec![]);
+        face.outline_glyph(id, &mut builder);
+        builder.0
+    }
+
+    #[track_caller]
+    fn assert_same_tables(decoded: &[u8], original: &[u8]) {
+        let (flavor, mut expected) = tables(original);
+        let (decoded_flavor, mut found) = tables(decoded);
+        expected.sort_by_key(|(tag, _)| *tag);
+        found.sort_by_key(|(tag, _)| *tag);
+        assert_eq!(decoded_flavor, flavor);
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_decompress_plain() {
+        assert!(matches!(decompress(ttf()), Some(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_decompress_woff() {
+        let woff = encode_woff(ttf());
+        assert!(woff.len() < ttf().len());
+        let decoded = decompress(&woff).unwrap();
+        assert_same_tables(&decoded, ttf());
+        assert!(ttf_parser::Face::parse(&decoded, 0).is_ok());
+    }
+
+    #[test]
+    fn test_decompress_woff2() {
+        let woff2 = encode_woff2(ttf(), false);
+        let decoded = decompress(&woff2).unwrap();
+        assert_same_tables(&decoded, ttf());
+        assert!(ttf_parser::Face::parse(&decoded, 0).is_ok());
+    }
+
+    #[test]
+    fn test_decompress_woff2_transformed() {
+        let woff2 = encode_woff2(ttf(), true);
+        assert!(woff2.len() < encode_woff2(ttf(), false).len());
+        let decoded = decompress(&woff2).unwrap();
+        let face = ttf_parser::Face::parse(&decoded, 0).unwrap();
+        let original = ttf_parser::Face::parse(ttf(), 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), original.number_of_glyphs());
+
+        // Only `glyf` and `loca` may differ from the original font.
+        let (_, sfnt_tables) = tables(&decoded);
+        let (_, original_tables) = tables(ttf());
+        for (tag, data) in &original_tables {
+            if !matches!(tag, b"glyf" | b"loca") {
+                assert!(sfnt_tables.contains(&(*tag, *data)));
+            }
+        }
+
+        // The outlines and the glyph headers must survive the transform.
+        for i in 0..face.number_of_glyphs() {
+            let id = ttf_parser::GlyphId(i);
+            assert_eq!(outline(&face, id), outline(&original, id));
+        }
+        let headers = glyph_headers(&decoded);
+        assert_eq!(headers, glyph_headers(ttf()));
+        let count =
+            |f: fn(i16) -> bool| headers.iter().flatten().filter(|h| f(h.0)).count();
+        assert!(count(|contours| contours > 0) > 100);
+        assert!(count(|contours| contours < 0) > 0);
+
+        // Also transform the `hmtx` table and reconstruct it.
+        let x_mins: Vec<_> =
+            headers.iter().map(|h| h.map_or(0, |(_, bbox)| bbox[0])).collect();
+        let woff2 = transform_hmtx(&woff2, &x_mins);
+        let reconstructed = decompress(&woff2).unwrap();
+        assert_same_tables(&reconstructed, &decoded);
+    }
+
+    #[test]
+    fn test_decompress_oversized() {
+        // A table that claims to decompress to 4 GiB must be rejected without
+        // allocating that much.
+        let mut woff = encode_woff(ttf());
+        let entry = 44 + 20 * 5;
+        woff[entry + 12..entry + 16].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(decompress(&woff).is_none());
+    }
+
+    #[test]
+    fn test_decompress_truncated() {
+        for container in [encode_woff(ttf()), encode_woff2(ttf(), false)] {
+            for len in (4..container.len() - 4).step_by(97) {
+                assert!(decompress(&container[..len]).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decompress_malformed() {
+        // Only the signature.
+        assert!(decompress(WOFF).is_none());
+        assert!(decompress(WOFF2).is_none());
+
+        // Overwriting any byte of the headers, directories, or data of small
+        // containers may produce garbage, but must not panic.
+        let tables = [(*b"glyf", 24), (*b"head", 54), (*b"loca", 8), (*b"zzzz", 5)]
+            .map(|(tag, len)| Table { tag, data: (0..len).collect() });
+        let face = Face {
+            flavor: 0x0001_0000,
+            tables: (0..tables.len()).collect(),
+        };
+        let sfnt = write_sfnt(&[face], &tables);
+        for container in [encode_woff(&sfnt), encode_woff2(&sfnt, false)] {
+            assert_same_tables(&decompress(&container).unwrap(), &sfnt);
+            for i in 4..container.len() {
+                for value in [0x00, 0x01, 0x7f, 0x80, 0xfe, 0xff] {
+                    let mut data = container.clone();
+                    data[i] = value;
+                    decompress(&data);
+                }
+            }
+        }
+
+        // A transformed `glyf` table with garbage data.
+        let mut woff2 = encode_woff2(&sfnt, false);
+        assert_eq!(woff2[48], 10 | 3 << 6);
+        woff2[48] = 10;
+        assert!(decompress(&woff2).is_none());
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{cast, func, scope, ty, Bytes, Dict, Fold, Repr};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// Hyphenation patterns loaded from a file.
+///
+/// Typst ships with hyphenation patterns for many languages. For other
+/// languages or to customize hyphenation, you can load TeX-style patterns
+/// from a file and pass them to the text function's
+/// [`hyphenation-patterns`]($text.hyphenation-patterns) parameter.
+///
+/// The file must be encoded in UTF-8. It can either contain the patterns
+/// wrapped in `\patterns{...}` and exceptions wrapped in `\hyphenation{...}`
+/// as in TeX or just a whitespace-separated list of patterns. Comments start
+/// with a `%` and run until the end of the line.
+///
+/// # Example
+/// ```typ
+/// #set text(
+///   lang: "cy",
+///   hyphenation-patterns: hyphenation-patterns("hyph-cy.tex"),
+/// )
+/// ```
+#[ty(scope)]
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct HyphenationPatterns(Arc<PatternsRepr>);
+
+/// The internal representation of hyphenation patterns.
+#[derive(Eq, PartialEq, Hash)]
+struct PatternsRepr {
+    /// The path the patterns were loaded from.
+    path: EcoString,
+    /// Maps from the letters of a pattern to its levels between the letters.
+    patterns: BTreeMap<EcoString, Vec<u8>>,
+    /// The number of letters in the longest pattern.
+    longest: usize,
+    /// Exceptions defined in the file.
+    exceptions: HyphenationExceptions,
+}
+
+#[scope]
+impl HyphenationPatterns {
+    /// Loads hyphenation patterns from a file.
+    #[func(constructor)]
+    pub fn construct(
+        /// The engine.
+        engine: &mut Engine,
+        /// Path to a pattern file.
+        path: Spanned<EcoString>,
+    ) -> SourceResult<HyphenationPatterns> {
+        let Spanned { v: path, span } = path;
+        let id = span.resolve_path(&path).at(span)?;
+        let data = engine.world.file(id).at(span)?;
+        HyphenationPatterns::new(path, data).at(span)
+    }
+}
+
+impl HyphenationPatterns {
+    /// Parse patterns from the raw data of a file at the given path.
+    #[comemo::memoize]
+    pub fn new(path: EcoString, data: Bytes) -> StrResult<HyphenationPatterns> {
+        let Ok(text) = std::str::from_utf8(&data) else {
+            bail!("file is not valid utf-8");
+        };
+
+        // Strip comments.
+        let text: String = text
+            .lines()
+            .map(|line| line.split('%').next().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let patterns_src = group(&text, "\\patterns");
+        let exceptions_src = group(&text, "\\hyphenation");
+        let patterns_src = match (patterns_src, exceptions_src) {
+            (Some(src), _) => src,
+            (None, None) => text.as_str(),
+            (None, Some(_)) => "",
+        };
+
+        let mut patterns = BTreeMap::new();
+        let mut longest = 0;
+        for token in patterns_src.split_whitespace() {
+            let (letters, levels) = parse_pattern(token)?;
+            longest = longest.max(letters.chars().count());
+            patterns.insert(letters, levels);
+        }
+
+        if patterns.is_empty() {
+            bail!("file contains no hyphenation patterns");
+        }
+
+        let exceptions = HyphenationExceptions::new(
+            exceptions_src
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(EcoString::from)
+                .collect(),
+        )?;
+
+        Ok(Self(Arc::new(PatternsRepr { path, patterns, longest, exceptions })))
+    }
+
+    /// The path the patterns were loaded from.
+    pub fn path(&self) -> &EcoString {
+        &self.0.path
+    }
+
+    /// The byte offsets in the word at which it may be hyphenated.
+    ///
+    /// Uses Liang's algorithm: The levels of all patterns that match
+    /// somewhere in the word are combined by taking the maximum and odd
+    /// levels between two letters allow hyphenation there.
+    pub fn hyphenate(&self, word: &str) -> Vec<usize> {
+        if let Some(points) = self.0.exceptions.get(word) {
+            return points;
+        }
+
+        // Lowercase char by char to keep the mapping to the word's chars.
+        let chars: Vec<char> = std::iter::once('.')
+            .chain(word.chars().map(|c| c.to_lowercase().next().unwrap_or(c)))
+            .chain(std::iter::once('.'))
+            .collect();
+
+        let mut levels = vec![0; chars.len() + 1];
+        let mut key = String::new();
+        for start in 0..chars.len() {
+            key.clear();
+            for &c in chars.iter().skip(start).take(self.0.longest) {
+                key.push(c);
+                let Some(values) = self.0.patterns.get(key.as_str()) else {
+                    continue;
+                };
+                for (level, &value) in levels[start..].iter_mut().zip(values) {
+                    *level = (*level).max(value);
+                }
+            }
+        }
+
+        // The level before the n-th letter of the word is at n + 1 due to
+        // the leading dot.
+        word.char_indices()
+            .enumerate()
+            .skip(1)
+            .filter(|&(n, _)| levels[n + 1] % 2 == 1)
+            .map(|(_, (offset, _))| offset)
+            .collect()
+    }
+}
+
+impl Debug for HyphenationPatterns {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "HyphenationPatterns({:?})", self.0.path)
+    }
+}
+
+impl Repr for HyphenationPatterns {
+    fn repr(&self) -> EcoString {
+        eco_format!("hyphenation-patterns({})", self.0.path.repr())
+    }
+}
+
+/// Extract the contents of a TeX group like `\patterns{...}`.
+fn group<'a>(text: &'a str, command: &str) -> Option<&'a str> {
+    let start = text.find(command)? + command.len();
+    let rest = text[start..].trim_start().strip_prefix('{')?;
+    Some(&rest[..rest.find('}').unwrap_or(rest.len())])
+}
+
+/// Parse a pattern like `.ab1c` into its letters and the levels between them.
+fn parse_pattern(token: &str) -> StrResult<(EcoString, Vec<u8>)> {
+    let mut letters = EcoString::new();
+    let mut levels = vec![0];
+    for c in token.chars() {
+        match c.to_digit(10) {
+            Some(digit) => *levels.last_mut().unwrap() = digit as u8,
+            None => {
+                letters.push(c.to_lowercase().next().unwrap_or(c));
+                levels.push(0);
+            }
+        }
+    }
+
+    if letters.is_empty() {
+        bail!("invalid hyphenation pattern {}", token.repr());
+    }
+
+    Ok((letters, levels))
+}
+
+/// Words with explicitly specified hyphenation points.
+///
+/// Each word marks its hyphenation points with hyphens, e.g. `"data-base"`.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct HyphenationExceptions(Vec<EcoString>);
+
+impl HyphenationExceptions {
+    /// Create a new list of exceptions, validating that each of them is a
+    /// word with hyphens only between its letters.
+    pub fn new(words: Vec<EcoString>) -> StrResult<Self> {
+        for word in &words {
+            if word.is_empty()
+                || word.starts_with('-')
+                || word.ends_with('-')
+                || word.contains("--")
+                || word.contains(char::is_whitespace)
+            {
+                bail!("invalid hyphenation exception {}", word.repr());
+            }
+        }
+
+        Ok(Self(words))
+    }
+
+    /// The byte offsets at which the word may be hyphenated if it is an
+    /// exception.
+    ///
+    /// Words are compared case-insensitively.
+    pub fn get(&self, word: &str) -> Option<Vec<usize>> {
+        self.0.iter().find_map(|exception| {
+            let mut points = vec![];
+            let mut chars = word.char_indices();
+            for c in exception.chars() {
+                if c == '-' {
+                    points.push(chars.clone().next()?.0);
+                    continue;
+                }
+
+                let (_, other) = chars.next()?;
+                if !c.to_lowercase().eq(other.to_lowercase()) {
+                    return None;
+                }
+            }
+
+            chars.next().is_none().then_some(points)
+        })
+    }
+}
+
+cast! {
+    HyphenationExceptions,
+    self => self.0.into_value(),
+    v: Vec<EcoString> => Self::new(v)?,
+}
+
+/// The minimum number of letters before and after a hyphenation point.
+///
+/// Limits are updated (prioritizing the later value) when folded.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct HyphenationLimits {
+    start: Option<NonZeroUsize>,
+    end: Option<NonZeroUsize>,
+}
+
+impl HyphenationLimits {
+    /// Whether hyphenating after `before` of the word's `len` letters
+    /// respects the limits. Unset limits default to the given values.
+    pub fn allows(&self, before: usize, len: usize, defaults: (usize, usize)) -> bool {
+        let start = self.start.map_or(defaults.0, NonZeroUsize::get);
+        let end = self.end.map_or(defaults.1, NonZeroUsize::get);
+        before >= start && len - before >= end
+    }
+}
+
+impl Fold for HyphenationLimits {
+    fn fold(self, outer: Self) -> Self {
+        Self {
+            start: self.start.or(outer.start),
+            end: self.end.or(outer.end),
+        }
+    }
+}
+
+cast! {
+    HyphenationLimits,
+    self => {
+        let mut dict = Dict::new();
+        if let Some(start) = self.start {
+            dict.insert("start".into(), start.into_value());
+        }
+        if let Some(end) = self.end {
+            dict.insert("end".into(), end.into_value());
+        }
+        dict.into_value()
+    },
+    mut v: Dict => {
+        let ret = Self {
+            start: v.take("start").ok().map(|v| v.cast()).transpose()?,
+            end: v.take("end").ok().map(|v| v.cast()).transpose()?,
+        };
+        v.finish(&["start", "end"])?;
+        ret
+    },
+}
@@ -242,18 +242,28 @@ pub trait LocalName {
 /// in both given language + region and English.
 #[comemo::memoize]
 pub fn localized_str(lang: Lang, region: Option<Region>, key: &str) -> &'static str {
-    let lang_region_bundle = parse_language_bundle(lang, region).unwrap();
-    if let Some(str) = lang_region_bundle.get(key) {
-        return str;
-    }
-    let lang_bundle = parse_language_bundle(lang, None).unwrap();
-    if let Some(str) = lang_bundle.get(key) {
+    if let Some(str) = try_localized_str(lang, region, key) {
         return str;
     }
     let english_bundle = parse_language_bundle(Lang::ENGLISH, None).unwrap();
     english_bundle.get(key).unwrap()
 }
 
+/// Retrieves the localized string for a given language and region without
+/// falling back to English.
+pub(crate) fn try_localized_str(
+    lang: Lang,
+    region: Option<Region>,
+    key: &str,
+) -> Option<&'static str> {
+    let lang_region_bundle = parse_language_bundle(lang, region).unwrap();
+    if let Some(str) = lang_region_bundle.get(key) {
+        return Some(str);
+    }
+    let lang_bundle = parse_language_bundle(lang, None).unwrap();
+    lang_bundle.get(key).copied()
+}
+
 /// Parses the translation file for a given language and region.
 /// Only returns an error if the language file is malformed.
 #[comemo::memoize]
@@ -0,0 +1,217 @@
+use comemo::Tracked;
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult};
+use crate::foundations::calc::Num;
+use crate::foundations::{func, repr, Cast, Context, Smart, Str};
+use crate::syntax::Span;
+use crate::text::{localized_str, try_localized_str, Lang, Region, TextElem};
+
+/// Formats a number according to the conventions of a language.
+///
+/// Uses the decimal mark, digit grouping, and percent and currency notation
+/// of the [language]($text.lang) and [region]($text.region) of the text. As
+/// these depend on the text's styles, this function must be used within a
+/// [`context`] expression or a [numbering] function.
+///
+/// # Example
+/// ```example
+/// #set text(lang: "de")
+/// #context format-number(1234567.891) \
+/// #context format-number(0.25, style: "percent") \
+/// #context format-number(19.9, style: "currency", currency: "EUR")
+/// ```
+#[func(contextual)]
+pub fn format_number(
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The callsite span.
+    span: Span,
+    /// The number to format.
+    number: Num,
+    /// How to format the number.
+    #[named]
+    #[default(NumberStyle::Decimal)]
+    style: NumberStyle,
+    /// How many digits to show after the decimal mark.
+    ///
+    /// If set to `{auto}`, integers are shown without a fractional part and
+    /// floats with as many digits as needed, up to nine. Amounts of money
+    /// are shown with the usual number of digits for their currency.
+    #[named]
+    #[default]
+    digits: Smart<usize>,
+    /// Whether to separate groups of three digits in the integer part.
+    #[named]
+    #[default(true)]
+    grouping: bool,
+    /// The currency of an amount of money.
+    ///
+    /// Either a three-letter code like `{"EUR"}`, which is replaced with the
+    /// currency's symbol if it has a well-known one, or any other text, which
+    /// is shown as is.
+    #[named]
+    #[default]
+    currency: Option<Str>,
+) -> SourceResult<Str> {
+    let styles = context.styles().at(span)?;
+    let lang = TextElem::lang_in(styles);
+    let region = TextElem::region_in(styles);
+
+    let (scale, digits, pattern) = match style {
+        NumberStyle::Decimal => (1, digits.custom(), "#"),
+        NumberStyle::Percent => {
+            (100, digits.custom(), localized_str(lang, region, "percent"))
+        }
+        NumberStyle::Currency => {
+            let Some(currency) = &currency else {
+                bail!(
+                    span,
+                    "cannot format an amount of money without a currency";
+                    hint: "specify it with the `currency` argument"
+                );
+            };
+            let digits = digits.unwrap_or(currency_digits(currency));
+            (1, Some(digits), localized_str(lang, region, "currency"))
+        }
+    };
+
+    let plain = match number {
+        Num::Int(n) => {
+            let mut plain = eco_format!("{}", i128::from(n) * scale);
+            if let Some(digits @ 1..) = digits {
+                plain.push('.');
+                plain.push_str(&"0".repeat(digits));
+            }
+            plain
+        }
+        Num::Float(f) if !f.is_finite() => {
+            bail!(span, "cannot format {}", repr::display_float(f))
+        }
+        Num::Float(f) => {
+            let f = f * scale as f64;
+            match digits {
+                Some(digits) => eco_format!("{f:.digits$}"),
+                None => eco_format!("{f:.9}")
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .into(),
+            }
+        }
+    };
+
+    // Don't show a sign for numbers that were rounded to zero.
+    let negative =
+        plain.starts_with('-') && plain.bytes().any(|b| matches!(b, b'1'..=b'9'));
+    let plain = plain.trim_start_matches('-');
+    let (int, frac) = plain.split_once('.').unwrap_or((plain, ""));
+
+    let (group, decimal) = separators(lang, region);
+    let mut number = EcoString::new();
+    for (i, c) in int.char_indices() {
+        if grouping && i > 0 && (int.len() - i) % 3 == 0 {
+            number.push_str(group);
+        }
+        number.push(c);
+    }
+    if !frac.is_empty() {
+        number.push_str(decimal);
+        number.push_str(frac);
+    }
+
+    let mut formatted = EcoString::new();
+    if negative {
+        formatted.push_str(repr::MINUS_SIGN);
+    }
+    for c in pattern.chars() {
+        match c {
+            '#' => formatted.push_str(&number),
+            '¤' => formatted
+                .push_str(currency_symbol(currency.as_deref().unwrap_or_default())),
+            c => formatted.push(c),
+        }
+    }
+
+    Ok(formatted.into())
+}
+
+/// How to format a number.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum NumberStyle {
+    /// As a plain number.
+    Decimal,
+    /// As a percentage, such that `{0.5}` becomes 50 percent.
+    Percent,
+    /// As an amount of money in the given `currency`.
+    Currency,
+}
+
+/// The name of a month, starting at 1 for January.
+///
+/// Languages that don't abbreviate month names, like Arabic, have no short
+/// names and use the full ones instead.
+pub(crate) fn month_name(
+    lang: Lang,
+    region: Option<Region>,
+    month: u8,
+    short: bool,
+) -> &'static str {
+    short
+        .then(|| try_localized_str(lang, region, &eco_format!("month-short-{month}")))
+        .flatten()
+        .unwrap_or_else(|| localized_str(lang, region, &eco_format!("month-{month}")))
+}
+
+/// The name of a weekday, starting at 1 for Monday.
+///
+/// Like for months, languages without abbreviated names use the full ones.
+pub(crate) fn weekday_name(
+    lang: Lang,
+    region: Option<Region>,
+    weekday: u8,
+    short: bool,
+) -> &'static str {
+    short
+        .then(|| try_localized_str(lang, region, &eco_format!("weekday-short-{weekday}")))
+        .flatten()
+        .unwrap_or_else(|| localized_str(lang, region, &eco_format!("weekday-{weekday}")))
+}
+
+/// The digit group separator and the decimal mark in the given language and
+/// region.
+///
+/// These are taken from the sample number `1,234.5` written in the language.
+fn separators(lang: Lang, region: Option<Region>) -> (&'static str, &'static str) {
+    let sample = localized_str(lang, region, "number");
+    sample
+        .strip_prefix('1')
+        .and_then(|rest| rest.split_once("234"))
+        .and_then(|(group, rest)| Some((group, rest.strip_suffix('5')?)))
+        .unwrap_or((",", "."))
+}
+
+/// The symbol of a currency, or the currency itself if it has none.
+fn currency_symbol(currency: &str) -> &str {
+    match currency {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        "KRW" => "₩",
+        "RUB" => "₽",
+        "BRL" => "R$",
+        "PLN" => "zł",
+        "SEK" | "NOK" | "DKK" => "kr",
+        _ => currency,
+    }
+}
+
+/// The usual number of digits after the decimal mark for amounts of the
+/// currency.
+fn currency_digits(currency: &str) -> usize {
+    match currency {
+        "JPY" | "KRW" => 0,
+        _ => 2,
+    }
+}
@@ -3,33 +3,44 @@
 mod case;
 mod deco;
 mod font;
+mod hyphenation;
 mod item;
 mod lang;
 mod linebreak;
+mod locale;
 #[path = "lorem.rs"]
 mod lorem_;
 mod raw;
+mod ruby;
 mod shift;
 #[path = "smallcaps.rs"]
 mod smallcaps_;
 mod smartquote;
 mod space;
+mod textpath;
 
 pub use self::case::*;
 pub use self::deco::*;
 pub use self::font::*;
+pub use self::hyphenation::*;
 pub use self::item::*;
 pub use self::lang::*;
 pub use self::linebreak::*;
+pub use self::locale::*;
 pub use self::lorem_::*;
 pub use self::raw::*;
+pub use self::ruby::*;
 pub use self::shift::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
 pub use self::space::*;
+pub use self::textpath::*;
 
 use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
+use comemo::Tracked;
 use ecow::{eco_format, EcoString};
 use rustybuzz::Feature;
 use smallvec::SmallVec;
@@ -40,12 +51,12 @@ use crate::engine::Engine;
 use crate::foundations::{
     cast, category, dict, elem, Args, Array, Cast, Category, Construct, Content, Dict,
     Fold, NativeElement, Never, Packed, PlainText, Repr, Resolve, Scope, Set, Smart,
-    StyleChain,
+    StyleChain, Value,
 };
-use crate::layout::{Abs, Axis, Dir, Em, Length, Ratio, Rel};
+use crate::layout::{Abs, Dir, Em, Length, Ratio, Rel};
 use crate::model::ParElem;
-use crate::syntax::Spanned;
-use crate::visualize::{Color, Paint, RelativeTo, Stroke};
+use crate::syntax::{Span, Spanned};
+use crate::visualize::{Color, Paint, RelativeTo, Shadow, Stroke};
 use crate::World;
 
 /// Text styling.
@@ -68,9 +79,14 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<HighlightElem>();
     global.define_elem::<SmallcapsElem>();
     global.define_elem::<RawElem>();
+    global.define_elem::<RubyElem>();
+    global.define_elem::<TextpathElem>();
+    global.define_type::<FontFile>();
+    global.define_type::<HyphenationPatterns>();
     global.define_func::<lower>();
     global.define_func::<upper>();
     global.define_func::<lorem>();
+    global.define_func::<format_number>();
 }
 
 /// Customizes the look and layout of text in a variety of ways.
@@ -131,7 +147,10 @@ pub struct TextElem {
         if let Some(font_list) = &font_list {
             let book = engine.world.book();
             for family in &font_list.v {
-                if !book.contains_family(family.as_str()) {
+                if family.file().is_none()
+                    && !book.contains_family(family.as_str())
+                    && !engine.tracer.has_font_family(family.as_str())
+                {
                     engine.tracer.warn(warning!(
                         font_list.span,
                         "unknown font family: {}",
@@ -173,9 +192,10 @@ pub struct TextElem {
     /// When an italic style is requested and only an oblique one is available,
     /// it is used. Similarly, the other way around, an italic style can stand
     /// in for an oblique one.  When neither an italic nor an oblique style is
-    /// available, Typst selects the normal style. Since most fonts are only
-    /// available either in an italic or oblique style, the difference between
-    /// italic and oblique style is rarely observable.
+    /// available, Typst selects the normal style and synthesizes an oblique
+    /// style by slanting the glyphs. Since most fonts are only available
+    /// either in an italic or oblique style, the difference between italic and
+    /// oblique style is rarely observable.
     ///
     /// If you want to emphasize your text, you should do so using the [emph]
     /// function instead. This makes it easy to adapt the style later if you
@@ -191,7 +211,9 @@ pub struct TextElem {
     /// The desired thickness of the font's glyphs. Accepts an integer between
     /// `{100}` and `{900}` or one of the predefined weight names. When the
     /// desired weight is not available, Typst selects the font from the family
-    /// that is closest in weight.
+    /// that is closest in weight. If a bold weight is requested, but the
+    /// family only has light or regular faces, Typst synthesizes the boldness
+    /// by thickening the glyphs.
     ///
     /// If you want to strongly emphasize your text, you should do so using the
     /// [strong] function instead. This makes it easy to adapt the style later
@@ -274,6 +296,17 @@ pub struct TextElem {
     #[ghost]
     pub stroke: Option<Stroke>,
 
+    /// A shadow that the text casts onto what is below it. See the
+    /// [rectangle's documentation]($rect.shadow) for the available settings.
+    /// Within a line, the shadows of all text are drawn below its glyphs.
+    ///
+    /// ```example
+    /// #text(size: 20pt, shadow: (dx: 1pt, dy: 1pt, blur: 2pt))[Shady]
+    /// ```
+    #[resolve]
+    #[ghost]
+    pub shadow: Option<Shadow>,
+
     /// The amount of space that should be added between characters.
     ///
     /// ```example
@@ -439,6 +472,8 @@ pub struct TextElem {
     /// - `{auto}`: Automatically infer the direction from the `lang` property.
     /// - `{ltr}`: Layout text from left to right.
     /// - `{rtl}`: Layout text from right to left.
+    /// - `{ttb}`: Layout text from top to bottom in vertical lines that are
+    ///   stacked from right to left.
     ///
     /// When writing in right-to-left scripts like Arabic or Hebrew, you should
     /// set the [text language]($text.lang) or direction. While individual runs
@@ -458,6 +493,22 @@ pub struct TextElem {
     /// #set text(dir: rtl)
     /// هذا عربي.
     /// ```
+    ///
+    /// Vertical text is meant for scripts like Chinese, Japanese, and
+    /// Mongolian and must be set for whole pages, that is, before any content
+    /// of the page. Setting it for a part of a page, for example for a single
+    /// word or within a box, is an error. The page's content is then laid out
+    /// in vertical lines and columns are stacked from top to bottom. Chinese
+    /// and Japanese characters stand upright, while runs of other scripts are
+    /// rotated sideways. Short numbers of up to two digits are set
+    /// horizontally within a single character's space (tate-chu-yoko). The
+    /// page's header, footer, background, and foreground stay horizontal.
+    ///
+    /// ```typ
+    /// #set page(width: 120pt, height: 160pt)
+    /// #set text(dir: ttb, lang: "ja")
+    /// 吾輩は猫である。名前はまだ無い。
+    /// ```
     #[resolve]
     #[ghost]
     pub dir: TextDir,
@@ -485,6 +536,48 @@ pub struct TextElem {
     #[ghost]
     pub hyphenate: Hyphenate,
 
+    /// Words with explicit hyphenation points that take precedence over the
+    /// hyphenation patterns.
+    ///
+    /// Each word marks the points at which it may be hyphenated with hyphens.
+    /// A word without hyphens is never hyphenated. Words are matched
+    /// case-insensitively.
+    ///
+    /// ```example
+    /// #set page(width: 80pt)
+    /// #set par(justify: true)
+    /// #set text(hyphenation-exceptions: ("Typ-st", "data-base"))
+    /// The Typst database.
+    /// ```
+    #[ghost]
+    pub hyphenation_exceptions: HyphenationExceptions,
+
+    /// Custom hyphenation patterns to use instead of the built-in patterns
+    /// for the [text language]($text.lang).
+    ///
+    /// Use this to hyphenate languages that Typst has no built-in patterns
+    /// for. See the [`hyphenation-patterns`]($hyphenation-patterns) type for
+    /// details on how to load them.
+    #[ghost]
+    pub hyphenation_patterns: Option<HyphenationPatterns>,
+
+    /// The minimum number of letters that must remain before (`start`) and
+    /// after (`end`) a hyphenation point.
+    ///
+    /// Unset limits default to the conventions of the text language. For
+    /// custom [hyphenation patterns]($text.hyphenation-patterns), they
+    /// default to two letters at the start and three letters at the end.
+    ///
+    /// ```example
+    /// #set page(width: 80pt)
+    /// #set par(justify: true)
+    /// #set text(hyphenation-limits: (start: 4, end: 4))
+    /// Hyphenation leaves longer fragments.
+    /// ```
+    #[fold]
+    #[ghost]
+    pub hyphenation_limits: HyphenationLimits,
+
     /// The "cost" of various choices when laying out text. A higher cost means
     /// the layout engine will make the choice less often. Costs are specified
     /// as a ratio of the default cost, so `50%` will make text layout twice as
@@ -510,7 +603,9 @@ pub struct TextElem {
     /// The `widow` and `orphan` costs allow disabling these modifications.
     /// (Currently, 0% allows widows/orphans; anything else, including the
     /// default of `auto`, prevents them. More nuanced cost specification for
-    /// these modifications is planned for the future.)
+    /// these modifications is planned for the future.) How many lines are
+    /// kept together is configured with the paragraph's
+    /// [`orphans`]($par.orphans) and [`widows`]($par.widows) properties.
     ///
     /// The default costs are an acceptable balance, but some may find that it
     /// hyphenates or avoids runs too eagerly, breaking the flow of dense prose.
@@ -680,6 +775,28 @@ pub struct TextElem {
     #[ghost]
     pub features: FontFeatures,
 
+    /// Coordinates on the variation axes of variable fonts.
+    ///
+    /// The dictionary maps from OpenType axis tags to the coordinates on the
+    /// axes. The `wght` and `wdth` axes are set automatically from the text's
+    /// [`weight`]($text.weight) and [`stretch`]($text.stretch), but this can
+    /// be overridden by setting them explicitly. Each key must be a
+    /// four-character tag and at least one font of the font list must have
+    /// the axis. Fonts without the axis ignore it and coordinates outside of
+    /// an axis's range are clamped.
+    ///
+    /// ```typ
+    /// #set text(
+    ///   font: "Roboto Flex",
+    ///   variations: (wght: 450, opsz: 12),
+    /// )
+    /// Variable fonts can be
+    /// instanced at any axis value.
+    /// ```
+    #[fold]
+    #[ghost]
+    pub variations: FontVariations,
+
     /// Content in which all text is styled according to the other arguments.
     #[external]
     #[required]
@@ -724,6 +841,12 @@ pub struct TextElem {
     #[default(false)]
     #[ghost]
     pub smallcaps: bool,
+
+    /// The font files declared in the manifests of imported packages.
+    #[internal]
+    #[borrowed]
+    #[ghost]
+    pub package_fonts: Vec<FontFile>,
 }
 
 impl TextElem {
@@ -764,29 +887,49 @@ impl PlainText for Packed<TextElem> {
 
 /// A lowercased font family like "arial".
 #[derive(Clone, Eq, PartialEq, Hash)]
-pub struct FontFamily(EcoString);
+pub struct FontFamily {
+    /// The lowercased family name.
+    name: EcoString,
+    /// The file that provides the family's fonts, if they are not taken from
+    /// the world.
+    file: Option<FontFile>,
+}
 
 impl FontFamily {
     /// Create a named font family variant.
     pub fn new(string: &str) -> Self {
-        Self(string.to_lowercase().into())
+        Self { name: string.to_lowercase().into(), file: None }
+    }
+
+    /// Create font families for the fonts in a file, one for each of the
+    /// file's families.
+    pub fn from_file(file: FontFile) -> Vec<Self> {
+        file.families()
+            .iter()
+            .map(|name| Self { name: name.clone(), file: Some(file.clone()) })
+            .collect()
     }
 
     /// The lowercased family name.
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.name
+    }
+
+    /// The file that provides the family's fonts, if any.
+    pub fn file(&self) -> Option<&FontFile> {
+        self.file.as_ref()
     }
 }
 
 impl Debug for FontFamily {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.0.fmt(f)
+        self.name.fmt(f)
     }
 }
 
 cast! {
     FontFamily,
-    self => self.0.into_value(),
+    self => self.name.into_value(),
     string: EcoString => Self::new(&string),
 }
 
@@ -805,13 +948,63 @@ impl<'a> IntoIterator for &'a FontList {
 
 cast! {
     FontList,
-    self => if self.0.len() == 1 {
-        self.0.into_iter().next().unwrap().0.into_value()
-    } else {
-        self.0.into_value()
+    self => {
+        // The families of a font file are represented by the file itself.
+        let mut values: Vec<Value> = vec![];
+        let mut last: Option<FontFile> = None;
+        for family in self.0 {
+            match family.file {
+                Some(file) if last.as_ref() == Some(&file) => {}
+                Some(file) => {
+                    values.push(file.clone().into_value());
+                    last = Some(file);
+                }
+                None => {
+                    values.push(family.name.into_value());
+                    last = None;
+                }
+            }
+        }
+        if values.len() == 1 {
+            values.pop().unwrap()
+        } else {
+            values.into_value()
+        }
     },
-    family: FontFamily => Self(vec![family]),
-    values: Array => Self(values.into_iter().map(|v| v.cast()).collect::<HintedStrResult<_>>()?),
+    entry: FontListEntry => Self(entry.into_families()),
+    values: Array => Self(
+        values
+            .into_iter()
+            .map(|v| v.cast().map(FontListEntry::into_families))
+            .collect::<HintedStrResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect()
+    ),
+}
+
+/// An entry in a font list as specified by the user.
+enum FontListEntry {
+    /// A named family.
+    Family(FontFamily),
+    /// A file that provides one or multiple families.
+    File(FontFile),
+}
+
+impl FontListEntry {
+    /// The families the entry stands for.
+    fn into_families(self) -> Vec<FontFamily> {
+        match self {
+            Self::Family(family) => vec![family],
+            Self::File(file) => FontFamily::from_file(file),
+        }
+    }
+}
+
+cast! {
+    FontListEntry,
+    family: FontFamily => Self::Family(family),
+    file: FontFile => Self::File(file),
 }
 
 /// Resolve a prioritized iterator over the font families.
@@ -831,6 +1024,101 @@ pub(crate) fn families(styles: StyleChain) -> impl Iterator<Item = &str> + Clone
         .chain(tail.iter().copied())
 }
 
+/// Select the font from the given `family` that matches the given `variant` as
+/// closely as possible.
+///
+/// Fonts loaded from files in the font list or declared by imported packages
+/// are part of the same families as the world's fonts. If a font from a file
+/// and one from the world match equally well, the one from the file is
+/// selected.
+pub(crate) fn select_font(
+    engine: &Engine,
+    styles: StyleChain,
+    family: &str,
+    variant: FontVariant,
+) -> Option<Font> {
+    let world = engine.world;
+    match font_set(TextElem::font_in(styles), TextElem::package_fonts_in(styles)) {
+        Some(set) => set
+            .layered(&world)
+            .select(family, variant)
+            .and_then(|id| set.font(world, id)),
+        None => world.book().select(family, variant).and_then(|id| world.font(id)),
+    }
+}
+
+/// Select a fallback font that is suitable for shaping the given `text`. See
+/// [`FontBook::select_fallback`] for details.
+///
+/// The fonts loaded from files in the font list or declared by imported
+/// packages are considered along with the world's fonts.
+pub(crate) fn select_fallback_font(
+    engine: &Engine,
+    styles: StyleChain,
+    like: Option<&FontInfo>,
+    variant: FontVariant,
+    text: &str,
+) -> Option<Font> {
+    let world = engine.world;
+    match font_set(TextElem::font_in(styles), TextElem::package_fonts_in(styles)) {
+        Some(set) => set
+            .layered(&world)
+            .select_fallback(like, variant, text)
+            .and_then(|id| set.font(world, id)),
+        None => world
+            .book()
+            .select_fallback(like, variant, text)
+            .and_then(|id| world.font(id)),
+    }
+}
+
+/// The fonts loaded from files, which are layered over the world's fonts.
+struct FontSet {
+    /// Describes the fonts from files.
+    book: FontBook,
+    /// The fonts loaded from files, in the same order as in the book.
+    files: Vec<Font>,
+}
+
+impl FontSet {
+    /// Layer the fonts from files over the world's fonts.
+    fn layered<'a>(&'a self, world: &'a Tracked<dyn World + '_>) -> LayeredFontBook<'a> {
+        LayeredFontBook { top: &self.book, base: world.book() }
+    }
+
+    /// Load the font with the given index in the layered book.
+    fn font(&self, world: Tracked<dyn World + '_>, id: usize) -> Option<Font> {
+        match self.files.get(id) {
+            Some(font) => Some(font.clone()),
+            None => world.font(id - self.files.len()),
+        }
+    }
+}
+
+/// Collect the fonts loaded from files in the font list, followed by those
+/// declared by imported packages.
+///
+/// Returns `None` if there are no such fonts.
+#[comemo::memoize]
+fn font_set(list: &FontList, packages: &[FontFile]) -> Option<Arc<FontSet>> {
+    let mut files: Vec<Font> = vec![];
+    let listed = list.into_iter().filter_map(FontFamily::file);
+    for file in listed.chain(packages) {
+        for font in file.fonts() {
+            if !files.contains(font) {
+                files.push(font.clone());
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return None;
+    }
+
+    let book = FontBook::from_fonts(&files);
+    Some(Arc::new(FontSet { book, files }))
+}
+
 /// Resolve the font variant.
 pub(crate) fn variant(styles: StyleChain) -> FontVariant {
     let mut variant = FontVariant::new(
@@ -1025,8 +1313,8 @@ cast! {
     TextDir,
     self => self.0.into_value(),
     v: Smart<Dir> => {
-        if v.is_custom_and(|dir| dir.axis() == Axis::Y) {
-            bail!("text direction must be horizontal");
+        if v.is_custom_and(|dir| dir == Dir::BTT) {
+            bail!("text direction must be horizontal or top-to-bottom");
         }
         Self(v)
     },
@@ -1147,6 +1435,112 @@ impl Fold for FontFeatures {
     }
 }
 
+/// Coordinates on the variation axes of variable fonts.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FontVariations(pub Vec<(Tag, f64)>);
+
+impl Hash for FontVariations {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+        for (tag, value) in &self.0 {
+            tag.hash(state);
+            value.to_bits().hash(state);
+        }
+    }
+}
+
+cast! {
+    FontVariations,
+    self => self.0
+        .into_iter()
+        .map(|(tag, num)| {
+            let bytes = tag.to_bytes();
+            let key = std::str::from_utf8(&bytes).unwrap_or_default();
+            (key.into(), num.into_value())
+        })
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| {
+            if k.len() != 4 || !k.bytes().all(|b| matches!(b, b' '..=b'~')) {
+                bail!(
+                    "invalid variation axis {}", k.repr();
+                    hint: "axes are identified by four-character tags like `wght`"
+                );
+            }
+            let num = v.cast::<f64>()?;
+            let tag = Tag::from_bytes(k.as_bytes().try_into().unwrap());
+            Ok((tag, num))
+        })
+        .collect::<HintedStrResult<_>>()?),
+}
+
+impl Fold for FontVariations {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
+/// Collect the coordinates on the variation axes of variable fonts.
+///
+/// Later entries take precedence over earlier ones.
+pub(crate) fn variations(styles: StyleChain) -> Vec<(Tag, f32)> {
+    let variant = variant(styles);
+    let mut coords = vec![
+        (Tag::from_bytes(b"wght"), f32::from(variant.weight.to_number())),
+        (Tag::from_bytes(b"wdth"), (variant.stretch.to_ratio().get() * 100.0) as f32),
+    ];
+
+    for (tag, value) in TextElem::variations_in(styles).0 {
+        coords.push((tag, value as f32));
+    }
+
+    coords
+}
+
+/// Ensure that the font list has a font with each of the variation axes that
+/// are set explicitly.
+pub(crate) fn check_variations(
+    engine: &Engine,
+    span: Span,
+    styles: StyleChain,
+) -> SourceResult<()> {
+    let variations = TextElem::variations_in(styles);
+    if variations.0.is_empty() {
+        return Ok(());
+    }
+
+    let list = TextElem::font_in(styles);
+    let set = font_set(list, TextElem::package_fonts_in(styles));
+    let empty = FontBook::new();
+    let book = LayeredFontBook {
+        top: set.as_ref().map_or(&empty, |set| &set.book),
+        base: engine.world.book(),
+    };
+    let infos: Vec<&FontInfo> = list
+        .into_iter()
+        .flat_map(|family| book.select_family(family.as_str()))
+        .filter_map(|id| book.info(id))
+        .collect();
+
+    // Unknown families are already reported when selecting fonts.
+    if infos.is_empty() {
+        return Ok(());
+    }
+
+    for (tag, _) in &variations.0 {
+        if !infos.iter().any(|info| info.axis(*tag).is_some()) {
+            bail!(
+                span, "the font has no variation axis `{tag}`";
+                hint: "this may be a static font or use a different tag for the axis"
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Collect the OpenType features to apply.
 pub(crate) fn features(styles: StyleChain) -> Vec<Feature> {
     let mut tags = vec![];
@@ -0,0 +1,248 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, Cast, Content, NativeElement, Packed, Resolve, SequenceElem, Show,
+    StyleChain, StyledElem,
+};
+use crate::layout::{
+    Abs, Axes, Em, FixAlignment, Frame, HAlignment, InlineElem, InlineItem, Length,
+    Point, Regions, Size,
+};
+use crate::model::{EmphElem, StrongElem};
+use crate::text::{TextElem, TextSize};
+use crate::utils::Numeric;
+
+/// Ruby annotations above or below base text.
+///
+/// Ruby are small annotations that indicate the pronunciation or meaning of
+/// base text. They are most commonly used to provide the reading of Chinese
+/// characters in Japanese (furigana) and Chinese (zhuyin or pinyin).
+///
+/// A ruby annotation always stays on the same line as its base text.
+///
+/// # Example
+/// ```example
+/// #set text(lang: "ja")
+/// #ruby[東京][とうきょう]に行く。
+/// ```
+///
+/// # Mono and group ruby
+/// When the annotation is given as content, it spans the whole base text
+/// (group ruby). When it is given as an array, each of its items annotates
+/// one character of the base text (mono ruby). Mono ruby may be broken across
+/// lines between the characters.
+///
+/// ```example
+/// #set text(lang: "ja")
+/// #ruby([漢字], ("かん", "じ"))
+/// ```
+#[elem(Show)]
+pub struct RubyElem {
+    /// Where to place the annotation.
+    ///
+    /// ```example
+    /// #ruby(position: "under")[北京][Běijīng]
+    /// ```
+    #[default(RubyPosition::Over)]
+    pub position: RubyPosition,
+
+    /// How to align an annotation that is narrower than its base text.
+    /// Annotations that are wider than their base text are always centered.
+    ///
+    /// ```example
+    /// #set ruby(align: start)
+    /// #ruby[東京][とうきょう]
+    /// ```
+    #[default(HAlignment::Center)]
+    pub align: HAlignment,
+
+    /// The font size of the annotation.
+    #[default(TextSize(Em::new(0.5).into()))]
+    pub size: TextSize,
+
+    /// The gap between the base text and the annotation.
+    #[resolve]
+    pub gap: Length,
+
+    /// Whether an annotation that is wider than its base text may overhang
+    /// the adjacent text.
+    ///
+    /// If enabled, the annotation may extend over adjacent spacing and
+    /// punctuation by up to half of the annotation's font size on each side.
+    /// It never extends over other base characters, including those of the
+    /// same ruby element. Where it can't overhang, the base text is spaced out
+    /// to make room for the annotation.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// 「#ruby[春][はる]」と#ruby[春][はる]。
+    /// ```
+    #[default(true)]
+    pub overhang: bool,
+
+    /// The base text.
+    #[required]
+    pub body: Content,
+
+    /// The annotation. Either content that spans the whole base text or an
+    /// array with the annotation for each character of the base text.
+    #[required]
+    pub annotation: RubyText,
+}
+
+impl Show for Packed<RubyElem> {
+    #[typst_macros::time(name = "ruby", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(InlineElem::layouter(self.clone(), layout_ruby).pack())
+    }
+}
+
+/// Where to place ruby annotations.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RubyPosition {
+    /// Above the base text (or to its right in vertical text).
+    Over,
+    /// Below the base text (or to its left in vertical text).
+    Under,
+}
+
+/// The annotation of a ruby element.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum RubyText {
+    /// An annotation for each character of the base text.
+    Mono(Vec<Content>),
+    /// An annotation spanning the whole base text.
+    Group(Content),
+}
+
+cast! {
+    RubyText,
+    self => match self {
+        Self::Mono(v) => v.into_value(),
+        Self::Group(v) => v.into_value(),
+    },
+    v: Vec<Content> => Self::Mono(v),
+    v: Content => Self::Group(v),
+}
+
+/// Lay out ruby annotations with their base text.
+fn layout_ruby(
+    elem: &Packed<RubyElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let pairs = match elem.annotation() {
+        RubyText::Group(annotation) => vec![(elem.body().clone(), annotation.clone())],
+        RubyText::Mono(annotations) => {
+            let mut bases = vec![];
+            split_graphemes(elem.body(), &mut bases);
+            if bases.len() != annotations.len() {
+                bail!(
+                    elem.span(),
+                    "expected {} annotations, one for each base character, found {}",
+                    bases.len(),
+                    annotations.len()
+                );
+            }
+
+            bases.into_iter().zip(annotations.iter().cloned()).collect()
+        }
+    };
+
+    let position = elem.position(styles);
+    let align = elem.align(styles).fix(TextElem::dir_in(styles));
+    let size = elem.size(styles);
+    let gap = elem.gap(styles);
+    let overhang =
+        if elem.overhang(styles) { size.0.resolve(styles) / 2.0 } else { Abs::zero() };
+
+    let pod = Regions::one(region, Axes::splat(false));
+    let count = pairs.len();
+    let mut items = vec![];
+    for (i, (base, annotation)) in pairs.into_iter().enumerate() {
+        let base = base.layout(engine, styles, pod)?.into_frame();
+        let annotation = annotation
+            .styled(TextElem::set_size(size))
+            .layout(engine, styles, pod)?
+            .into_frame();
+
+        // Center a wide annotation above the base text. Only its outer ends
+        // may overhang, so that it doesn't extend over the neighbouring base
+        // characters of a mono ruby.
+        let excess = ((annotation.width() - base.width()) / 2.0).max(Abs::zero());
+        let left = if i == 0 { excess.min(overhang) } else { Abs::zero() };
+        let right = if i + 1 == count { excess.min(overhang) } else { Abs::zero() };
+        let width = base.width() + 2.0 * excess - left - right;
+        let base_x = excess - left;
+        let annotation_x = if annotation.width() > base.width() {
+            -left
+        } else {
+            base_x + align.position(base.width() - annotation.width())
+        };
+
+        let height = base.height() + gap + annotation.height();
+        let mut frame = Frame::soft(Size::new(width, height));
+        let (base_y, annotation_y) = match position {
+            RubyPosition::Over => (annotation.height() + gap, Abs::zero()),
+            RubyPosition::Under => (Abs::zero(), base.height() + gap),
+        };
+
+        frame.set_baseline(base_y + base.baseline());
+        frame.push_frame(Point::new(base_x, base_y), base);
+        frame.push_frame(Point::new(annotation_x, annotation_y), annotation);
+        items.push(if left.is_zero() && right.is_zero() {
+            InlineItem::Frame(frame)
+        } else {
+            InlineItem::Overhang(frame, left, right)
+        });
+    }
+
+    Ok(items)
+}
+
+/// Split the base text of a mono ruby into its graphemes, keeping the styles
+/// of each.
+///
+/// Content that can't be split, like a box, counts as a single grapheme.
+fn split_graphemes(content: &Content, out: &mut Vec<Content>) {
+    if let Some(elem) = content.to_packed::<TextElem>() {
+        let span = elem.span();
+        out.extend(
+            elem.text()
+                .graphemes(true)
+                .map(|grapheme| TextElem::packed(grapheme).spanned(span)),
+        );
+    } else if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        for child in &sequence.children {
+            split_graphemes(child, out);
+        }
+    } else if let Some(styled) = content.to_packed::<StyledElem>() {
+        let mut children = vec![];
+        split_graphemes(&styled.child, &mut children);
+        out.extend(
+            children
+                .into_iter()
+                .map(|child| child.styled_with_map(styled.styles.clone())),
+        );
+    } else if let Some(emph) = content.to_packed::<EmphElem>() {
+        let mut children = vec![];
+        split_graphemes(emph.body(), &mut children);
+        out.extend(children.into_iter().map(|child| {
+            EmphElem::clone(emph).with_body(child).pack().spanned(emph.span())
+        }));
+    } else if let Some(strong) = content.to_packed::<StrongElem>() {
+        let mut children = vec![];
+        split_graphemes(strong.body(), &mut children);
+        out.extend(children.into_iter().map(|child| {
+            StrongElem::clone(strong)
+                .with_body(child)
+                .pack()
+                .spanned(strong.span())
+        }));
+    } else {
+        out.push(content.clone());
+    }
+}
@@ -4,8 +4,7 @@ use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{elem, Content, Packed, SequenceElem, Show, StyleChain};
 use crate::layout::{Em, Length};
-use crate::text::{variant, SpaceElem, TextElem, TextSize};
-use crate::World;
+use crate::text::{select_font, variant, SpaceElem, TextElem, TextSize};
 
 /// Renders text in subscript.
 ///
@@ -151,12 +150,8 @@ fn search_text(content: &Content, sub: bool) -> Option<EcoString> {
 /// Checks whether the first retrievable family contains all code points of the
 /// given string.
 fn is_shapable(engine: &Engine, text: &str, styles: StyleChain) -> bool {
-    let world = engine.world;
     for family in TextElem::font_in(styles) {
-        if let Some(font) = world
-            .book()
-            .select(family.as_str(), variant(styles))
-            .and_then(|id| world.font(id))
+        if let Some(font) = select_font(engine, styles, family.as_str(), variant(styles))
         {
             return text.chars().all(|c| font.ttf().glyph_index(c).is_some());
         }
@@ -22,17 +22,15 @@ use crate::text::TextElem;
 ///
 /// # Smallcaps fonts
 /// By default, this enables the OpenType `smcp` feature for the font. Not all
-/// fonts support this feature. Sometimes smallcaps are part of a dedicated
-/// font. This is, for example, the case for the _Latin Modern_ family of fonts.
-/// In those cases, you can use a show-set rule to customize the appearance of
-/// the text in smallcaps:
+/// fonts support this feature. For fonts that don't, Typst synthesizes
+/// smallcaps by rendering lowercase letters as scaled-down capitals. Sometimes
+/// smallcaps are part of a dedicated font. This is, for example, the case for
+/// the _Latin Modern_ family of fonts. In those cases, you can use a show-set
+/// rule to customize the appearance of the text in smallcaps:
 ///
 /// ```typ
 /// #show smallcaps: set text(font: "Latin Modern Roman Caps")
 /// ```
-///
-/// In the future, this function will support synthesizing smallcaps from normal
-/// letters, but this is not yet implemented.
 #[elem(title = "Small Capitals", Show)]
 pub struct SmallcapsElem {
     /// The content to display in small capitals.
@@ -0,0 +1,230 @@
+use kurbo::{BezPath, PathEl};
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::layout::{
+    Abs, Angle, Axes, BlockElem, Em, FixAlignment, Frame, FrameItem, HAlignment, Length,
+    Point, Region, Regions, Rel, Size, Transform,
+};
+use crate::text::{TextElem, TextItem};
+use crate::utils::SliceExt;
+use crate::visualize::{find_outline, Path, PathItem};
+
+/// Text that follows a curve.
+///
+/// The text is laid out along the outline of a [path], [circle], [ellipse],
+/// or any other shape. Each cluster of glyphs is placed with its baseline on
+/// the outline and rotated to follow the outline's direction. On open
+/// outlines, glyphs that would lie beyond either end are left out, while on
+/// closed outlines the text wraps around.
+///
+/// The shape itself is drawn as usual. To only show the text, set the shape's
+/// stroke to `{none}`. Only the text follows the outline: Decorations and
+/// other content in the body are left out.
+///
+/// # Example
+/// ```example
+/// #textpath(
+///   circle(radius: 40pt, stroke: none),
+///   start: 25%,
+///   align: center,
+/// )[Round and round it goes]
+/// ```
+#[elem(title = "Text on a Path", Show)]
+pub struct TextpathElem {
+    /// Where along the outline to position the text, relative to the length
+    /// of the outline.
+    ///
+    /// ```example
+    /// #let wave = path(
+    ///   ((0pt, 20pt), (20pt, -20pt)),
+    ///   ((120pt, 20pt), (20pt, 20pt)),
+    /// )
+    ///
+    /// #textpath(wave, start: 10pt)[On the wave]
+    /// ```
+    #[resolve]
+    pub start: Rel<Length>,
+
+    /// How to align the text to its start position.
+    ///
+    /// With `{start}`, the text begins at the start position. With `{center}`
+    /// and `{end}`, it is centered on it and ends at it, respectively.
+    #[default(HAlignment::Start)]
+    pub align: HAlignment,
+
+    /// The shape whose outline the text follows.
+    #[required]
+    pub path: Content,
+
+    /// The text.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<TextpathElem> {
+    #[typst_macros::time(name = "textpath", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(BlockElem::single_layouter(self.clone(), layout_textpath).pack())
+    }
+}
+
+/// Lay out text along the outline of a shape.
+fn layout_textpath(
+    elem: &Packed<TextpathElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let pod = Regions::one(region.size, Axes::splat(false));
+    let mut frame = elem.path().clone().layout(engine, styles, pod)?.into_frame();
+    let Some((outline, _)) = find_outline(&frame) else {
+        bail!(
+            elem.span(),
+            "failed to find the outline of the shape";
+            hint: "try a path, circle, or ellipse"
+        );
+    };
+
+    let outline = Outline::new(&outline);
+    if outline.length <= Abs::zero() {
+        return Ok(frame);
+    }
+
+    // Lay out the text on a single line.
+    let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+    let text = elem.body().clone().layout(engine, styles, pod)?.into_frame();
+    let align = elem.align(styles).fix(TextElem::dir_in(styles));
+    let start =
+        elem.start(styles).relative_to(outline.length) - align.position(text.width());
+
+    let mut items = vec![];
+    find_text(&mut items, &text, Transform::identity());
+    let baseline = items.first().map_or(text.baseline(), |(ts, _)| ts.ty);
+
+    // Place each cluster of glyphs at the point of the outline below its
+    // center, rotated along the outline's direction there. Transformations of
+    // the body, like scaling, are applied to each cluster on its own.
+    for (ts, item) in items {
+        let mut x = Abs::zero();
+        for (_, glyphs) in item.glyphs.group_by_key(|glyph| glyph.range.clone()) {
+            let width =
+                glyphs.iter().map(|glyph| glyph.x_advance).sum::<Em>().at(item.size);
+            let center = Point::with_x(x + width / 2.0).transform(ts);
+            if let Some((point, angle)) = outline.sample(start + center.x) {
+                let cluster = TextItem { glyphs: glyphs.to_vec(), ..item.clone() };
+                let mut piece = Frame::soft(Size::zero());
+                piece.push(Point::with_x(x), FrameItem::Text(cluster));
+                piece.transform(
+                    Transform::rotate(angle)
+                        .pre_concat(Transform::translate(-center.x, -baseline))
+                        .pre_concat(ts),
+                );
+                frame.push_frame(point, piece);
+            }
+            x += width;
+        }
+    }
+
+    Ok(frame)
+}
+
+/// Find the text items in the frame together with their accumulated
+/// transformations.
+fn find_text<'a>(
+    items: &mut Vec<(Transform, &'a TextItem)>,
+    frame: &'a Frame,
+    ts: Transform,
+) {
+    for (pos, item) in frame.items() {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Group(group) => {
+                find_text(items, &group.frame, ts.pre_concat(group.transform))
+            }
+            FrameItem::Text(text) => items.push((ts, text)),
+            _ => {}
+        }
+    }
+}
+
+/// An outline flattened into line segments, which can be sampled by the
+/// distance along it.
+struct Outline {
+    /// The segments with the distance along the outline at which they start.
+    segments: Vec<(Abs, Point, Point)>,
+    /// The total length of the outline.
+    length: Abs,
+    /// Whether the outline ends where it starts.
+    closed: bool,
+}
+
+impl Outline {
+    /// Flatten a bezier path.
+    fn new(path: &Path) -> Self {
+        let point = |p: Point| kurbo::Point::new(p.x.to_raw(), p.y.to_raw());
+        let mut bez = BezPath::new();
+        for item in &path.0 {
+            match *item {
+                PathItem::MoveTo(p) => bez.move_to(point(p)),
+                PathItem::LineTo(p) => bez.line_to(point(p)),
+                PathItem::CubicTo(p1, p2, p3) => {
+                    bez.curve_to(point(p1), point(p2), point(p3))
+                }
+                PathItem::ClosePath => bez.close_path(),
+            }
+        }
+
+        let mut segments = vec![];
+        let mut length = Abs::zero();
+        let mut first = None;
+        let mut start = Point::zero();
+        let mut prev = Point::zero();
+        kurbo::flatten(&bez, 0.05, |el| {
+            let to = match el {
+                PathEl::MoveTo(p) => {
+                    start = Point::new(Abs::raw(p.x), Abs::raw(p.y));
+                    prev = start;
+                    first.get_or_insert(start);
+                    return;
+                }
+                PathEl::LineTo(p) => Point::new(Abs::raw(p.x), Abs::raw(p.y)),
+                PathEl::ClosePath => start,
+                _ => return,
+            };
+
+            let distance = (to - prev).hypot();
+            if distance > Abs::zero() {
+                segments.push((length, prev, to));
+                length += distance;
+            }
+            prev = to;
+        });
+
+        let closed = first.is_some_and(|first| (prev - first).hypot() < Abs::pt(0.01));
+        Self { segments, length, closed }
+    }
+
+    /// The point at the given distance along the outline and the outline's
+    /// direction there.
+    fn sample(&self, distance: Abs) -> Option<(Point, Angle)> {
+        let distance = if self.closed {
+            Abs::raw(distance.to_raw().rem_euclid(self.length.to_raw()))
+        } else if distance >= Abs::zero() && distance <= self.length {
+            distance
+        } else {
+            return None;
+        };
+
+        let i = self
+            .segments
+            .partition_point(|&(start, ..)| start <= distance)
+            .saturating_sub(1);
+        let (start, from, to) = *self.segments.get(i)?;
+        let delta = to - from;
+        let t = (distance - start) / delta.hypot();
+        let angle = Angle::rad(delta.y.to_raw().atan2(delta.x.to_raw()));
+        Some((from + delta * t, angle))
+    }
+}
@@ -8,7 +8,8 @@ use kurbo::Vec2;
 
 use crate::diag::{bail, SourceResult};
 use crate::foundations::{
-    array, cast, func, scope, ty, Args, Array, Cast, Func, IntoValue, Repr, Smart,
+    array, cast, func, scope, ty, Args, Array, Cast, Func, IntoValue, Reflect, Repr,
+    Smart, Value,
 };
 use crate::layout::{Angle, Axes, Dir, Quadrant, Ratio};
 use crate::syntax::{Span, Spanned};
@@ -18,8 +19,11 @@ use crate::visualize::{Color, ColorSpace, WeightedColor};
 ///
 /// Typst supports linear gradients through the
 /// [`gradient.linear` function]($gradient.linear), radial gradients through
-/// the [`gradient.radial` function]($gradient.radial), and conic gradients
-/// through the [`gradient.conic` function]($gradient.conic).
+/// the [`gradient.radial` function]($gradient.radial), conic gradients
+/// through the [`gradient.conic` function]($gradient.conic), and mesh
+/// gradients through the [`gradient.mesh` function]($gradient.mesh). Mesh
+/// gradients that follow a path are created with the
+/// [`gradient.along` function]($gradient.along).
 ///
 /// A gradient can be used for the following purposes:
 /// - As a fill to paint the interior of a shape:
@@ -180,6 +184,7 @@ pub enum Gradient {
     Linear(Arc<LinearGradient>),
     Radial(Arc<RadialGradient>),
     Conic(Arc<ConicGradient>),
+    Mesh(Arc<MeshGradient>),
 }
 
 #[scope]
@@ -455,6 +460,213 @@ impl Gradient {
         })))
     }
 
+    /// Creates a new mesh gradient, in which colors are interpolated between
+    /// the corners of a grid of patches.
+    ///
+    /// The mesh is given as rows of vertices, from top to bottom, and each
+    /// row lists its vertices from left to right. All rows must have the
+    /// same number of vertices. Each four neighboring vertices span a patch,
+    /// inside of which the colors of its corners are blended.
+    ///
+    /// A vertex is either just a color, in which case the vertices are
+    /// spread evenly over the container, or an array of a color and a
+    /// position, like `{(red, (20%, 10%))}`. The position is relative to the
+    /// container. Moving vertices lets the gradient follow the shape of the
+    /// data or drawing it fills. Parts of the container that are not covered
+    /// by any patch stay transparent.
+    ///
+    /// The edges of the patches are straight by default. To curve them, a
+    /// vertex can be followed by control points for its horizontal and its
+    /// vertical edges, like `{(red, (20%, 10%), (5%, 0%), none)}`. Like for a
+    /// [path]($path), a control point is relative to the vertex and is used
+    /// for the edge to the previous vertex in the row (or column) and
+    /// mirrored for the edge to the next one. An array of two control points
+    /// sets both of them. The result is a mesh of Coons patches.
+    ///
+    /// ```example
+    /// #rect(
+    ///   width: 100%,
+    ///   height: 60pt,
+    ///   fill: gradient.mesh(
+    ///     (red, yellow, green),
+    ///     (blue, white, purple),
+    ///   ),
+    /// )
+    ///
+    /// #rect(
+    ///   width: 100%,
+    ///   height: 60pt,
+    ///   fill: gradient.mesh(
+    ///     ((red, (0%, 0%)), (yellow, (100%, 0%))),
+    ///     (
+    ///       (blue, (0%, 100%), (-20%, 30%), none),
+    ///       (green, (100%, 100%), (-20%, 30%), none),
+    ///     ),
+    ///   ),
+    /// )
+    /// ```
+    #[func(title = "Mesh Gradient")]
+    pub fn mesh(
+        /// The call site of this function.
+        span: Span,
+        /// The rows of vertices of the mesh.
+        #[variadic]
+        rows: Vec<Spanned<Vec<MeshVertex>>>,
+        /// The color space in which to interpolate the gradient.
+        ///
+        /// Defaults to a perceptually uniform color space called
+        /// [Oklab]($color.oklab).
+        #[named]
+        #[default(ColorSpace::Oklab)]
+        space: ColorSpace,
+        /// The [relative placement](#relativeness) of the gradient.
+        ///
+        /// For an element placed at the root/top level of the document, the parent
+        /// is the page itself. For other elements, the parent is the innermost block,
+        /// box, column, grid, or stack that contains the element.
+        #[named]
+        #[default(Smart::Auto)]
+        relative: Smart<RelativeTo>,
+    ) -> SourceResult<Gradient> {
+        if rows.len() < 2 {
+            bail!(
+                span, "a mesh gradient must have at least two rows";
+                hint: "try a linear gradient instead"
+            );
+        }
+
+        let columns = rows[0].v.len();
+        let positioned = rows[0].v.first().is_some_and(|v| v.position.is_some());
+        for row in &rows {
+            if row.v.len() < 2 {
+                bail!(
+                    row.span,
+                    "each row of a mesh gradient must have at least two vertices"
+                );
+            }
+
+            if row.v.len() != columns {
+                bail!(
+                    row.span,
+                    "all rows of a mesh gradient must have the same number of vertices"
+                );
+            }
+
+            if row.v.iter().any(|vertex| vertex.position.is_some() != positioned) {
+                bail!(
+                    row.span, "either all vertices must have a position or none of them can";
+                    hint: "try adding a position to all vertices"
+                );
+            }
+        }
+
+        let count = rows.len();
+        let rows = rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.v
+                    .into_iter()
+                    .enumerate()
+                    .map(|(j, vertex)| MeshPoint {
+                        color: vertex.color,
+                        position: vertex.position.unwrap_or_else(|| {
+                            Axes::new(
+                                Ratio::new(j as f64 / (columns - 1) as f64),
+                                Ratio::new(i as f64 / (count - 1) as f64),
+                            )
+                        }),
+                        horizontal: vertex.horizontal.map(|handles| handles.0),
+                        vertical: vertex.vertical.map(|handles| handles.0),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Gradient::Mesh(Arc::new(MeshGradient {
+            rows,
+            space,
+            relative,
+            anti_alias: true,
+        })))
+    }
+
+    /// Creates a new gradient that follows a path.
+    ///
+    /// The gradient's stops are spread over the length of the path and each
+    /// color extends perpendicularly to the path, so that the gradient runs
+    /// along it like a ribbon. Parts of the container that are further away
+    /// from the path than half of the gradient's thickness stay transparent.
+    /// The result is a [mesh gradient]($gradient.mesh) of curved patches.
+    ///
+    /// ```example
+    /// #let arch = (
+    ///   ((0%, 80%), (0%, 0%), (25%, -80%)),
+    ///   ((100%, 80%), (-25%, -80%), (0%, 0%)),
+    /// )
+    /// #rect(
+    ///   width: 100%,
+    ///   height: 60pt,
+    ///   fill: gradient.along(arch, ..color.map.rainbow, thickness: 20%),
+    /// )
+    /// ```
+    #[func(title = "Gradient Along a Path")]
+    pub fn along(
+        /// The call site of this function.
+        span: Span,
+        /// The path the gradient follows.
+        ///
+        /// Like for a [path]($path), each vertex is either a point or an array
+        /// of a point and one or two control points. All points are relative to
+        /// the container and control points are relative to their vertex.
+        path: Spanned<Vec<GradientPathVertex>>,
+        /// The color [stops](#stops) of the gradient.
+        #[variadic]
+        stops: Vec<Spanned<GradientStop>>,
+        /// The thickness of the gradient across the path, relative to the
+        /// container.
+        #[named]
+        #[default(Ratio::new(0.1))]
+        thickness: Ratio,
+        /// The color space in which to interpolate the gradient.
+        ///
+        /// Defaults to a perceptually uniform color space called
+        /// [Oklab]($color.oklab).
+        #[named]
+        #[default(ColorSpace::Oklab)]
+        space: ColorSpace,
+        /// The [relative placement](#relativeness) of the gradient.
+        ///
+        /// For an element placed at the root/top level of the document, the parent
+        /// is the page itself. For other elements, the parent is the innermost block,
+        /// box, column, grid, or stack that contains the element.
+        #[named]
+        #[default(Smart::Auto)]
+        relative: Smart<RelativeTo>,
+    ) -> SourceResult<Gradient> {
+        if path.v.len() < 2 {
+            bail!(path.span, "the path of a gradient must have at least two vertices");
+        }
+
+        if stops.len() < 2 {
+            bail!(
+                span, "a gradient must have at least two stops";
+                hint: "try filling the shape with a single color instead"
+            );
+        }
+
+        let stops = process_stops(&stops)?;
+        let rows =
+            ribbon(&path.v, thickness.get() / 2.0, |t| sample_stops(&stops, space, t));
+
+        Ok(Gradient::Mesh(Arc::new(MeshGradient {
+            rows,
+            space,
+            relative,
+            anti_alias: true,
+        })))
+    }
+
     /// Creates a sharp version of this gradient.
     ///
     /// Sharp gradients have discrete jumps between colors, instead of a
@@ -482,6 +694,10 @@ impl Gradient {
             bail!(steps.span, "sharp gradients must have at least two stops");
         }
 
+        if let Self::Mesh(_) = self {
+            bail!(steps.span, "mesh gradients cannot be made sharp");
+        }
+
         if smoothness.v.get() < 0.0 || smoothness.v.get() > 1.0 {
             bail!(smoothness.span, "smoothness must be between 0 and 1");
         }
@@ -549,6 +765,7 @@ impl Gradient {
                 relative: conic.relative,
                 anti_alias: false,
             })),
+            Self::Mesh(_) => unreachable!(),
         })
     }
 
@@ -577,6 +794,10 @@ impl Gradient {
             bail!(repetitions.span, "must repeat at least once");
         }
 
+        if let Self::Mesh(_) = self {
+            bail!(repetitions.span, "mesh gradients cannot be repeated");
+        }
+
         let n = repetitions.v;
         let mut stops = std::iter::repeat(self.stops_ref())
             .take(n)
@@ -631,6 +852,7 @@ impl Gradient {
                 relative: conic.relative,
                 anti_alias: conic.anti_alias,
             })),
+            Self::Mesh(_) => unreachable!(),
         })
     }
 
@@ -641,10 +863,14 @@ impl Gradient {
             Self::Linear(_) => Self::linear_data().into(),
             Self::Radial(_) => Self::radial_data().into(),
             Self::Conic(_) => Self::conic_data().into(),
+            Self::Mesh(_) => Self::mesh_data().into(),
         }
     }
 
     /// Returns the stops of this gradient.
+    ///
+    /// For mesh gradients, these are the colors of the vertices, row by row,
+    /// without offsets.
     #[func]
     pub fn stops(&self) -> Vec<GradientStop> {
         match self {
@@ -672,6 +898,12 @@ impl Gradient {
                     offset: Some(*offset),
                 })
                 .collect(),
+            Self::Mesh(mesh) => mesh
+                .rows
+                .iter()
+                .flatten()
+                .map(|point| GradientStop { color: point.color, offset: None })
+                .collect(),
         }
     }
 
@@ -682,6 +914,7 @@ impl Gradient {
             Self::Linear(linear) => linear.space,
             Self::Radial(radial) => radial.space,
             Self::Conic(conic) => conic.space,
+            Self::Mesh(mesh) => mesh.space,
         }
     }
 
@@ -692,6 +925,7 @@ impl Gradient {
             Self::Linear(linear) => linear.relative,
             Self::Radial(radial) => radial.relative,
             Self::Conic(conic) => conic.relative,
+            Self::Mesh(mesh) => mesh.relative,
         }
     }
 
@@ -702,6 +936,7 @@ impl Gradient {
             Self::Linear(linear) => Some(linear.angle),
             Self::Radial(_) => None,
             Self::Conic(conic) => Some(conic.angle),
+            Self::Mesh(_) => None,
         }
     }
 
@@ -710,6 +945,9 @@ impl Gradient {
     /// The position is either a position along the gradient (a [ratio] between
     /// `{0%}` and `{100%}`) or an [angle]. Any value outside of this range will
     /// be clamped.
+    ///
+    /// Mesh gradients are sampled along the diagonal from the top-left to
+    /// the bottom-right corner of their container.
     #[func]
     pub fn sample(
         &self,
@@ -722,6 +960,7 @@ impl Gradient {
             Self::Linear(linear) => sample_stops(&linear.stops, linear.space, value),
             Self::Radial(radial) => sample_stops(&radial.stops, radial.space, value),
             Self::Conic(conic) => sample_stops(&conic.stops, conic.space, value),
+            Self::Mesh(mesh) => mesh.sample(value, value),
         }
     }
 
@@ -751,16 +990,22 @@ impl Gradient {
             Self::Conic(conic) => {
                 Arc::make_mut(conic).relative = Smart::Custom(relative);
             }
+            Self::Mesh(mesh) => {
+                Arc::make_mut(mesh).relative = Smart::Custom(relative);
+            }
         }
 
         self
     }
     /// Returns a reference to the stops of this gradient.
+    ///
+    /// Mesh gradients have no stops.
     pub fn stops_ref(&self) -> &[(Color, Ratio)] {
         match self {
             Gradient::Linear(linear) => &linear.stops,
             Gradient::Radial(radial) => &radial.stops,
             Gradient::Conic(conic) => &conic.stops,
+            Gradient::Mesh(_) => &[],
         }
     }
 
@@ -821,6 +1066,7 @@ impl Gradient {
                 );
                 ((-y.atan2(x) + PI + angle.to_rad()) % TAU) / TAU
             }
+            Self::Mesh(mesh) => return mesh.sample(x as f64, y as f64),
         };
 
         self.sample(RatioOrAngle::Ratio(Ratio::new(t.clamp(0.0, 1.0))))
@@ -832,6 +1078,7 @@ impl Gradient {
             Self::Linear(linear) => linear.anti_alias,
             Self::Radial(radial) => radial.anti_alias,
             Self::Conic(conic) => conic.anti_alias,
+            Self::Mesh(mesh) => mesh.anti_alias,
         }
     }
 
@@ -868,6 +1115,7 @@ impl Debug for Gradient {
             Self::Linear(v) => v.fmt(f),
             Self::Radial(v) => v.fmt(f),
             Self::Conic(v) => v.fmt(f),
+            Self::Mesh(v) => v.fmt(f),
         }
     }
 }
@@ -878,6 +1126,7 @@ impl Repr for Gradient {
             Self::Radial(radial) => radial.repr(),
             Self::Linear(linear) => linear.repr(),
             Self::Conic(conic) => conic.repr(),
+            Self::Mesh(mesh) => mesh.repr(),
         }
     }
 }
@@ -1090,6 +1339,438 @@ impl Repr for ConicGradient {
     }
 }
 
+/// A gradient that interpolates between the colors at the corners of a grid
+/// of patches.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MeshGradient {
+    /// The rows of vertices.
+    pub rows: Vec<Vec<MeshPoint>>,
+    /// The color space in which to interpolate the gradient.
+    pub space: ColorSpace,
+    /// The relative placement of the gradient.
+    pub relative: Smart<RelativeTo>,
+    /// Whether to anti-alias the gradient.
+    pub anti_alias: bool,
+}
+
+/// A vertex of a mesh gradient.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MeshPoint {
+    /// The color at the vertex.
+    pub color: Color,
+    /// The position of the vertex relative to the container.
+    pub position: Axes<Ratio>,
+    /// The control points of the edges to the previous and the next vertex
+    /// in the row, relative to the vertex. The edges are straight if there
+    /// are none.
+    pub horizontal: Option<[Axes<Ratio>; 2]>,
+    /// The control points of the edges to the previous and the next vertex
+    /// in the column, relative to the vertex. The edges are straight if there
+    /// are none.
+    pub vertical: Option<[Axes<Ratio>; 2]>,
+}
+
+impl MeshGradient {
+    /// The number of patches along each axis.
+    pub fn patches(&self) -> Axes<usize> {
+        Axes::new(self.rows[0].len() - 1, self.rows.len() - 1)
+    }
+
+    /// The colors at the corners of the patch whose top-left vertex is in the
+    /// given row and column, in clockwise order starting at the top-left.
+    pub fn colors(&self, row: usize, column: usize) -> [Color; 4] {
+        [
+            self.rows[row][column].color,
+            self.rows[row][column + 1].color,
+            self.rows[row + 1][column + 1].color,
+            self.rows[row + 1][column].color,
+        ]
+    }
+
+    /// The twelve control points of the edges of the patch whose top-left
+    /// vertex is in the given row and column, relative to the container.
+    ///
+    /// The points go around the patch clockwise, starting at its top-left
+    /// corner, such that every third point is a corner.
+    pub fn controls(&self, row: usize, column: usize) -> [Axes<f64>; 12] {
+        let [top, right, bottom, left] = self
+            .edges(row, column)
+            .map(|edge| edge.map(|point| Axes::new(point.x, point.y)));
+        [
+            top[0], top[1], top[2], right[0], right[1], right[2], bottom[3], bottom[2],
+            bottom[1], left[3], left[2], left[1],
+        ]
+    }
+
+    /// The point within a patch at the horizontal and vertical progress `u`
+    /// and `v` from the patch's top-left vertex, relative to the container.
+    pub fn point_at(&self, row: usize, column: usize, u: f64, v: f64) -> Axes<f64> {
+        let point = coons(&self.edges(row, column), u, v);
+        Axes::new(point.x, point.y)
+    }
+
+    /// The color at a point within a patch, given by its horizontal and
+    /// vertical progress `u` and `v` from the patch's top-left vertex.
+    pub fn color_at(&self, row: usize, column: usize, u: f64, v: f64) -> Color {
+        let [c00, c10, c11, c01] = self.colors(row, column);
+
+        // Blend along the top and bottom edges first and then between them,
+        // as hue-based spaces can only mix two colors at once.
+        let mix = |c0, c1, t: f64| {
+            Color::mix_iter(
+                [WeightedColor::new(c0, 1.0 - t), WeightedColor::new(c1, t)],
+                self.space,
+            )
+            .unwrap()
+        };
+        mix(mix(c00, c10, u), mix(c01, c11, u), v)
+    }
+
+    /// Sample the gradient at a point relative to its container.
+    ///
+    /// Points outside of all patches are transparent.
+    pub fn sample(&self, x: f64, y: f64) -> Color {
+        /// The number of cells along each side of a curved patch, within
+        /// which the patch is treated as bilinear.
+        const CELLS: usize = 8;
+
+        let point = Vec2::new(x, y);
+        let patches = self.patches();
+        for row in 0..patches.y {
+            for column in 0..patches.x {
+                let edges = self.edges(row, column);
+                if !may_contain(&edges, point) {
+                    continue;
+                }
+
+                if !self.is_curved(row, column) {
+                    let [p00, p10, p11, p01] =
+                        [edges[0][0], edges[0][3], edges[2][3], edges[2][0]];
+                    if let Some((u, v)) = invert_bilinear(point, p00, p10, p11, p01) {
+                        return self.color_at(row, column, u, v);
+                    }
+                    continue;
+                }
+
+                let n = CELLS as f64;
+                let grid: Vec<Vec<Vec2>> = (0..=CELLS)
+                    .map(|i| {
+                        (0..=CELLS)
+                            .map(|j| coons(&edges, j as f64 / n, i as f64 / n))
+                            .collect()
+                    })
+                    .collect();
+
+                for i in 0..CELLS {
+                    for j in 0..CELLS {
+                        let (p00, p10) = (grid[i][j], grid[i][j + 1]);
+                        let (p11, p01) = (grid[i + 1][j + 1], grid[i + 1][j]);
+                        if let Some((u, v)) = invert_bilinear(point, p00, p10, p11, p01) {
+                            let u = (j as f64 + u) / n;
+                            let v = (i as f64 + v) / n;
+                            return self.color_at(row, column, u, v);
+                        }
+                    }
+                }
+            }
+        }
+
+        Color::BLACK.with_alpha(0.0)
+    }
+
+    /// The edges of a patch as cubic Bézier curves: the top and the bottom
+    /// edge from left to right and the right and the left edge from top to
+    /// bottom.
+    fn edges(&self, row: usize, column: usize) -> [[Vec2; 4]; 4] {
+        let p00 = &self.rows[row][column];
+        let p10 = &self.rows[row][column + 1];
+        let p11 = &self.rows[row + 1][column + 1];
+        let p01 = &self.rows[row + 1][column];
+        [
+            edge(p00, p10, p00.horizontal, p10.horizontal),
+            edge(p10, p11, p10.vertical, p11.vertical),
+            edge(p01, p11, p01.horizontal, p11.horizontal),
+            edge(p00, p01, p00.vertical, p01.vertical),
+        ]
+    }
+
+    /// Whether any edge of the patch is curved.
+    fn is_curved(&self, row: usize, column: usize) -> bool {
+        let [p00, p10] = [&self.rows[row][column], &self.rows[row][column + 1]];
+        let [p01, p11] = [&self.rows[row + 1][column], &self.rows[row + 1][column + 1]];
+        [p00, p10, p01, p11]
+            .iter()
+            .any(|point| point.horizontal.is_some() || point.vertical.is_some())
+    }
+}
+
+impl Repr for MeshGradient {
+    fn repr(&self) -> EcoString {
+        let mut r = EcoString::from("gradient.mesh(");
+
+        if self.space != ColorSpace::Oklab {
+            r.push_str("space: ");
+            r.push_str(&self.space.into_value().repr());
+            r.push_str(", ");
+        }
+
+        if self.relative.is_custom() {
+            r.push_str("relative: ");
+            r.push_str(&self.relative.into_value().repr());
+            r.push_str(", ");
+        }
+
+        for (i, row) in self.rows.iter().enumerate() {
+            r.push('(');
+            for (j, point) in row.iter().enumerate() {
+                let vertex = MeshVertex {
+                    color: point.color,
+                    position: Some(point.position),
+                    horizontal: point.horizontal.map(MeshHandles),
+                    vertical: point.vertical.map(MeshHandles),
+                };
+                r.push_str(&vertex.into_value().repr());
+                if j != row.len() - 1 {
+                    r.push_str(", ");
+                }
+            }
+            r.push(')');
+            if i != self.rows.len() - 1 {
+                r.push_str(", ");
+            }
+        }
+
+        r.push(')');
+        r
+    }
+}
+
+/// An edge between two vertices of a mesh as a cubic Bézier curve. Without
+/// control points, the edge is straight and its control points divide it
+/// into thirds.
+fn edge(
+    from: &MeshPoint,
+    to: &MeshPoint,
+    from_handles: Option<[Axes<Ratio>; 2]>,
+    to_handles: Option<[Axes<Ratio>; 2]>,
+) -> [Vec2; 4] {
+    let a = to_vec2(from.position);
+    let b = to_vec2(to.position);
+    let c1 = from_handles.map_or(a + (b - a) / 3.0, |[_, next]| a + to_vec2(next));
+    let c2 = to_handles.map_or(b - (b - a) / 3.0, |[previous, _]| b + to_vec2(previous));
+    [a, c1, c2, b]
+}
+
+/// Evaluate a cubic Bézier curve.
+fn bezier(&[p0, p1, p2, p3]: &[Vec2; 4], t: f64) -> Vec2 {
+    let s = 1.0 - t;
+    p0 * (s * s * s) + p1 * (3.0 * s * s * t) + p2 * (3.0 * s * t * t) + p3 * (t * t * t)
+}
+
+/// Evaluate a Coons patch, given by its top, right, bottom, and left edges
+/// as returned by [`MeshGradient::edges`].
+fn coons(edges: &[[Vec2; 4]; 4], u: f64, v: f64) -> Vec2 {
+    let [top, right, bottom, left] = edges;
+    let (p00, p10, p11, p01) = (top[0], top[3], bottom[3], bottom[0]);
+    let ruled_u = bezier(top, u) * (1.0 - v) + bezier(bottom, u) * v;
+    let ruled_v = bezier(left, v) * (1.0 - u) + bezier(right, v) * u;
+    let bilinear = p00 * ((1.0 - u) * (1.0 - v))
+        + p10 * (u * (1.0 - v))
+        + p11 * (u * v)
+        + p01 * ((1.0 - u) * v);
+    ruled_u + ruled_v - bilinear
+}
+
+/// Whether a patch may contain the point, judging by the bounding box of its
+/// control points. The box is enlarged slightly since a Coons patch can
+/// bulge beyond its control points.
+fn may_contain(edges: &[[Vec2; 4]; 4], point: Vec2) -> bool {
+    let points = edges.iter().flatten();
+    let (mut min, mut max) = (
+        Vec2::new(f64::INFINITY, f64::INFINITY),
+        -Vec2::new(f64::INFINITY, f64::INFINITY),
+    );
+    for p in points {
+        min = Vec2::new(min.x.min(p.x), min.y.min(p.y));
+        max = Vec2::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    let margin = (max - min) * 0.1;
+    (min.x - margin.x..=max.x + margin.x).contains(&point.x)
+        && (min.y - margin.y..=max.y + margin.y).contains(&point.y)
+}
+
+/// Convert a position relative to the container into a vector.
+fn to_vec2(point: Axes<Ratio>) -> Vec2 {
+    Vec2::new(point.x.get(), point.y.get())
+}
+
+/// Convert a vector into a position relative to the container.
+fn from_vec2(point: Vec2) -> Axes<Ratio> {
+    Axes::new(Ratio::new(point.x), Ratio::new(point.y))
+}
+
+/// Build the vertices of a mesh gradient that follows a path, with the given
+/// distance of its edges from the path and the colors at each progress
+/// along the path.
+fn ribbon(
+    path: &[GradientPathVertex],
+    distance: f64,
+    color: impl Fn(f64) -> Color,
+) -> Vec<Vec<MeshPoint>> {
+    /// The number of patches per segment of the path, so that the colors
+    /// follow the stops closely.
+    const PIECES: usize = 8;
+
+    // Split the path's segments into pieces.
+    let mut pieces = vec![];
+    for pair in path.windows(2) {
+        let a = to_vec2(pair[0].point);
+        let b = to_vec2(pair[1].point);
+        let c1 = match to_vec2(pair[0].from) {
+            handle if handle == Vec2::ZERO => a + (b - a) / 3.0,
+            handle => a + handle,
+        };
+        let c2 = match to_vec2(pair[1].to) {
+            handle if handle == Vec2::ZERO => b - (b - a) / 3.0,
+            handle => b + handle,
+        };
+
+        let mut rest = [a, c1, c2, b];
+        for k in 0..PIECES {
+            let t = 1.0 / (PIECES - k) as f64;
+            let (piece, tail) = split(rest, t);
+            pieces.push(piece);
+            rest = tail;
+        }
+    }
+
+    // The progress along the path at the start of each piece and its end.
+    let lengths: Vec<f64> = pieces
+        .iter()
+        .map(|[p0, p1, p2, p3]| {
+            let chord = (*p3 - *p0).hypot();
+            let polygon = (*p1 - *p0).hypot() + (*p2 - *p1).hypot() + (*p3 - *p2).hypot();
+            (chord + polygon) / 2.0
+        })
+        .collect();
+    let total = lengths.iter().sum::<f64>().max(f64::EPSILON);
+    let mut progress = vec![0.0];
+    for length in &lengths {
+        progress.push(progress.last().unwrap() + length / total);
+    }
+
+    // The direction across the path at each vertex, averaged between the
+    // pieces that meet there.
+    let normal = |tangent: Vec2| Vec2::new(-tangent.y, tangent.x).normalize();
+    let starts = pieces.iter().map(|p| normal(tangent(p[0], p[1], p[2], p[3])));
+    let ends = pieces.iter().map(|p| normal(tangent(p[3], p[2], p[1], p[0]) * -1.0));
+    let starts: Vec<Vec2> = starts.collect();
+    let ends: Vec<Vec2> = ends.collect();
+    let normals: Vec<Vec2> = (0..=pieces.len())
+        .map(|k| {
+            let n = match (k.checked_sub(1).map(|i| ends[i]), starts.get(k)) {
+                (Some(end), Some(&start)) => end + start,
+                (Some(end), None) => end,
+                (None, Some(&start)) => start,
+                (None, None) => unreachable!(),
+            };
+            if n.hypot() > 1e-9 {
+                n.normalize()
+            } else {
+                starts.get(k).copied().unwrap_or(n)
+            }
+        })
+        .collect();
+
+    [distance, -distance]
+        .into_iter()
+        .map(|offset| {
+            (0..=pieces.len())
+                .map(|k| {
+                    let point = match pieces.get(k) {
+                        Some(piece) => piece[0],
+                        None => pieces[k - 1][3],
+                    };
+                    let previous =
+                        k.checked_sub(1).map_or(Vec2::ZERO, |i| pieces[i][2] - point);
+                    let next = pieces.get(k).map_or(Vec2::ZERO, |piece| piece[1] - point);
+                    MeshPoint {
+                        color: color(progress[k].min(1.0)),
+                        position: from_vec2(point + normals[k] * offset),
+                        horizontal: Some([from_vec2(previous), from_vec2(next)]),
+                        vertical: None,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The direction in which a cubic Bézier curve leaves its start point,
+/// falling back to later control points if earlier ones coincide with it.
+fn tangent(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Vec2 {
+    [p1, p2, p3]
+        .into_iter()
+        .map(|p| p - p0)
+        .find(|d| d.hypot() > 1e-9)
+        .unwrap_or(Vec2::new(1.0, 0.0))
+}
+
+/// Split a cubic Bézier curve at `t` with de Casteljau's algorithm.
+fn split([p0, p1, p2, p3]: [Vec2; 4], t: f64) -> ([Vec2; 4], [Vec2; 4]) {
+    let lerp = |a: Vec2, b: Vec2| a + (b - a) * t;
+    let (q0, q1, q2) = (lerp(p0, p1), lerp(p1, p2), lerp(p2, p3));
+    let (r0, r1) = (lerp(q0, q1), lerp(q1, q2));
+    let s = lerp(r0, r1);
+    ([p0, q0, r0, s], [s, r1, q2, p3])
+}
+
+/// Find the progress `(u, v)` at which the bilinear patch with the given
+/// corners (in clockwise order, starting at the top-left) passes through a
+/// point, if it does.
+fn invert_bilinear(
+    point: Vec2,
+    p00: Vec2,
+    p10: Vec2,
+    p11: Vec2,
+    p01: Vec2,
+) -> Option<(f64, f64)> {
+    const EPS: f64 = 1e-9;
+    let cross = |a: Vec2, b: Vec2| a.x * b.y - a.y * b.x;
+    let e = p10 - p00;
+    let f = p01 - p00;
+    let g = p00 - p10 + p11 - p01;
+    let h = point - p00;
+
+    // Solve `k2 * v² + k1 * v + k0 = 0` for `v` and then find `u`.
+    let k2 = cross(g, f);
+    let k1 = cross(e, f) + cross(h, g);
+    let k0 = cross(h, e);
+    let discriminant = k1 * k1 - 4.0 * k0 * k2;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    // Avoid cancellation for nearly parallelogram-shaped patches, where `k2`
+    // is tiny.
+    let q = -0.5 * (k1 + k1.signum() * discriminant.sqrt());
+    let candidates = [(k2.abs() > EPS).then(|| q / k2), (q.abs() > EPS).then(|| k0 / q)];
+
+    let within = |t: f64| (-EPS..=1.0 + EPS).contains(&t);
+    candidates.into_iter().flatten().find_map(|v| {
+        let dx = e.x + g.x * v;
+        let dy = e.y + g.y * v;
+        let u = if dx.abs() > dy.abs() {
+            (h.x - f.x * v) / dx
+        } else if dy.abs() > EPS {
+            (h.y - f.y * v) / dy
+        } else {
+            return None;
+        };
+        (within(u) && within(v)).then(|| (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0)))
+    })
+}
+
 /// What is the gradient relative to.
 #[derive(Cast, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RelativeTo {
@@ -1135,6 +1816,121 @@ cast! {
     }
 }
 
+/// A vertex of a mesh gradient as specified by the user.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MeshVertex {
+    /// The color at this vertex.
+    pub color: Color,
+    /// The position of the vertex relative to the container.
+    pub position: Option<Axes<Ratio>>,
+    /// The control points of the vertex's horizontal edges.
+    pub horizontal: Option<MeshHandles>,
+    /// The control points of the vertex's vertical edges.
+    pub vertical: Option<MeshHandles>,
+}
+
+cast! {
+    MeshVertex,
+    self => match self.position {
+        Some(position) if self.horizontal.is_some() || self.vertical.is_some() => array![
+            self.color.into_value(),
+            array![position.x, position.y],
+            self.horizontal.into_value(),
+            self.vertical.into_value(),
+        ]
+        .into_value(),
+        Some(position) => {
+            array![self.color.into_value(), array![position.x, position.y]].into_value()
+        }
+        None => self.color.into_value(),
+    },
+    color: Color => Self { color, position: None, horizontal: None, vertical: None },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), c, d, None) => Self {
+                color: a.cast()?,
+                position: Some(b.cast()?),
+                horizontal: c.map(Value::cast).transpose()?.flatten(),
+                vertical: d.map(Value::cast).transpose()?.flatten(),
+            },
+            _ => Err("a mesh vertex must contain two to four entries")?,
+        }
+    }
+}
+
+/// The control points of the edges to the previous and the next vertex of a
+/// mesh gradient, relative to the vertex.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MeshHandles(pub [Axes<Ratio>; 2]);
+
+cast! {
+    MeshHandles,
+    self => {
+        let [previous, next] = self.0;
+        if next == previous.map(|v| -v) {
+            array![previous.x, previous.y].into_value()
+        } else {
+            array![array![previous.x, previous.y], array![next.x, next.y]].into_value()
+        }
+    },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) if Ratio::castable(&a) => {
+                let point = Axes::new(a.cast()?, b.cast()?);
+                Self([point, point.map(|v| -v)])
+            }
+            (Some(a), Some(b), None) => Self([a.cast()?, b.cast()?]),
+            _ => Err("expected a control point or an array of two control points")?,
+        }
+    }
+}
+
+/// A vertex of the path of a gradient.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GradientPathVertex {
+    /// The vertex itself.
+    pub point: Axes<Ratio>,
+    /// The control point of the segment to the previous vertex, relative to
+    /// the vertex.
+    pub to: Axes<Ratio>,
+    /// The control point of the segment to the next vertex, relative to the
+    /// vertex.
+    pub from: Axes<Ratio>,
+}
+
+cast! {
+    GradientPathVertex,
+    self => array![
+        array![self.point.x, self.point.y],
+        array![self.to.x, self.to.y],
+        array![self.from.x, self.from.y],
+    ]
+    .into_value(),
+    array: Array => {
+        let zero = Axes::splat(Ratio::zero());
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None, None) if !Axes::<Ratio>::castable(&a) => Self {
+                point: Axes::new(a.cast()?, b.cast()?),
+                to: zero,
+                from: zero,
+            },
+            (Some(a), Some(b), None, None) => {
+                let control: Axes<Ratio> = b.cast()?;
+                Self { point: a.cast()?, to: control, from: control.map(|v| -v) }
+            }
+            (Some(a), Some(b), Some(c), None) => Self {
+                point: a.cast()?,
+                to: b.cast()?,
+                from: c.cast()?,
+            },
+            _ => bail!("path vertex must have 1, 2, or 3 points"),
+        }
+    },
+}
+
 /// A ratio or an angle.
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum RatioOrAngle {
@@ -0,0 +1,156 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Cast, Content, NativeElement, Packed, Show, StyleChain};
+use crate::layout::{Axes, BlockElem, Frame, Ratio, Region, Regions};
+
+/// Composites content as a whole onto what is below it.
+///
+/// Normally, each shape, text run, and image is drawn on its own. A group
+/// first draws all of its content in isolation and then combines the result
+/// with the content below it, with an opacity, a blend mode, and a mask. This
+/// way, overlapping parts of a semi-transparent group don't shine through
+/// each other.
+///
+/// # Example
+/// ```example
+/// #set block(spacing: 0pt)
+/// #rect(fill: aqua, width: 60pt, height: 20pt)
+/// #group(opacity: 50%, move(dy: -10pt)[
+///   #rect(fill: red, width: 40pt, height: 20pt)
+/// ])
+/// ```
+#[elem(Show)]
+pub struct GroupElem {
+    /// How opaque the group is, from `{0%}` for invisible to `{100%}` for
+    /// fully opaque.
+    #[default(Ratio::one())]
+    pub opacity: Ratio,
+
+    /// How the colors of the group are combined with the colors below it.
+    ///
+    /// ```example
+    /// #box(fill: gradient.linear(..color.map.rainbow), inset: 8pt)[
+    ///   #group(blend: "multiply", rect(fill: gray))
+    ///   #group(blend: "difference", rect(fill: white))
+    /// ]
+    /// ```
+    pub blend: BlendMode,
+
+    /// Content that determines which parts of the group are visible.
+    ///
+    /// The mask is laid out in the area of the group. Where the mask is
+    /// transparent, the group is hidden. How visible the group is in other
+    /// places depends on the [`mask-mode`]($group.mask-mode).
+    ///
+    /// ```example
+    /// #group(
+    ///   mask: rect(
+    ///     width: 100%,
+    ///     height: 100%,
+    ///     fill: gradient.linear(black, black.transparentize(100%)),
+    ///   ),
+    /// )[Fading into nothing]
+    /// ```
+    pub mask: Option<Content>,
+
+    /// How the mask determines the visibility of the group.
+    #[default(MaskMode::Alpha)]
+    pub mask_mode: MaskMode,
+
+    /// The content to composite.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<GroupElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(BlockElem::single_layouter(self.clone(), layout_group).pack())
+    }
+}
+
+/// Layout the grouped content.
+#[typst_macros::time(span = elem.span())]
+fn layout_group(
+    elem: &Packed<GroupElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let mut frame = elem
+        .body()
+        .layout(engine, styles, region.into_regions())?
+        .into_frame();
+
+    let mask = match elem.mask(styles) {
+        Some(mask) => {
+            let pod = Regions::one(frame.size(), Axes::splat(true));
+            let frame = mask.layout(engine, styles, pod)?.into_frame();
+            Some(Mask { frame, mode: elem.mask_mode(styles) })
+        }
+        None => None,
+    };
+
+    let opacity = Ratio::new(elem.opacity(styles).get().clamp(0.0, 1.0));
+    frame.composite(opacity, elem.blend(styles), mask);
+    Ok(frame)
+}
+
+/// How the colors of content are combined with the colors below it.
+///
+/// The modes work like the blend modes of the same names in graphics editors
+/// and on the web.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BlendMode {
+    /// The content is drawn on top. This is the default.
+    #[default]
+    Normal,
+    /// Multiplies the colors, which darkens them.
+    Multiply,
+    /// Multiplies the inverted colors, which lightens them.
+    Screen,
+    /// Multiplies or screens the colors, depending on the color below.
+    Overlay,
+    /// Keeps the darker of the colors.
+    Darken,
+    /// Keeps the lighter of the colors.
+    Lighten,
+    /// Brightens the color below to reflect the content's color.
+    ColorDodge,
+    /// Darkens the color below to reflect the content's color.
+    ColorBurn,
+    /// Multiplies or screens the colors, depending on the content's color.
+    HardLight,
+    /// Darkens or lightens the colors, depending on the content's color.
+    SoftLight,
+    /// Subtracts the darker from the lighter color.
+    Difference,
+    /// Like `difference`, but with less contrast.
+    Exclusion,
+    /// Uses the hue of the content's color.
+    Hue,
+    /// Uses the saturation of the content's color.
+    Saturation,
+    /// Uses the hue and saturation of the content's color.
+    Color,
+    /// Uses the luminosity of the content's color.
+    Luminosity,
+}
+
+/// A laid-out mask that determines which parts of a group are visible.
+#[derive(Debug, Clone, Hash)]
+pub struct Mask {
+    /// The mask's content, positioned relative to the group.
+    pub frame: Frame,
+    /// How the content determines the visibility.
+    pub mode: MaskMode,
+}
+
+/// How a mask determines the visibility of content.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum MaskMode {
+    /// The content is as visible as the mask is opaque.
+    Alpha,
+    /// The content is as visible as the mask is bright, so that it is
+    /// hidden by black or transparent parts and shown by white parts.
+    Luminance,
+}
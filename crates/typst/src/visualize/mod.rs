@@ -2,23 +2,27 @@
 
 mod color;
 mod gradient;
+mod group;
 mod image;
 mod line;
 mod paint;
 mod path;
 mod pattern;
 mod polygon;
+mod shadow;
 mod shape;
 mod stroke;
 
 pub use self::color::*;
 pub use self::gradient::*;
+pub use self::group::*;
 pub use self::image::*;
 pub use self::line::*;
 pub use self::paint::*;
 pub use self::path::*;
 pub use self::pattern::*;
 pub use self::polygon::*;
+pub use self::shadow::*;
 pub use self::shape::*;
 pub use self::stroke::*;
 
@@ -47,4 +51,5 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<CircleElem>();
     global.define_elem::<PolygonElem>();
     global.define_elem::<PathElem>();
+    global.define_elem::<GroupElem>();
 }
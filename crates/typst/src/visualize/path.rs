@@ -1,20 +1,28 @@
-use kurbo::{CubicBez, ParamCurveExtrema};
+use kurbo::{BezPath, CubicBez, ParamCurveExtrema, PathEl, QuadBez};
 
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    array, cast, elem, Array, Content, NativeElement, Packed, Reflect, Resolve, Show,
-    Smart, StyleChain,
+    array, cast, elem, scope, Array, Cast, Content, NativeElement, Packed, Reflect,
+    Resolve, Show, Smart, Str, StyleChain,
 };
 use crate::layout::{
-    Abs, Axes, BlockElem, Frame, FrameItem, Length, Point, Region, Rel, Size,
+    Abs, Angle, Axes, BlockElem, Frame, FrameItem, Length, Point, Region, Rel, Size,
+    Transform,
 };
 use crate::visualize::{FixedStroke, Geometry, Paint, Shape, Stroke};
 
-use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
+use PathVertex::{AllControlPoints, Component, Data, MirroredControlPoint, Vertex};
 
 /// A path through a list of points, connected by Bezier curves.
 ///
+/// Besides points, a path can be built from components like
+/// [lines]($path.line), [arcs]($path.arc), and [quadratic
+/// curves]($path.quad), which can also start new subpaths with
+/// [`path.move`]($path.move). Alternatively, a path can be given as path data
+/// in [SVG syntax](https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/d),
+/// with coordinates in points.
+///
 /// # Example
 /// ```example
 /// #path(
@@ -25,18 +33,31 @@ use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
 ///   (100%, 50pt),
 ///   ((50%, 0pt), (40pt, 0pt)),
 /// )
+///
+/// #path(stroke: blue, "M 0 20 Q 30 -20 60 20 t 60 0")
 /// ```
-#[elem(Show)]
+#[elem(scope, Show)]
 pub struct PathElem {
     /// How to fill the path.
     ///
     /// When setting a fill, the default stroke disappears. To create a
     /// rectangle with both fill and stroke, you have to configure both.
-    ///
-    /// Currently all paths are filled according to the [non-zero winding
-    /// rule](https://en.wikipedia.org/wiki/Nonzero-rule).
     pub fill: Option<Paint>,
 
+    /// The rule used to determine which parts of the path are inside of it
+    /// and thus filled.
+    ///
+    /// ```example
+    /// #let star = (
+    ///   (25pt, 0pt), (40pt, 45pt), (0pt, 17pt),
+    ///   (50pt, 17pt), (10pt, 45pt),
+    /// )
+    ///
+    /// #path(fill: red, closed: true, ..star)
+    /// #path(fill: red, closed: true, fill-rule: "even-odd", ..star)
+    /// ```
+    pub fill_rule: FillRule,
+
     /// How to [stroke] the path. This can be:
     ///
     /// Can be set to  `{none}` to disable the stroke or to `{auto}` for a
@@ -54,7 +75,7 @@ pub struct PathElem {
 
     /// The vertices of the path.
     ///
-    /// Each vertex can be defined in 3 ways:
+    /// Each vertex can be defined in 5 ways:
     ///
     /// - A regular point, as given to the [`line`] or [`polygon`] function.
     /// - An array of two points, the first being the vertex and the second
@@ -66,10 +87,35 @@ pub struct PathElem {
     /// - An array of three points, the first being the vertex and the next
     ///   being the control points (control point for curves coming in and out,
     ///   respectively).
+    /// - A path component like [`path.line`]($path.line) or
+    ///   [`path.close`]($path.close). Points that follow a component are
+    ///   connected to its end.
+    /// - A string with path data in SVG syntax.
     #[variadic]
     pub vertices: Vec<PathVertex>,
 }
 
+#[scope]
+impl PathElem {
+    #[elem]
+    type PathMove;
+
+    #[elem]
+    type PathLine;
+
+    #[elem]
+    type PathQuad;
+
+    #[elem]
+    type PathCubic;
+
+    #[elem]
+    type PathArc;
+
+    #[elem]
+    type PathClose;
+}
+
 impl Show for Packed<PathElem> {
     fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
         Ok(BlockElem::single_layouter(self.clone(), layout_path).pack())
@@ -88,57 +134,31 @@ fn layout_path(
         axes.resolve(styles).zip_map(region.size, Rel::relative_to).to_point()
     };
 
-    let vertices = elem.vertices();
-    let points: Vec<Point> = vertices.iter().map(|c| resolve(c.vertex())).collect();
-
-    let mut size = Size::zero();
-    if points.is_empty() {
-        return Ok(Frame::soft(size));
-    }
-
-    // Only create a path if there are more than zero points.
-    // Construct a closed path given all points.
-    let mut path = Path::new();
-    path.move_to(points[0]);
-
-    let mut add_cubic = |from_point: Point,
-                         to_point: Point,
-                         from: PathVertex,
-                         to: PathVertex| {
-        let from_control_point = resolve(from.control_point_from()) + from_point;
-        let to_control_point = resolve(to.control_point_to()) + to_point;
-        path.cubic_to(from_control_point, to_control_point, to_point);
-
-        let p0 = kurbo::Point::new(from_point.x.to_raw(), from_point.y.to_raw());
-        let p1 = kurbo::Point::new(
-            from_control_point.x.to_raw(),
-            from_control_point.y.to_raw(),
-        );
-        let p2 =
-            kurbo::Point::new(to_control_point.x.to_raw(), to_control_point.y.to_raw());
-        let p3 = kurbo::Point::new(to_point.x.to_raw(), to_point.y.to_raw());
-        let extrema = CubicBez::new(p0, p1, p2, p3).bounding_box();
-        size.x.set_max(Abs::raw(extrema.x1));
-        size.y.set_max(Abs::raw(extrema.y1));
-    };
-
-    for (vertex_window, point_window) in vertices.windows(2).zip(points.windows(2)) {
-        let from = vertex_window[0];
-        let to = vertex_window[1];
-        let from_point = point_window[0];
-        let to_point = point_window[1];
-
-        add_cubic(from_point, to_point, from, to);
+    let mut builder = PathBuilder::new();
+    for vertex in elem.vertices() {
+        match vertex {
+            PathVertex::Component(component) => {
+                add_component(&mut builder, component, styles, resolve)
+            }
+            PathVertex::Data(_, path) => add_data(&mut builder, path),
+            _ => {
+                let point = resolve(vertex.vertex());
+                builder.vertex(
+                    point,
+                    point + resolve(vertex.control_point_to()),
+                    point + resolve(vertex.control_point_from()),
+                );
+            }
+        }
     }
 
     if elem.closed(styles) {
-        let from = *vertices.last().unwrap(); // We checked that we have at least one element.
-        let to = vertices[0];
-        let from_point = *points.last().unwrap();
-        let to_point = points[0];
+        builder.close_smoothly();
+    }
 
-        add_cubic(from_point, to_point, from, to);
-        path.close_path();
+    let (path, size) = builder.finish();
+    if path.0.is_empty() {
+        return Ok(Frame::soft(size));
     }
 
     // Prepare fill and stroke.
@@ -150,31 +170,476 @@ fn layout_path(
     };
 
     let mut frame = Frame::soft(size);
-    let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
+    let shape = Shape {
+        geometry: Geometry::Path(path),
+        stroke,
+        fill,
+        fill_rule: elem.fill_rule(styles),
+    };
     frame.push(Point::zero(), FrameItem::Shape(shape, elem.span()));
     Ok(frame)
 }
 
+/// Add a path component to the path.
+fn add_component(
+    builder: &mut PathBuilder,
+    component: &Content,
+    styles: StyleChain,
+    resolve: impl Fn(Axes<Rel<Length>>) -> Point,
+) {
+    // Relative points are resolved against the point at which the component
+    // starts, so this must be done before adding anything to the path.
+    let at =
+        |axes: Axes<Rel<Length>>, relative: bool| builder.locate(resolve(axes), relative);
+
+    if let Some(elem) = component.to_packed::<PathMove>() {
+        let start = at(*elem.start(), elem.relative(styles));
+        builder.move_to(start);
+    } else if let Some(elem) = component.to_packed::<PathLine>() {
+        let end = at(*elem.end(), elem.relative(styles));
+        builder.line_to(end);
+    } else if let Some(elem) = component.to_packed::<PathQuad>() {
+        let relative = elem.relative(styles);
+        let control = at(*elem.control(), relative);
+        let end = at(*elem.end(), relative);
+        builder.quad_to(control, end);
+    } else if let Some(elem) = component.to_packed::<PathCubic>() {
+        let relative = elem.relative(styles);
+        let control_start = at(*elem.control_start(), relative);
+        let control_end = at(*elem.control_end(), relative);
+        let end = at(*elem.end(), relative);
+        builder.cubic_to(control_start, control_end, end);
+    } else if let Some(elem) = component.to_packed::<PathArc>() {
+        let end = at(*elem.end(), elem.relative(styles));
+        let radius = elem.radius().0.resolve(styles);
+        builder.arc_to(
+            radius,
+            elem.rotation(styles),
+            elem.large(styles),
+            elem.clockwise(styles),
+            end,
+        );
+    } else if component.is::<PathClose>() {
+        builder.close();
+    }
+}
+
+/// Add parsed path data to the path.
+fn add_data(builder: &mut PathBuilder, data: &Path) {
+    for item in &data.0 {
+        match *item {
+            PathItem::MoveTo(p) => builder.move_to(p),
+            PathItem::LineTo(p) => builder.line_to(p),
+            PathItem::CubicTo(p1, p2, p3) => builder.cubic_to(p1, p2, p3),
+            PathItem::ClosePath => builder.close(),
+        }
+    }
+}
+
+/// Parse path data in SVG syntax, with coordinates in points.
+fn parse_data(data: &str) -> Option<Path> {
+    let bez = BezPath::from_svg(data).ok()?;
+    let point = |p: kurbo::Point| Point::new(Abs::pt(p.x), Abs::pt(p.y));
+    let mut path = Path::new();
+    let mut start = kurbo::Point::ZERO;
+    let mut from = start;
+    for el in bez.elements() {
+        match *el {
+            PathEl::MoveTo(p) => {
+                path.move_to(point(p));
+                start = p;
+                from = p;
+            }
+            PathEl::LineTo(p) => {
+                path.line_to(point(p));
+                from = p;
+            }
+            PathEl::QuadTo(p1, p2) => {
+                let cubic = QuadBez::new(from, p1, p2).raise();
+                path.cubic_to(point(cubic.p1), point(cubic.p2), point(cubic.p3));
+                from = p2;
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                path.cubic_to(point(p1), point(p2), point(p3));
+                from = p3;
+            }
+            PathEl::ClosePath => {
+                path.close_path();
+                from = start;
+            }
+        }
+    }
+    Some(path)
+}
+
+/// Builds a path from vertices and path components.
+struct PathBuilder {
+    /// The path built so far.
+    path: Path,
+    /// The start of the current or last subpath together with the control
+    /// point for a curve coming into it.
+    start: Option<(Point, Point)>,
+    /// The current point together with the control point for a curve
+    /// leaving it. This is `None` if no subpath is in progress.
+    current: Option<(Point, Point)>,
+}
+
+impl PathBuilder {
+    /// Create a builder for an empty path.
+    fn new() -> Self {
+        Self { path: Path::new(), start: None, current: None }
+    }
+
+    /// The point at which the next segment starts.
+    fn position(&self) -> Point {
+        self.current.or(self.start).map_or(Point::zero(), |(point, _)| point)
+    }
+
+    /// Resolve a point that may be relative to the current position.
+    fn locate(&self, point: Point, relative: bool) -> Point {
+        if relative {
+            self.position() + point
+        } else {
+            point
+        }
+    }
+
+    /// Start a subpath at the current position if none is in progress.
+    fn ensure_started(&mut self) {
+        if self.current.is_none() {
+            self.move_to(self.position());
+        }
+    }
+
+    /// Add a vertex that is connected to the previous one with a cubic curve
+    /// through the given control points. Starts a new subpath if none is in
+    /// progress.
+    fn vertex(&mut self, point: Point, control_in: Point, control_out: Point) {
+        match self.current {
+            Some((_, prev_out)) => self.path.cubic_to(prev_out, control_in, point),
+            None => {
+                self.path.move_to(point);
+                self.start = Some((point, control_in));
+            }
+        }
+        self.current = Some((point, control_out));
+    }
+
+    /// Start a new subpath.
+    fn move_to(&mut self, point: Point) {
+        self.path.move_to(point);
+        self.start = Some((point, point));
+        self.current = Some((point, point));
+    }
+
+    /// Add a straight line.
+    fn line_to(&mut self, point: Point) {
+        self.ensure_started();
+        self.path.line_to(point);
+        self.current = Some((point, point));
+    }
+
+    /// Add a quadratic curve, which is converted into a cubic one.
+    fn quad_to(&mut self, control: Point, point: Point) {
+        self.ensure_started();
+        let from = self.position();
+        self.path.cubic_to(
+            from + (control - from) * (2.0 / 3.0),
+            point + (control - point) * (2.0 / 3.0),
+            point,
+        );
+        self.current = Some((point, point));
+    }
+
+    /// Add a cubic curve.
+    fn cubic_to(&mut self, control_start: Point, control_end: Point, point: Point) {
+        self.ensure_started();
+        self.path.cubic_to(control_start, control_end, point);
+        self.current = Some((point, point));
+    }
+
+    /// Add an elliptical arc, which is approximated with cubic curves. Like
+    /// in SVG, the radii are scaled up if they are too small to reach the
+    /// point, and the arc degenerates to a line if a radius is zero.
+    fn arc_to(
+        &mut self,
+        radii: Axes<Abs>,
+        rotation: Angle,
+        large: bool,
+        clockwise: bool,
+        point: Point,
+    ) {
+        self.ensure_started();
+        let from = self.position();
+        let to_kurbo = |p: Point| kurbo::Point::new(p.x.to_pt(), p.y.to_pt());
+        let arc = kurbo::SvgArc {
+            from: to_kurbo(from),
+            to: to_kurbo(point),
+            radii: kurbo::Vec2::new(radii.x.to_pt().abs(), radii.y.to_pt().abs()),
+            x_rotation: rotation.to_rad(),
+            large_arc: large,
+            sweep: clockwise,
+        };
+
+        match kurbo::Arc::from_svg_arc(&arc) {
+            Some(arc) => {
+                let from_kurbo = |p: kurbo::Point| Point::new(Abs::pt(p.x), Abs::pt(p.y));
+                for el in arc.append_iter(0.01) {
+                    if let PathEl::CurveTo(p1, p2, p3) = el {
+                        self.path.cubic_to(
+                            from_kurbo(p1),
+                            from_kurbo(p2),
+                            from_kurbo(p3),
+                        );
+                    }
+                }
+            }
+            None => self.path.line_to(point),
+        }
+
+        self.current = Some((point, point));
+    }
+
+    /// Close the current subpath with a straight line.
+    fn close(&mut self) {
+        if self.current.take().is_some() {
+            self.path.close_path();
+        }
+    }
+
+    /// Close the current subpath with a curve that takes the control points
+    /// of its last and first vertex into account.
+    fn close_smoothly(&mut self) {
+        if let (Some((_, control_out)), Some((start, control_in))) =
+            (self.current, self.start)
+        {
+            self.path.cubic_to(control_out, control_in, start);
+            self.close();
+        }
+    }
+
+    /// Finish the path and compute the size of the area it spans from the
+    /// origin.
+    fn finish(self) -> (Path, Size) {
+        let point = |p: Point| kurbo::Point::new(p.x.to_raw(), p.y.to_raw());
+        let mut size = Size::zero();
+        let mut from = Point::zero();
+        let mut start = Point::zero();
+        for item in &self.path.0 {
+            let extrema = match *item {
+                PathItem::MoveTo(p) => {
+                    from = p;
+                    start = p;
+                    continue;
+                }
+                PathItem::LineTo(p) => {
+                    kurbo::Line::new(point(from), point(p)).bounding_box()
+                }
+                PathItem::CubicTo(p1, p2, p3) => {
+                    CubicBez::new(point(from), point(p1), point(p2), point(p3))
+                        .bounding_box()
+                }
+                PathItem::ClosePath => {
+                    kurbo::Line::new(point(from), point(start)).bounding_box()
+                }
+            };
+
+            size.x.set_max(Abs::raw(extrema.x1));
+            size.y.set_max(Abs::raw(extrema.y1));
+            from = match *item {
+                PathItem::LineTo(p) | PathItem::CubicTo(_, _, p) => p,
+                _ => start,
+            };
+        }
+
+        (self.path, size)
+    }
+}
+
+/// A rule that determines which parts of a shape are inside of it.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FillRule {
+    /// A point is inside if the outline winds around it in one direction
+    /// more often than in the other. See the [non-zero winding
+    /// rule](https://en.wikipedia.org/wiki/Nonzero-rule).
+    #[default]
+    NonZero,
+    /// A point is inside if a ray from it crosses the outline an odd number
+    /// of times. See the [even-odd
+    /// rule](https://en.wikipedia.org/wiki/Even%E2%80%93odd_rule).
+    EvenOdd,
+}
+
+/// Starts a new subpath of a [path] at a point.
+///
+/// ```example
+/// #path(
+///   fill: blue,
+///   fill-rule: "even-odd",
+///   path.move((0pt, 0pt)),
+///   path.line((40pt, 0pt)),
+///   path.line((20pt, 40pt)),
+///   path.close(),
+///   path.move((12pt, 6pt)),
+///   path.line((28pt, 6pt)),
+///   path.line((20pt, 22pt)),
+///   path.close(),
+/// )
+/// ```
+#[elem(name = "move", title = "Path Move")]
+pub struct PathMove {
+    /// The point at which the subpath starts.
+    #[required]
+    pub start: Axes<Rel<Length>>,
+
+    /// Whether the point is relative to the current point.
+    #[default(false)]
+    pub relative: bool,
+}
+
+/// Adds a straight line to a [path].
+#[elem(name = "line", title = "Path Line")]
+pub struct PathLine {
+    /// The point at which the line ends.
+    #[required]
+    pub end: Axes<Rel<Length>>,
+
+    /// Whether the point is relative to the start of the line.
+    #[default(false)]
+    pub relative: bool,
+}
+
+/// Adds a quadratic Bezier curve to a [path].
+///
+/// ```example
+/// #path(
+///   path.move((0pt, 30pt)),
+///   path.quad((30pt, -30pt), (60pt, 30pt)),
+/// )
+/// ```
+#[elem(name = "quad", title = "Path Quadratic Segment")]
+pub struct PathQuad {
+    /// The control point of the curve.
+    #[required]
+    pub control: Axes<Rel<Length>>,
+
+    /// The point at which the curve ends.
+    #[required]
+    pub end: Axes<Rel<Length>>,
+
+    /// Whether the points are relative to the start of the curve.
+    #[default(false)]
+    pub relative: bool,
+}
+
+/// Adds a cubic Bezier curve to a [path].
+#[elem(name = "cubic", title = "Path Cubic Segment")]
+pub struct PathCubic {
+    /// The control point that affects the curve at its start.
+    #[required]
+    pub control_start: Axes<Rel<Length>>,
+
+    /// The control point that affects the curve at its end.
+    #[required]
+    pub control_end: Axes<Rel<Length>>,
+
+    /// The point at which the curve ends.
+    #[required]
+    pub end: Axes<Rel<Length>>,
+
+    /// Whether the points are relative to the start of the curve.
+    #[default(false)]
+    pub relative: bool,
+}
+
+/// Adds a circular or elliptical arc to a [path].
+///
+/// Of the arcs with the given radius that connect the start and end point,
+/// `large` and `clockwise` select one. If the radius is too small to reach
+/// the end point, it is enlarged.
+///
+/// ```example
+/// #path(
+///   stroke: blue,
+///   path.move((0pt, 20pt)),
+///   path.arc((40pt, 20pt), 20pt, clockwise: true),
+///   path.arc((40pt, 0pt), (20pt, 10pt), rotation: 30deg, relative: true),
+/// )
+/// ```
+#[elem(name = "arc", title = "Path Arc")]
+pub struct PathArc {
+    /// The point at which the arc ends.
+    #[required]
+    pub end: Axes<Rel<Length>>,
+
+    /// The radius of the arc. Either a single length for a circular arc or
+    /// an array with the horizontal and vertical radius of an elliptical one.
+    #[required]
+    pub radius: ArcRadius,
+
+    /// How much the ellipse of an elliptical arc is rotated clockwise.
+    #[default]
+    pub rotation: Angle,
+
+    /// Whether to take the longer of the two possible arcs.
+    #[default(false)]
+    pub large: bool,
+
+    /// Whether the arc runs clockwise.
+    #[default(false)]
+    pub clockwise: bool,
+
+    /// Whether the end point is relative to the start of the arc.
+    #[default(false)]
+    pub relative: bool,
+}
+
+/// The horizontal and vertical radius of an arc.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct ArcRadius(pub Axes<Length>);
+
+cast! {
+    ArcRadius,
+    self => if self.0.x == self.0.y {
+        self.0.x.into_value()
+    } else {
+        self.0.into_value()
+    },
+    v: Length => Self(Axes::splat(v)),
+    v: Axes<Length> => Self(v),
+}
+
+/// Closes the current subpath of a [path] with a straight line to its start.
+#[elem(name = "close", title = "Path Close")]
+pub struct PathClose {}
+
 /// A component used for path creation.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 pub enum PathVertex {
     Vertex(Axes<Rel<Length>>),
     MirroredControlPoint(Axes<Rel<Length>>, Axes<Rel<Length>>),
     AllControlPoints(Axes<Rel<Length>>, Axes<Rel<Length>>, Axes<Rel<Length>>),
+    /// A path component like [`PathMove`] or [`PathArc`].
+    Component(Content),
+    /// Path data in SVG syntax, together with the path parsed from it.
+    Data(Str, Path),
 }
 
 impl PathVertex {
+    /// The vertex's point. Components and path data have none and yield the
+    /// origin.
     pub fn vertex(&self) -> Axes<Rel<Length>> {
         match self {
             Vertex(x) => *x,
             MirroredControlPoint(x, _) => *x,
             AllControlPoints(x, _, _) => *x,
+            Component(_) | Data(..) => Axes::new(Rel::zero(), Rel::zero()),
         }
     }
 
     pub fn control_point_from(&self) -> Axes<Rel<Length>> {
         match self {
-            Vertex(_) => Axes::new(Rel::zero(), Rel::zero()),
+            Vertex(_) | Component(_) | Data(..) => Axes::new(Rel::zero(), Rel::zero()),
             MirroredControlPoint(_, a) => a.map(|x| -x),
             AllControlPoints(_, _, b) => *b,
         }
@@ -182,7 +647,7 @@ impl PathVertex {
 
     pub fn control_point_to(&self) -> Axes<Rel<Length>> {
         match self {
-            Vertex(_) => Axes::new(Rel::zero(), Rel::zero()),
+            Vertex(_) | Component(_) | Data(..) => Axes::new(Rel::zero(), Rel::zero()),
             MirroredControlPoint(_, a) => *a,
             AllControlPoints(_, a, _) => *a,
         }
@@ -195,6 +660,8 @@ cast! {
         Vertex(x) => x.into_value(),
         MirroredControlPoint(x, c) => array![x, c].into_value(),
         AllControlPoints(x, c1, c2) => array![x, c1, c2].into_value(),
+        Component(content) => content.into_value(),
+        Data(data, _) => data.into_value(),
     },
     array: Array => {
         let mut iter = array.into_iter();
@@ -215,6 +682,22 @@ cast! {
             _ => bail!("path vertex must have 1, 2, or 3 points"),
         }
     },
+    data: Str => match parse_data(&data) {
+        Some(path) => Data(data, path),
+        None => bail!("failed to parse path data"),
+    },
+    content: Content => {
+        if !(content.is::<PathMove>()
+            || content.is::<PathLine>()
+            || content.is::<PathQuad>()
+            || content.is::<PathCubic>()
+            || content.is::<PathArc>()
+            || content.is::<PathClose>())
+        {
+            bail!("expected path vertex or path component, found content");
+        }
+        Component(content)
+    },
 }
 
 /// A bezier path.
@@ -269,6 +752,25 @@ impl Path {
         self.0.push(PathItem::ClosePath);
     }
 
+    /// Apply a transformation to all points of the path.
+    pub fn transform(&self, ts: Transform) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|item| match *item {
+                    PathItem::MoveTo(p) => PathItem::MoveTo(p.transform(ts)),
+                    PathItem::LineTo(p) => PathItem::LineTo(p.transform(ts)),
+                    PathItem::CubicTo(p1, p2, p3) => PathItem::CubicTo(
+                        p1.transform(ts),
+                        p2.transform(ts),
+                        p3.transform(ts),
+                    ),
+                    PathItem::ClosePath => PathItem::ClosePath,
+                })
+                .collect(),
+        )
+    }
+
     /// Computes the size of bounding box of this path.
     pub fn bbox_size(&self) -> Size {
         let mut min_x = Abs::inf();
@@ -0,0 +1,86 @@
+use crate::diag::HintedStrResult;
+use crate::foundations::{cast, dict, Dict, FromValue, Resolve, StyleChain};
+use crate::layout::{Abs, Length, Point};
+use crate::visualize::Color;
+
+/// A shadow cast by content onto what is below it.
+///
+/// Can be cast from a color or a dictionary with the keys `dx`, `dy`, `blur`,
+/// `spread`, and `color`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Shadow {
+    /// The horizontal offset.
+    pub dx: Length,
+    /// The vertical offset.
+    pub dy: Length,
+    /// The blur radius.
+    pub blur: Length,
+    /// The spread radius.
+    pub spread: Length,
+    /// The shadow's color.
+    pub color: Color,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Self {
+            dx: Length::zero(),
+            dy: Abs::pt(2.0).into(),
+            blur: Abs::pt(4.0).into(),
+            spread: Length::zero(),
+            color: Color::BLACK.with_alpha(0.4),
+        }
+    }
+}
+
+impl Resolve for Shadow {
+    type Output = FixedShadow;
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        FixedShadow {
+            offset: Point::new(self.dx.resolve(styles), self.dy.resolve(styles)),
+            blur: self.blur.resolve(styles).max(Abs::zero()),
+            spread: self.spread.resolve(styles),
+            color: self.color,
+        }
+    }
+}
+
+cast! {
+    Shadow,
+    self => dict! {
+        "dx" => self.dx,
+        "dy" => self.dy,
+        "blur" => self.blur,
+        "spread" => self.spread,
+        "color" => self.color,
+    }.into_value(),
+    color: Color => Self { color, ..Default::default() },
+    mut dict: Dict => {
+        fn take<T: FromValue>(dict: &mut Dict, key: &str, default: T) -> HintedStrResult<T> {
+            Ok(dict.take(key).ok().map(T::from_value).transpose()?.unwrap_or(default))
+        }
+
+        let default = Self::default();
+        let dx = take(&mut dict, "dx", default.dx)?;
+        let dy = take(&mut dict, "dy", default.dy)?;
+        let blur = take(&mut dict, "blur", default.blur)?;
+        let spread = take(&mut dict, "spread", default.spread)?;
+        let color = take(&mut dict, "color", default.color)?;
+        dict.finish(&["dx", "dy", "blur", "spread", "color"])?;
+        Self { dx, dy, blur, spread, color }
+    },
+}
+
+/// A fully resolved shadow.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FixedShadow {
+    /// How far the shadow is offset from the content.
+    pub offset: Point,
+    /// How far the shadow's edges are blurred. This is never negative.
+    pub blur: Abs,
+    /// How much the shadow grows beyond the content's outline.
+    pub spread: Abs,
+    /// The shadow's color.
+    pub color: Color,
+}
@@ -5,11 +5,11 @@ use crate::engine::Engine;
 use crate::foundations::{elem, Content, NativeElement, Packed, Show, Smart, StyleChain};
 use crate::layout::{
     Abs, Axes, BlockElem, Corner, Corners, Frame, FrameItem, Length, Point, Ratio,
-    Region, Regions, Rel, Sides, Size,
+    Region, Regions, Rel, Sides, Size, Transform,
 };
 use crate::syntax::Span;
 use crate::utils::Get;
-use crate::visualize::{FixedStroke, Paint, Path, Stroke};
+use crate::visualize::{FillRule, FixedShadow, FixedStroke, Paint, Path, Shadow, Stroke};
 
 /// A rectangle with optional content.
 ///
@@ -123,6 +123,24 @@ pub struct RectElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// A shadow that the rectangle casts onto what is below it.
+    ///
+    /// The shadow follows the outline of the rectangle and its content. It
+    /// can be a color or a dictionary with the following keys, all of which
+    /// are optional:
+    /// - `dx`: The horizontal offset of the shadow. Defaults to `{0pt}`.
+    /// - `dy`: The vertical offset of the shadow. Defaults to `{2pt}`.
+    /// - `blur`: How far the shadow's edges are blurred. Defaults to `{4pt}`.
+    /// - `spread`: How much the shadow grows beyond the outline before it is
+    ///   blurred. A negative spread shrinks it instead. Defaults to `{0pt}`.
+    /// - `color`: The shadow's color. Defaults to a translucent black.
+    ///
+    /// ```example
+    /// #rect(fill: white, shadow: (dy: 3pt, blur: 6pt))
+    /// ```
+    #[resolve]
+    pub shadow: Option<Shadow>,
+
     /// The content to place into the rectangle.
     ///
     /// When this is omitted, the rectangle takes on a default size of at most
@@ -146,6 +164,7 @@ impl Show for Packed<RectElem> {
                 elem.inset(styles),
                 elem.outset(styles),
                 elem.radius(styles),
+                elem.shadow(styles),
                 elem.span(),
             )
         })
@@ -227,6 +246,11 @@ pub struct SquareElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// A shadow that the square casts onto what is below it. See the
+    /// [rectangle's documentation]($rect.shadow) for more details.
+    #[resolve]
+    pub shadow: Option<Shadow>,
+
     /// The content to place into the square. The square expands to fit this
     /// content, keeping the 1-1 aspect ratio.
     ///
@@ -251,6 +275,7 @@ impl Show for Packed<SquareElem> {
                 elem.inset(styles),
                 elem.outset(styles),
                 elem.radius(styles),
+                elem.shadow(styles),
                 elem.span(),
             )
         })
@@ -305,6 +330,11 @@ pub struct EllipseElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// A shadow that the ellipse casts onto what is below it. See the
+    /// [rectangle's documentation]($rect.shadow) for more details.
+    #[resolve]
+    pub shadow: Option<Shadow>,
+
     /// The content to place into the ellipse.
     ///
     /// When this is omitted, the ellipse takes on a default size of at most
@@ -328,6 +358,7 @@ impl Show for Packed<EllipseElem> {
                 elem.inset(styles),
                 elem.outset(styles),
                 Corners::splat(None),
+                elem.shadow(styles),
                 elem.span(),
             )
         })
@@ -409,6 +440,11 @@ pub struct CircleElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// A shadow that the circle casts onto what is below it. See the
+    /// [rectangle's documentation]($rect.shadow) for more details.
+    #[resolve]
+    pub shadow: Option<Shadow>,
+
     /// The content to place into the circle. The circle expands to fit this
     /// content, keeping the 1-1 aspect ratio.
     #[positional]
@@ -430,6 +466,7 @@ impl Show for Packed<CircleElem> {
                 elem.inset(styles),
                 elem.outset(styles),
                 Corners::splat(None),
+                elem.shadow(styles),
                 elem.span(),
             )
         })
@@ -453,6 +490,7 @@ fn layout_shape(
     inset: Sides<Option<Rel<Abs>>>,
     outset: Sides<Option<Rel<Abs>>>,
     radius: Corners<Option<Rel<Abs>>>,
+    shadow: Option<FixedShadow>,
     span: Span,
 ) -> SourceResult<Frame> {
     let mut frame;
@@ -524,6 +562,10 @@ fn layout_shape(
         }
     }
 
+    if let Some(shadow) = shadow {
+        frame.shadow(shadow);
+    }
+
     Ok(frame)
 }
 
@@ -561,6 +603,8 @@ pub struct Shape {
     pub fill: Option<Paint>,
     /// The shape's border stroke.
     pub stroke: Option<FixedStroke>,
+    /// The rule that determines which parts of the shape are filled.
+    pub fill_rule: FillRule,
 }
 
 /// A shape's geometry.
@@ -577,12 +621,22 @@ pub enum Geometry {
 impl Geometry {
     /// Fill the geometry without a stroke.
     pub fn filled(self, fill: Paint) -> Shape {
-        Shape { geometry: self, fill: Some(fill), stroke: None }
+        Shape {
+            geometry: self,
+            fill: Some(fill),
+            stroke: None,
+            fill_rule: FillRule::default(),
+        }
     }
 
     /// Stroke the geometry without a fill.
     pub fn stroked(self, stroke: FixedStroke) -> Shape {
-        Shape { geometry: self, fill: None, stroke: Some(stroke) }
+        Shape {
+            geometry: self,
+            fill: None,
+            stroke: Some(stroke),
+            fill_rule: FillRule::default(),
+        }
     }
 
     /// The bounding box of the geometry.
@@ -595,6 +649,44 @@ impl Geometry {
     }
 }
 
+/// Find the outline of the first shape in a frame, together with the rule
+/// that determines its inside.
+pub(crate) fn find_outline(frame: &Frame) -> Option<(Path, FillRule)> {
+    find_outline_impl(frame, Transform::identity())
+}
+
+/// Find the outline of the first shape in a frame, transformed by `ts`.
+fn find_outline_impl(frame: &Frame, ts: Transform) -> Option<(Path, FillRule)> {
+    for (pos, item) in frame.items() {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Group(group) => {
+                if let Some(outline) =
+                    find_outline_impl(&group.frame, ts.pre_concat(group.transform))
+                {
+                    return Some(outline);
+                }
+            }
+            FrameItem::Shape(shape, _) => {
+                let path = match &shape.geometry {
+                    Geometry::Line(to) => {
+                        let mut path = Path::new();
+                        path.move_to(Point::zero());
+                        path.line_to(*to);
+                        path
+                    }
+                    Geometry::Rect(size) => Path::rect(*size),
+                    Geometry::Path(path) => path.clone(),
+                };
+                return Some((path.transform(ts), shape.fill_rule));
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
 /// Produce a shape that approximates an axis-aligned ellipse.
 pub(crate) fn ellipse(
     size: Size,
@@ -617,7 +709,12 @@ pub(crate) fn ellipse(
     path.cubic_to(point(rx, my), point(mx, ry), point(z, ry));
     path.cubic_to(point(-mx, ry), point(-rx, my), point(-rx, z));
 
-    Shape { geometry: Geometry::Path(path), stroke, fill }
+    Shape {
+        geometry: Geometry::Path(path),
+        stroke,
+        fill,
+        fill_rule: FillRule::default(),
+    }
 }
 
 /// Creates a new rectangle as a path.
@@ -680,7 +777,12 @@ fn simple_rect(
     fill: Option<Paint>,
     stroke: Option<FixedStroke>,
 ) -> Vec<Shape> {
-    vec![Shape { geometry: Geometry::Rect(size), fill, stroke }]
+    vec![Shape {
+        geometry: Geometry::Rect(size),
+        fill,
+        stroke,
+        fill_rule: FillRule::default(),
+    }]
 }
 
 fn corners_control_points(
@@ -756,6 +858,7 @@ fn segmented_rect(
             geometry: Geometry::Path(path),
             fill: Some(fill),
             stroke: None,
+            fill_rule: FillRule::default(),
         });
         stroke_insert += 1;
     }
@@ -892,6 +995,7 @@ fn stroke_segment(
         geometry: Geometry::Path(path),
         stroke: Some(stroke),
         fill: None,
+        fill_rule: FillRule::default(),
     }
 }
 
@@ -990,6 +1094,7 @@ fn fill_segment(
         geometry: Geometry::Path(path),
         stroke: None,
         fill: Some(stroke.paint.clone()),
+        fill_rule: FillRule::default(),
     }
 }
 
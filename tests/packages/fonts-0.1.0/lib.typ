@@ -0,0 +1 @@
+#let stem = text(font: "Package Test", fallback: false)[I]
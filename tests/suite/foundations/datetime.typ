@@ -72,6 +72,35 @@
 #test(datetime(day: 1, month: 3, year: 2001).ordinal(), 31 + 28 + 1);
 #test(datetime(day: 31, month: 12, year: 2001).ordinal(), 365);
 
+--- datetime-display-localized ---
+#set text(lang: "de")
+#let d = datetime(year: 2023, month: 3, day: 29)
+#context test(
+  d.display("[weekday], [day]. [month repr:long] [year]"),
+  "Mittwoch, 29. März 2023",
+)
+#context test(d.display("[weekday repr:short] [month repr:short]"), "Mi. März")
+#test(d.display("[month repr:long]"), "March")
+
+--- datetime-display-localized-unabbreviated ---
+// Arabic doesn't abbreviate the names, so the short ones are the long ones.
+#set text(lang: "ar")
+#let d = datetime(year: 2023, month: 3, day: 29)
+#context test(
+  d.display("[weekday repr:short] [month repr:short]"),
+  d.display("[weekday] [month repr:long]"),
+)
+#context test(d.display("[month repr:short]"), "مارس")
+
+--- datetime-display-localized-more ---
+#let d = datetime(year: 2023, month: 3, day: 29)
+#set text(lang: "pl")
+#context test(d.display("[weekday] [month repr:long]"), "środa marzec")
+#set text(lang: "zh", region: "TW")
+#context test(d.display("[weekday repr:short] [month repr:long]"), "週三 三月")
+#set text(lang: "vi", region: none)
+#context test(d.display("[weekday], [month repr:short]"), "Thứ Tư, thg 3")
+
 --- datetime-display-missing-closing-bracket ---
 // Error: 27-34 missing closing bracket for bracket at index 0
 #datetime.today().display("[year")
@@ -122,3 +122,15 @@ Hallo
   = B
   Text
 ]
+
+--- columns-balance ---
+// Test balancing the columns of the last region.
+#set page(height: 120pt)
+#columns(2, balance: true, lorem(30))
+#rect(width: 100%, height: 10pt, fill: conifer)
+
+--- columns-balance-page ---
+// Test balancing the columns of pages. Only the last page is balanced.
+#set page(height: 100pt, columns: 2)
+#set columns(balance: true)
+#lorem(60)
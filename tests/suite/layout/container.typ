@@ -173,6 +173,50 @@ First!
   image("/assets/images/rhino.png", width: 30pt)
 )
 
+--- box-clip-shape-field ---
+#test(box(clip: circle())[A].clip, circle())
+#test(block(clip: true)[A].clip, true)
+
+--- box-clip-circle ---
+#box(
+  clip: circle(),
+  width: 60pt,
+  height: 40pt,
+  rect(fill: gradient.linear(..color.map.rainbow), width: 100%, height: 100%),
+)
+#box(clip: circle(radius: 15pt), image("/assets/images/tiger.jpg", width: 40pt))
+
+--- block-clip-path ---
+// Test clipping to a path with curves and to a path with the even-odd rule.
+#block(
+  clip: path(
+    closed: true,
+    (0pt, 30pt),
+    ((50pt, 0pt), (-20pt, 0pt), (20pt, 0pt)),
+    (100pt, 30pt),
+    (50pt, 60pt),
+  ),
+  width: 100%,
+  height: 60pt,
+  fill: forest,
+  lorem(20),
+)
+#block(
+  clip: path(
+    fill-rule: "even-odd",
+    closed: true,
+    (25pt, 0pt), (40pt, 50pt), (0pt, 18pt), (50pt, 18pt), (10pt, 50pt),
+  ),
+  fill: conifer,
+  width: 50pt,
+  height: 50pt,
+)
+
+--- box-clip-without-shape ---
+// Error: 2-34 failed to find the outline of the clip shape
+// Hint: 2-34 try a rect, circle, ellipse, polygon, or path
+#box(clip: [text], width: 1em)[A]
+
 --- container-layoutable-child ---
 // Test box/block sizing with directly layoutable child.
 //
@@ -29,3 +29,65 @@ This is the start and it goes on.
 // All three lines go to the next page.
 #set text(olive)
 #lorem(10)
+
+--- flow-par-orphans-zero ---
+// Error: 19-20 number must be positive
+#set par(orphans: 0)
+
+--- flow-par-orphans-three ---
+// The columns stand in for pages and each word is a line of its own. Two lines
+// fit into the first column, which is enough by default, but too few with
+// three orphans. The words only contain metadata, so nothing is visible.
+#let probe(body) = place(block(height: 40pt, columns(2, gutter: 0pt, body)))
+#let word(body) = box(width: 30pt, height: 8pt, body)
+#let words = range(4).map(_ => word(metadata(none))).join(" ")
+#probe[
+  #v(12pt)
+  #word[#metadata(none) <two>] #words
+]
+#probe[
+  #set par(orphans: 3)
+  #v(12pt)
+  #word[#metadata(none) <three>] #words
+]
+#context {
+  test(locate(<two>).position().x < 60pt, true)
+  test(locate(<three>).position().x >= 60pt, true)
+}
+
+--- flow-par-widows-three ---
+// Three of the five lines fit into the first column. With three widows, the
+// third line moves to the second column.
+#let probe(body) = place(block(height: 40pt, columns(2, gutter: 0pt, body)))
+#let word(body) = box(width: 30pt, height: 8pt, body)
+#let words = range(2).map(_ => word(metadata(none))).join(" ")
+#probe[
+  #words #word[#metadata(none) <two>] #words
+]
+#probe[
+  #set par(widows: 3)
+  #words #word[#metadata(none) <three>] #words
+]
+#context {
+  test(locate(<two>).position().x < 60pt, true)
+  test(locate(<three>).position().x >= 60pt, true)
+}
+
+--- flow-block-sticky ---
+// A sticky block moves to the next column together with a successor that
+// doesn't fit anymore, while a normal block stays behind.
+#let probe(body) = place(block(height: 40pt, columns(2, gutter: 0pt, body)))
+#probe[
+  #block(height: 5pt)
+  #block(sticky: true, metadata(none)) <sticky>
+  #block(height: 20pt, breakable: false)
+]
+#probe[
+  #block(height: 5pt)
+  #block(metadata(none)) <normal>
+  #block(height: 20pt, breakable: false)
+]
+#context {
+  test(locate(<sticky>).position().x >= 60pt, true)
+  test(locate(<normal>).position().x < 60pt, true)
+}
@@ -157,3 +157,45 @@ el objetivo de protegerle de las patrullas de milicianos.
 #context {
   assert.eq(text.costs, (hyphenation: 1%, runt: 2%, widow: 3%, orphan: 100%))
 }
+
+--- hyphenate-limits-fold ---
+#set text(hyphenation-limits: (start: 3))
+#set text(hyphenation-limits: (end: 4))
+#context assert.eq(text.hyphenation-limits, (start: 3, end: 4))
+
+--- hyphenate-limits-zero ---
+// Error: 31-41 number must be positive
+#set text(hyphenation-limits: (start: 0))
+
+--- hyphenate-exception-invalid ---
+// Error: 35-44 invalid hyphenation exception "-abc"
+#set text(hyphenation-exceptions: ("-abc",))
+
+--- hyphenate-patterns-file ---
+// The pattern allows a break between "a" and "b" and the exception one after
+// "cdc", so both words are split across two lines.
+#let patterns = hyphenation-patterns("hyphenate.tex")
+#test(repr(patterns), "hyphenation-patterns(\"hyphenate.tex\")")
+#set text(lang: "cy", hyphenate: true)
+#context {
+  let height(body) = measure(block(width: 20pt, body)).height
+  let custom(body) = text(hyphenation-patterns: patterns, body)
+  let line = height[aaa]
+  test(height[aaabbb], line)
+  test(height[cdcdef], line)
+  test(height(custom[aaabbb]) > line, true)
+  test(height(custom[cdcdef]) > line, true)
+  test(height(custom[aaaaaa]), line)
+}
+
+--- hyphenate-patterns-file-not-found ---
+// Error: 23-33 file not found (searched at tests/suite/layout/inline/nope.tex)
+#hyphenation-patterns("nope.tex")
+
+--- hyphenate-exception-punctuation ---
+// Leading and trailing punctuation is not part of the word, so the exception
+// applies to the quoted and parenthesized words, too.
+#set page(width: 45pt)
+#set text(hyphenate: true, hyphenation-exceptions: ("sup-er-cal-ifrag",))
+supercalifrag \
+"(supercalifrag)," \
@@ -163,6 +163,33 @@ int main() {
 #set par(justify: true)
 #block(width: 1cm, fill: aqua, lorem(2))
 
+--- justify-kashida-marks ---
+// Test that kashidas are found in words with vowel marks and superscript
+// alefs, so that the spaces of a justified line keep their natural width.
+// The line is wide enough for its kashidas to take all of the extra space.
+#set page(width: 250pt)
+#set text(lang: "ar", font: "Noto Sans Arabic")
+#let rest = [الرَّحْمٰنُ عَلَّمَ الطَّالِبَ دَرْسَهُ فِي الْمَكْتَبَةِ وَذَهَبَ إِلَى الْبَيْتِ]
+#par(justify: true, kashida: 100%)[هٰذَا#metadata(none)<a> #metadata(none)<b>#rest]
+#par[هٰذَا#metadata(none)<c> #metadata(none)<d>#rest]
+#context test(
+  locate(<a>).position().x - locate(<b>).position().x,
+  locate(<c>).position().x - locate(<d>).position().x,
+)
+
+--- justify-kashida-limit ---
+// Test that kashidas are at most half an em long and that the spaces take the
+// rest of the extra space.
+#set page(width: 150pt)
+#set text(lang: "ar", font: "Noto Sans Arabic")
+#par(justify: true, kashida: 100%)[كتبه#metadata(none)<a> #metadata(none)<b>كتبه#linebreak(justify: true)]
+#par[كتبه#metadata(none)<c> #metadata(none)<d>كتبه]
+#context test(
+  locate(<a>).position().x - locate(<b>).position().x
+    > locate(<c>).position().x - locate(<d>).position().x,
+  true,
+)
+
 --- issue-2419-justify-hanging-indent ---
 // Test that combination of justification and hanging indent doesn't result in
 // an underfull first line.
@@ -22,3 +22,60 @@
 #set align(end)
 #set text(dir: rtl)
 :
+
+--- overhang-protrusion-bad-key ---
+// Error: 22-33 expected a single character, found "ab"
+#set par(protrusion: ("ab": 50%))
+
+--- overhang-protrusion-quotes ---
+// Test that closing quotes only protrude when added to the dictionary.
+#set page(width: 80pt, margin: 10pt)
+#set align(end)
+“Hi#metadata(none) <default>”
+
+#[
+  #set par(protrusion: ("”": (end: 50%)))
+  “Hi#metadata(none) <quote>”
+]
+
+#[
+  #set par(protrusion: ("”": 50%))
+  #text(overhang: false)[“Hi#metadata(none) <off>”]
+]
+
+#context {
+  let x = locate(<default>).position().x
+  test(locate(<quote>).position().x > x, true)
+  test(locate(<off>).position().x, x)
+}
+
+--- overhang-protrusion-cjk ---
+// Test that full-width punctuation only protrudes by default in Chinese and
+// Japanese text.
+#set align(end)
+#set text(font: "Noto Serif CJK SC")
+#place(hide[
+  #text(lang: "zh")[你好#metadata(none) <zh>。]
+
+  #text(lang: "ja")[你好#metadata(none) <ja>。]
+
+  #text(lang: "en")[你好#metadata(none) <en>。]
+])
+
+#context {
+  let x = locate(<en>).position().x
+  test(locate(<zh>).position().x > x, true)
+  test(locate(<ja>).position().x, locate(<zh>).position().x)
+}
+
+--- overhang-expansion ---
+#set page(width: 130pt, margin: 15pt)
+#set par(justify: true, expansion: 2%)
+#set text(size: 9pt)
+#context test(par.expansion, 2%)
+This is a little bit of text that is justified with a bit of font expansion
+to keep its word spacing even.
+
+--- overhang-expansion-too-large ---
+// Error: 21-24 expansion must be between 0% and 10%
+#set par(expansion: 20%)
@@ -0,0 +1,54 @@
+// Test vertical writing mode.
+
+--- vertical-basic ---
+// Test that lines run from top to bottom and follow each other from right to
+// left, with Latin text and numbers rotated or combined.
+#set page(width: 120pt, height: 100pt)
+#set text(dir: ttb, lang: "ja", font: ("Linux Libertine", "Noto Serif CJK SC"))
+吾輩は猫である。名前はまだ無い。Typst で 12 月に書いた。
+
+--- vertical-lines-order ---
+// Test that the first line is at the right edge of the page and the next one
+// to the left of it.
+#set page(width: 120pt, height: 100pt, margin: 10pt)
+#set text(dir: ttb, font: ("Linux Libertine", "Noto Serif CJK SC"))
+#metadata(none) <first> 一行目 \
+#metadata(none) <second> 二行目
+#context {
+  let first = locate(<first>).position()
+  let second = locate(<second>).position()
+  test(first.x > 90pt, true)
+  test(second.x < first.x, true)
+}
+
+--- vertical-columns ---
+// Test that columns are stacked from top to bottom.
+#set page(width: 100pt, height: 120pt, margin: 10pt, columns: 2)
+#set text(dir: ttb, font: ("Linux Libertine", "Noto Serif CJK SC"))
+#context test(here().position().y < 60pt, true)
+#lorem(3)
+#colbreak()
+#context test(here().position().y > 60pt, true)
+#lorem(3)
+
+--- vertical-marginals ---
+// Test that the header and footer stay horizontal.
+#set page(
+  width: 120pt,
+  height: 100pt,
+  header: [Header],
+  numbering: "1",
+)
+#set text(dir: ttb, font: ("Linux Libertine", "Noto Serif CJK SC"))
+縦書きの本文。
+
+--- vertical-inline ---
+// Vertical text cannot be set for a part of a page.
+// Error: 19-22 vertical text must be set for whole pages
+// Hint: 19-22 use `set text(dir: ttb)` before any content of the page
+A #text(dir: ttb)[縦書き]
+
+--- vertical-box ---
+// Error: 27-30 vertical text must be set for whole pages
+// Hint: 27-30 use `set text(dir: ttb)` before any content of the page
+#box[#set text(dir: ttb); 縦書き]
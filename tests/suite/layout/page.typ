@@ -258,3 +258,22 @@ Default page numbers now.
 Hi
 #pagebreak()
 = Second
+
+--- page-baseline-grid-lines ---
+// Test that every line of a paragraph is on the grid, including the orphan
+// and widow lines that are kept together.
+#set page(height: 120pt, margin: 10pt, baseline-grid: 8pt)
+#set par(leading: 0.5em)
+A#metadata(1) <l> \
+B#metadata(2) <l> \
+C#metadata(3) <l> \
+D#metadata(4) <l> \
+E#metadata(5) <l>
+#context {
+  let ys = query(<l>).map(m => m.location().position().y)
+  test(ys, (18pt, 34pt, 50pt, 66pt, 82pt))
+}
+
+--- page-baseline-grid-unknown-key ---
+// Error: 26-48 unexpected key "gap", valid keys are "step" and "offset"
+#set page(baseline-grid: (step: 12pt, gap: 2pt))
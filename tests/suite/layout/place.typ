@@ -251,3 +251,83 @@ Start.
 )
 
 #lorem(20)
+
+--- place-side-not-floating ---
+// Error: 2-22 side placement is only available for floating placement
+// Hint: 2-22 you can enable floating placement with `place(float: true, ..)`
+#place(side: left)[A]
+
+--- place-side-wrap-empty ---
+// Error: 39-41 wrap profile must not be empty
+#place(float: true, side: left, wrap: ())[A]
+
+--- place-side-wrap-lines ---
+// Lines of different heights wrap around the float based on where they end
+// up, not on an estimated line height.
+#set page(height: 150pt)
+#place(float: true, side: left, clearance: 4pt, rect(width: 30pt, height: 50pt))
+#lorem(6) #text(2em)[Big] #lorem(20)
+
+--- place-side-wrap-blocks ---
+// Lists, tables and nested blocks are narrowed to fit next to a side float.
+#set page(height: 200pt)
+#place(float: true, side: right, clearance: 4pt, rect(width: 40pt, height: 80pt))
+- First item
+- Second item
+#table(columns: 2, [A], [B], [C], [D])
+#block(fill: aqua, inset: 4pt, width: 100%)[Nested]
+#lorem(10)
+
+--- place-side-wrap-blocks-position ---
+#set page(width: 120pt, height: 200pt, margin: 10pt)
+#place(float: true, side: left, clearance: 5pt, rect(width: 40pt, height: 60pt))
+- #context test(here().position().x > 55pt, true)
+#block(width: 100%, context test(here().position().x, 55pt))
+#v(60pt)
+#block(width: 100%, context test(here().position().x, 10pt))
+
+--- place-scope-page ---
+// Test page-scoped floats spanning all columns at the top and bottom.
+#set page(height: 160pt, columns: 2)
+#place(
+  top + center,
+  float: true,
+  scope: "page",
+  rect(width: 80%, height: 20pt, fill: conifer),
+)
+#place(
+  bottom,
+  float: true,
+  scope: "page",
+  rect(width: 100%, height: 15pt, fill: forest),
+)
+#lorem(50)
+
+--- place-scope-page-figure ---
+// Test a page-scoped figure that appears in the second column.
+#set page(height: 160pt, columns: 2)
+#lorem(30)
+#figure(
+  placement: top,
+  scope: "page",
+  rect(width: 100%, height: 20pt, fill: conifer),
+  caption: [Wide],
+)
+#lorem(20)
+
+--- place-scope-page-locate ---
+// Test that page-scoped floats keep introspection working.
+#set page(height: 160pt, columns: 2)
+#place(top, float: true, scope: "page")[
+  #rect(width: 100%, height: 20pt) <wide>
+]
+A #context test(locate(<wide>).position().y, 10pt)
+
+--- place-scope-page-not-floating ---
+// Error: 2-31 page-scoped placement is only available for floating placement
+// Hint: 2-31 you can enable floating placement with `place(float: true, ..)`
+#place(top, scope: "page")[A]
+
+--- place-scope-invalid ---
+// Error: 28-36 expected "column" or "page"
+#place(float: true, scope: "parent")[A]
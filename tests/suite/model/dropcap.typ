@@ -0,0 +1,57 @@
+--- dropcap-basic ---
+#set par(justify: true)
+#dropcap(lines: 3)[
+  Typst is a markup-based typesetting system for the sciences. It is designed
+  to be an alternative both to advanced tools like LaTeX and simpler tools.
+]
+
+--- dropcap-two-lines ---
+// Test that only the lines next to the drop cap make room for it.
+#place(hide[
+  #dropcap(lines: 2)[
+    "Hi,"\ #metadata(none) <second>she said\ #metadata(none) <third>and left.
+  ]
+])
+
+#context test(locate(<second>).position().x > locate(<third>).position().x, true)
+
+--- dropcap-initial ---
+// Test that the body is left as is when the initial is given explicitly.
+#place(hide[
+  #dropcap(initial: text(red)[W], lines: 2)[#metadata(none) <start>hen given.]
+])
+#place(hide[#metadata(none) <plain>hen given.])
+
+#context test(locate(<start>).position().x > locate(<plain>).position().x, true)
+
+--- dropcap-short-paragraph ---
+// Test that a paragraph shorter than the drop cap reserves its height.
+#place(hide[
+  #dropcap(lines: 3)[Short.]
+
+  #metadata(none) <three>After.
+])
+#place(hide[
+  #dropcap(lines: 2)[Short.]
+
+  #metadata(none) <two>After.
+])
+
+#context test(locate(<three>).position().y > locate(<two>).position().y, true)
+
+--- dropcap-page-break ---
+// Test that the lines next to the drop cap are not split across pages.
+#set page(height: 70pt)
+#lorem(8)
+
+#dropcap(lines: 3)[#lorem(20)]
+
+--- dropcap-no-letter ---
+// Error: 2-18 failed to find the first letter of the body
+// Hint: 2-18 specify the drop cap with the `initial` parameter
+#dropcap[--- ...]
+
+--- dropcap-no-initial ---
+// Error: 2-17 failed to find the first letter of the body
+// Hint: 2-17 specify the drop cap with the `initial` parameter
+#dropcap(box[x])
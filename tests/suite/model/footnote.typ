@@ -187,3 +187,60 @@ B #footnote[b]
 #set page(height: 50pt)
 #footnote[A]
 #footnote[B]
+
+--- footnote-numbering-scope-invalid ---
+// Error: 32-41 expected "document" or "page"
+#set footnote(numbering-scope: "chapter")
+
+--- footnote-entry-columns-zero ---
+// Error: 30-31 number must be positive
+#set footnote.entry(columns: 0)
+
+--- footnote-numbering-scope-page ---
+#set page(height: 80pt)
+#set footnote(numbering-scope: "page")
+A#footnote[First] B#footnote[Second]
+#pagebreak()
+C#footnote[First again]
+
+--- footnote-series ---
+// Test that the main series is listed first, even if another series appears
+// before it on the page.
+#let variant = footnote.with(series: "apparatus", numbering: "a")
+A variant#variant[_variant_ #metadata(none) <variant>]
+and a note#footnote[Regular #metadata(none) <main>].
+#context test(
+  locate(<main>).position().y < locate(<variant>).position().y,
+  true,
+)
+
+--- footnote-series-numbering ---
+// Test that each series is numbered on its own.
+#let variant = footnote.with(series: "apparatus", numbering: "a")
+A#footnote[One] B#variant[Alpha] C#footnote[Two] D#variant[Beta]
+#context test(counter(footnote).get(), (2,))
+
+--- footnote-entry-columns ---
+#set footnote.entry(columns: 2)
+Footnotes:
+#footnote[Left #metadata(none) <left>],
+#footnote[Right #metadata(none) <right>]
+#context {
+  let left = locate(<left>).position()
+  let right = locate(<right>).position()
+  test(left.y, right.y)
+  test(left.x < right.x, true)
+}
+
+--- footnote-entry-run-in ---
+#set footnote.entry(run-in: true, gap: 1em)
+Footnotes:
+#footnote[One #metadata(none) <one>],
+#footnote[Two #metadata(none) <two>],
+#footnote[Three]
+#context {
+  let one = locate(<one>).position()
+  let two = locate(<two>).position()
+  test(one.y, two.y)
+  test(one.x < two.x, true)
+}
@@ -0,0 +1,59 @@
+--- marginnote-side-center ---
+// Error: 19-25 expected `start`, `left`, `right`, or `end`, found center
+#marginnote(side: center)[A]
+
+--- marginnote-sides ---
+#set page(width: 120pt, height: 80pt, margin: (x: 40pt, y: 10pt))
+A #marginnote(side: left)[L#metadata(none) <l>]
+B #marginnote(side: right)[R#metadata(none) <r>]
+#context {
+  test(locate(<l>).position().x < 40pt, true)
+  test(locate(<r>).position().x > 80pt, true)
+}
+
+--- marginnote-baseline ---
+// Test that the note is aligned with its line and that notes don't overlap.
+#set page(width: 120pt, height: 100pt, margin: (right: 50pt, rest: 10pt))
+#set marginnote(side: right)
+A#metadata(none) <a> #marginnote[N#metadata(none) <n1>]
+#marginnote[M#metadata(none) <n2>]
+#context {
+  let y = locate(<a>).position().y
+  test(locate(<n1>).position().y, y)
+  test(locate(<n2>).position().y > y, true)
+}
+
+--- marginnote-styles ---
+// Test that the note is laid out with the styles at the note.
+#set page(width: 120pt, height: 80pt, margin: (right: 60pt, rest: 10pt))
+#set marginnote(side: right)
+#[
+  #set text(size: 6pt, fill: eastern)
+  Text #marginnote(context test(text.size, 6pt))
+]
+Text #marginnote(context test(text.size, 10pt))
+
+--- marginnote-overflow-last-page ---
+// Test that notes that don't fit onto the last page continue on a new page.
+#set page(width: 120pt, height: 60pt, margin: (right: 50pt, rest: 10pt))
+#set marginnote(side: right)
+A #marginnote(lorem(3))
+#marginnote[X#metadata(none) <x>]
+#context test(locate(<x>).page(), 2)
+#context test(counter(page).final(), (2,))
+
+--- marginnote-footnote ---
+// Test that footnotes in the margin follow the margin note settings and the
+// styles at the footnote.
+#set page(width: 120pt, height: 80pt, margin: (x: 40pt, y: 10pt))
+#set footnote(margin: true)
+#[
+  #set marginnote(side: left)
+  #set text(fill: eastern)
+  A#footnote[L#metadata(none) <l> #context test(text.fill, eastern)]
+]
+B#footnote[R#metadata(none) <r>]
+#context {
+  test(locate(<l>).position().x < 40pt, true)
+  test(locate(<r>).position().x > 80pt, true)
+}
@@ -76,3 +76,57 @@ Welcome \ here. Does this work well?
 #set text(dir: rtl)
 لآن وقد أظلم الليل وبدأت النجوم
 تنضخ وجه الطبيعة التي أعْيَتْ من طول ما انبعثت في النهار
+
+--- par-line-constructor-forbidden ---
+// Error: 2-12 cannot be constructed manually
+#par.line()
+
+--- par-line-numbering-scope-invalid ---
+// Error: 32-41 expected "document" or "page"
+#set par.line(numbering-scope: "section")
+
+--- par-line-numbering ---
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line(numbering: "1")
+Roses are red. \
+Violets are blue.
+
+Typst is there for you.
+
+--- par-line-numbering-right ---
+#set page(margin: (right: 30pt, rest: 10pt))
+#set par.line(numbering: "i", number-margin: right)
+Numbered \
+on the right.
+
+--- par-line-numbering-skipped ---
+// Test that the lines of headings, table cells, figure captions and footnotes
+// are not numbered.
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line(numbering: "1")
+= Heading
+Text#footnote[Note]
+#table(columns: 2)[A][B]
+#figure(rect(height: 10pt), caption: [Caption])
+#context test(counter(par.line).get(), (1,))
+
+--- par-line-numbering-scope-page ---
+#set page(height: 50pt, margin: (left: 30pt, rest: 10pt))
+#set par.line(numbering: "1", numbering-scope: "page")
+#for i in range(5) [Line #i \ ]
+#context test(counter(par.line).final(), (5,))
+
+--- par-line-numbering-counter-update ---
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line(numbering: "1")
+First \
+#counter(par.line).update(10)
+Second
+#context test(counter(par.line).get(), (11,))
+
+--- par-line-ref ---
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line(numbering: "1")
+Roses are red. \
+Violets are blue. #metadata(none) <blue> \
+See @blue for the color.
@@ -76,3 +76,74 @@ I
 // Warning: 23-56 unknown font family: non-existing-fonts
 #let var = text(font: ("list-of", "non-existing-fonts"))[don't]
 #var
+
+--- text-font-file-not-found ---
+// Error: 20-30 file not found (searched at tests/suite/text/nope.ttf)
+#let f = font-file("nope.ttf")
+
+--- text-font-file-malformed ---
+// Error: 20-44 failed to load font file (unknown or malformed format)
+#let f = font-file("/assets/text/hello.txt")
+
+--- text-font-file ---
+#let roboto = font-file("/assets/fonts/Roboto-Regular.ttf")
+#test(repr(roboto), "font-file(\"/assets/fonts/Roboto-Regular.ttf\")")
+#set text(font: (roboto, "Linux Libertine"))
+#context test(text.font, (roboto, "linux libertine"))
+Hello from a font file. \
+#text(font: "Linux Libertine")[Hello from the world.]
+
+--- text-font-file-measure ---
+#let roboto = font-file("/assets/fonts/Roboto-Regular.ttf")
+#context {
+  let from-file = measure(text(font: roboto, fallback: false)[Typst])
+  let by-name = measure(text(font: "Roboto", fallback: false)[Typst])
+  let libertine = measure(text(font: "Linux Libertine", fallback: false)[Typst])
+  test(from-file.width, by-name.width)
+  test(from-file.width != libertine.width, true)
+}
+
+--- text-variations-static ---
+// Test that the implicit weight and width axes work with static fonts.
+#set text(font: "Linux Libertine", weight: "bold", stretch: 75%)
+#context test(text.variations, (:))
+Typst
+
+--- text-variations-tag-too-long ---
+// Error: 23-36 invalid variation axis "weight"
+// Hint: 23-36 axes are identified by four-character tags like `wght`
+#set text(variations: (weight: 500))
+
+--- text-variations-tag-non-ascii ---
+// Error: 23-35 invalid variation axis "wdtä"
+// Hint: 23-35 axes are identified by four-character tags like `wght`
+#set text(variations: ("wdtä": 80))
+
+--- text-variations-missing-axis ---
+#set text(font: "Linux Libertine", variations: (opsz: 12))
+// Error: 1-6 the font has no variation axis `opsz`
+// Hint: 1-6 this may be a static font or use a different tag for the axis
+Typst
+
+--- text-font-file-world-variants ---
+// Test that the world's fonts complement the family of a font file.
+#let plex = font-file("/assets/fonts/IBMPlexSans-Regular.ttf")
+#context {
+  let from-file = measure(text(font: plex, weight: "bold", fallback: false)[Typst])
+  let by-name = measure(text(font: "IBM Plex Sans", weight: "bold", fallback: false)[Typst])
+  let regular = measure(text(font: plex, fallback: false)[Typst])
+  test(from-file.width, by-name.width)
+  test(from-file.width != regular.width, true)
+}
+
+--- text-font-package ---
+// Test that fonts declared in a package manifest are available to the
+// package and the importing document.
+#import "@test/fonts:0.1.0": stem
+#set text(size: 10pt, font: "Package Test", fallback: false)
+#context test(measure(stem).width, 3pt)
+#context test(measure[I].width, 3pt)
+
+--- text-font-package-malformed ---
+// Error: 9-31 failed to load package font lib.typ (unknown or malformed format)
+#import "@test/bad-font:0.1.0"
@@ -0,0 +1,42 @@
+--- format-number ---
+#context test(format-number(1234567), "1,234,567")
+#context test(format-number(-1234.5), "−1,234.5")
+#context test(format-number(0.125, style: "percent"), "12.5%")
+#context test(format-number(1234.5, digits: 2, grouping: false), "1234.50")
+#context test(format-number(-0.001, digits: 2), "0.00")
+#context test(format-number(19.9, style: "currency", currency: "USD"), "$19.90")
+
+--- format-number-lang ---
+#set text(lang: "de")
+#context test(format-number(1234567.5), "1.234.567,5")
+#context test(format-number(0.25, style: "percent"), "25\u{a0}%")
+#context test(format-number(1000, style: "currency", currency: "JPY"), "1.000\u{a0}¥")
+
+--- format-number-lang-ar ---
+#set text(lang: "ar")
+#context test(format-number(1234.5), "1٬234٫5")
+#context test(format-number(0.5, style: "percent"), "50٪")
+#context test(format-number(3, style: "currency", currency: "EUR"), "3٫00\u{a0}€")
+
+--- format-number-region ---
+#set text(lang: "zh", region: "TW")
+#context test(format-number(1234.5, style: "currency", currency: "USD"), "$1,234.50")
+#context test(format-number(0.25, style: "percent"), "25%")
+
+--- format-number-counter ---
+// Test formatting in a counter's numbering function.
+#set text(lang: "de")
+#let c = counter("big")
+#c.update(12345)
+#context test(c.display(n => format-number(n)), "12.345")
+
+--- format-number-currency-missing ---
+// Error: 10-45 cannot format an amount of money without a currency
+// Hint: 10-45 specify it with the `currency` argument
+#context format-number(5, style: "currency")
+
+--- format-number-without-context ---
+// Error: 2-18 can only be used when context is known
+// Hint: 2-18 try wrapping this in a `context` expression
+// Hint: 2-18 the `context` expression should wrap everything that depends on this function
+#format-number(5)
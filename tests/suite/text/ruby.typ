@@ -0,0 +1,34 @@
+--- ruby-mono-mismatch ---
+// Error: 2-29 expected 2 annotations, one for each base character, found 3
+#ruby([漢字], ("か", "ん", "じ"))
+
+--- ruby-overhang ---
+// Wide annotations overhang spacing and punctuation, but not other base text.
+#set page(width: auto)
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+「#ruby[春][はるはる]」 \
+字#ruby[春][はるはる]字 \
+#ruby([春夏], ("はるはる", "なつなつ"))。 \
+#ruby(overhang: false)[春][はるはる]。
+
+--- ruby-overhang-measure ---
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#let wide = ruby[春][はるはる]
+#let tight = ruby(overhang: false)[春][はるはる]
+#context {
+  test(measure[「#wide」].width < measure[「#tight」].width, true)
+  test(measure[字#wide;字].width, measure[字#tight;字].width)
+  test(measure(wide).width, measure(tight).width)
+}
+
+--- ruby-mono-styled ---
+// Each base character of a mono ruby keeps its styles.
+#let base = [*a*#text(size: 1.5em)[b]_c_]
+#context test(
+  measure(ruby(base, ("x", "y", "z"))).width,
+  measure(base).width,
+)
+
+--- ruby-mono-styled-mismatch ---
+// Error: 2-34 expected 3 annotations, one for each base character, found 2
+#ruby([*漢*#emph[字]x], ("か", "ん"))
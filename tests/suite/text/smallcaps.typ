@@ -10,3 +10,13 @@
 
 #show smallcaps: set text(fill: red)
 #smallcaps[Smallcaps]
+
+--- smallcaps-synthesized ---
+// DejaVu Sans Mono has no smallcaps, so lowercase letters are rendered as
+// scaled-down capitals.
+#set text(font: "DejaVu Sans Mono")
+#context test(
+  calc.round(measure(smallcaps[abc]).width / measure[ABC].width, digits: 3),
+  0.7,
+)
+#context test(measure(smallcaps[ABC]).width, measure[ABC].width)
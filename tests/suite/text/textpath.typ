@@ -0,0 +1,26 @@
+--- textpath-without-shape ---
+// Error: 2-22 failed to find the outline of the shape
+// Hint: 2-22 try a path, circle, or ellipse
+#textpath([Text])[Hi]
+
+--- textpath-circle ---
+// Text wraps around a closed outline, rotated along it.
+#textpath(
+  circle(radius: 40pt, stroke: 0.5pt + gray),
+  start: 25%,
+  align: center,
+)[Round and round it goes and goes]
+
+--- textpath-open ---
+// Glyphs beyond the ends of an open outline are left out.
+#let wave = path(
+  stroke: 0.5pt + gray,
+  ((0pt, 20pt), (20pt, -20pt)),
+  ((100pt, 20pt), (20pt, 20pt)),
+)
+#textpath(wave, start: 10pt)[On the wave, and then over the edge]
+
+--- textpath-transformed ---
+// Transformed text is placed along the outline with its transformation.
+#let line = path(stroke: 0.5pt + gray, (0pt, 30pt), (100pt, 0pt))
+#textpath(line)[Hi #scale(x: 200%, origin: left)[wide] #rotate(180deg)[up] there]
@@ -371,6 +371,92 @@
 #set text(fill: gradient.conic(red, blue, angle: 45deg))
 #lorem(30)
 
+--- gradient-mesh-fields ---
+#let g = gradient.mesh((red, blue), (green, yellow), space: color.rgb)
+#test(g.kind(), gradient.mesh)
+#test(g.space(), color.rgb)
+#test(g.angle(), none)
+#test(g.stops(), (red, blue, green, yellow))
+#test(g.sample(0%), red)
+#test(g.sample(100%), yellow)
+
+--- gradient-mesh-too-few-rows ---
+// Error: 2-28 a mesh gradient must have at least two rows
+// Hint: 2-28 try a linear gradient instead
+#gradient.mesh((red, blue))
+
+--- gradient-mesh-short-row ---
+// Error: 29-35 each row of a mesh gradient must have at least two vertices
+#gradient.mesh((red, blue), (red,))
+
+--- gradient-mesh-mixed-positions ---
+// Error: 29-59 either all vertices must have a position or none of them can
+// Hint: 29-59 try adding a position to all vertices
+#gradient.mesh((red, blue), (green, (yellow, (50%, 100%))))
+
+--- gradient-mesh-repeat ---
+// Error: 53-54 mesh gradients cannot be repeated
+#gradient.mesh((red, blue), (green, yellow)).repeat(2)
+
+--- gradient-mesh-curved ---
+// The top edge bulges upwards, so the patch covers points above the straight
+// line between its top corners.
+#let corners = ((green, (0%, 100%)), (yellow, (100%, 100%)))
+#let straight = gradient.mesh(((red, (0%, 20%)), (blue, (100%, 20%))), corners)
+#let curved = gradient.mesh(
+  ((red, (0%, 20%), (-30%, 20%)), (blue, (100%, 20%), (-30%, -20%))),
+  corners,
+)
+#test(straight.sample(15%), luma(0%).transparentize(100%))
+#test(curved.sample(15%).components().last(), 100%)
+#test(curved.sample(5%), luma(0%).transparentize(100%))
+#test(curved.sample(100%), yellow.oklab())
+#test(
+  repr(curved),
+  "gradient.mesh(((rgb(\"#ff4136\"), (0%, 20%), (-30%, 20%), none), (rgb(\"#0074d9\"), (100%, 20%), (-30%, -20%), none)), ((rgb(\"#2ecc40\"), (0%, 100%)), (rgb(\"#ffdc00\"), (100%, 100%))))",
+)
+
+--- gradient-mesh-curved-both-handles ---
+#let g = gradient.mesh(
+  ((red, (0%, 0%), none, ((0%, -10%), (0%, 20%))), (blue, (100%, 0%))),
+  ((green, (0%, 100%)), (yellow, (100%, 100%))),
+)
+#test(repr(eval(repr(g))), repr(g))
+#test(g.sample(50%).components().last(), 100%)
+
+--- gradient-mesh-bad-handles ---
+// Error: 16-53 expected a control point or an array of two control points
+#gradient.mesh(((red, (0%, 0%), (1%, 1%, 1%)), blue), (green, yellow))
+
+--- gradient-mesh-vertex-too-long ---
+// Error: 16-57 a mesh vertex must contain two to four entries
+#gradient.mesh(((red, (0%, 0%), none, none, none), blue), (green, yellow))
+
+--- gradient-along ---
+#let g = gradient.along(((0%, 50%), (100%, 50%)), red, blue, thickness: 20%)
+#test(g.kind(), gradient.mesh)
+#test(g.sample(50%), color.mix(red, blue))
+#test(g.sample(10%), luma(0%).transparentize(100%))
+#rect(
+  width: 100%,
+  height: 40pt,
+  fill: gradient.along(
+    ((10%, 10%), ((50%, 90%), (-20%, 0%)), (90%, 10%)),
+    red,
+    blue,
+    space: color.hsl,
+  ),
+)
+
+--- gradient-along-too-short ---
+// Error: 17-28 the path of a gradient must have at least two vertices
+#gradient.along(((0%, 0%),), red, blue)
+
+--- gradient-along-too-few-stops ---
+// Error: 2-43 a gradient must have at least two stops
+// Hint: 2-43 try filling the shape with a single color instead
+#gradient.along(((0%, 0%), (1%, 1%)), red)
+
 --- gradient-text-bad-relative ---
 // Make sure they don't work when `relative: "self"`.
 // Hint: 17-61 make sure to set `relative: auto` on your text fill
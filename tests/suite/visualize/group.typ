@@ -0,0 +1,57 @@
+// Test compositing of groups.
+
+--- group-fields ---
+#let g = group(opacity: 50%, blend: "multiply", mask-mode: "luminance")[A]
+#test(g.opacity, 50%)
+#test(g.blend, "multiply")
+#test(g.mask-mode, "luminance")
+
+--- group-blend-invalid ---
+// Error: 15-24 expected "normal", "multiply", "screen", "overlay", "darken", "lighten", "color-dodge", "color-burn", "hard-light", "soft-light", "difference", "exclusion", "hue", "saturation", "color", or "luminosity"
+#group(blend: "mutiply")[A]
+
+--- group-opacity ---
+// Overlapping parts of a semi-transparent group don't shine through each
+// other, unlike those of separate semi-transparent shapes.
+#set page(width: auto)
+#let shapes = box(width: 50pt, height: 40pt)[
+  #place(rect(fill: red, width: 30pt, height: 30pt))
+  #place(dx: 15pt, dy: 10pt, circle(fill: blue, radius: 15pt))
+]
+#rect(fill: conifer, inset: 5pt)[
+  #group(opacity: 50%, shapes)
+  #box(width: 50pt, height: 40pt)[
+    #place(rect(fill: red.transparentize(50%), width: 30pt, height: 30pt))
+    #place(dx: 15pt, dy: 10pt, circle(fill: blue.transparentize(50%), radius: 15pt))
+  ]
+]
+
+--- group-blend ---
+#set page(width: auto)
+#let stripes = box(fill: gradient.linear(..color.map.rainbow), inset: 5pt, stack(
+  dir: ltr,
+  spacing: 5pt,
+  ..("normal", "multiply", "screen", "overlay", "difference", "luminosity").map(mode => {
+    group(blend: mode, rect(fill: gray, width: 15pt, height: 30pt))
+  }),
+))
+#stripes
+
+--- group-mask-alpha ---
+#group(
+  mask: rect(
+    width: 100%,
+    height: 100%,
+    fill: gradient.linear(black, black.transparentize(100%)),
+  ),
+  rect(fill: forest, width: 100%, height: 20pt),
+)
+
+--- group-mask-luminance ---
+// In luminance mode, bright parts of the mask show the group and dark parts
+// hide it.
+#group(
+  mask: circle(width: 100%, fill: gradient.radial(white, black)),
+  mask-mode: "luminance",
+  rect(fill: forest, width: 100%, height: 100pt),
+)
@@ -51,6 +51,61 @@
 // Error: 7-31 point array must contain exactly two entries
 #path(((0%, 0%), (0%, 0%, 0%)))
 
+--- path-bad-data ---
+// Error: 7-16 failed to parse path data
+#path("M 0 0 X")
+
+--- path-bad-component ---
+// Error: 7-11 expected path vertex or path component, found content
+#path([hi])
+
+--- path-fill-rule ---
+#test(path(fill-rule: "even-odd").fill-rule, "even-odd")
+
+--- path-fill-rule-even-odd ---
+// The inner subpath and the center of the star are holes with the even-odd
+// rule, but not with the non-zero rule.
+#set page(width: auto)
+#let star = ((25pt, 0pt), (40pt, 45pt), (0pt, 17pt), (50pt, 17pt), (10pt, 45pt))
+#let frame = "M 0 0 H 40 V 40 H 0 Z M 10 10 H 30 V 30 H 10 Z"
+#stack(
+  dir: ltr,
+  spacing: 5pt,
+  path(fill: forest, closed: true, ..star),
+  path(fill: forest, closed: true, fill-rule: "even-odd", ..star),
+  path(fill: forest, frame),
+  path(fill: forest, fill-rule: "even-odd", frame),
+)
+
+--- path-arc-elliptical ---
+#path(
+  stroke: blue,
+  path.move((0pt, 20pt)),
+  path.arc((60pt, 20pt), (30pt, 15pt)),
+  path.arc((0pt, 20pt), (30pt, 15pt), rotation: 30deg, large: true),
+)
+
+--- path-arc-fields ---
+#test(path.arc((0pt, 0pt), 5pt).radius, 5pt)
+#test(path.arc((0pt, 0pt), (5pt, 5pt)).radius, 5pt)
+#test(path.arc((0pt, 0pt), (5pt, 2pt)).radius, (5pt, 2pt))
+#test(path.arc((0pt, 0pt), 5pt, rotation: 45deg).rotation, 45deg)
+
+--- path-arc-bad-radius ---
+// Error: 23-38 length array must contain exactly two entries
+#path.arc((0pt, 0pt), (1pt, 2pt, 3pt))
+
+--- path-size ---
+// Test the size of paths built from components and path data.
+#context test(measure(path("M 0 0 L 20 10")).width, 20pt)
+#context test(
+  measure(path(
+    path.move((10pt, 10pt)),
+    path.line((5pt, 5pt), relative: true),
+  )).height,
+  15pt,
+)
+
 --- issue-path-in-sized-container ---
 // Paths used to implement `LayoutMultiple` rather than `LayoutSingle` without
 // fulfilling the necessary contract of respecting region expansion.
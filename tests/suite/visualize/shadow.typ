@@ -0,0 +1,41 @@
+// Test shadows.
+
+--- shadow-fields ---
+#let r = rect(shadow: (dx: 1pt, blur: 2pt))
+#test(r.shadow.dx, 1pt)
+#test(r.shadow.dy, 2pt)
+#test(r.shadow.blur, 2pt)
+#test(r.shadow.spread, 0pt)
+#test(rect(shadow: red).shadow.color, red)
+#test(box(shadow: (spread: -1em)).shadow.spread, -1em)
+#test(block(shadow: none).shadow, none)
+
+--- shadow-unknown-key ---
+// Error: 15-23 unexpected key "x", valid keys are "dx", "dy", "blur", "spread", and "color"
+#rect(shadow: (x: 1pt))
+
+--- shadow-shapes ---
+#set page(width: 120pt)
+#rect(width: 40pt, height: 20pt, fill: white, shadow: (dx: 3pt, dy: 3pt))
+#box(width: 30pt, height: 20pt, fill: aqua, radius: 5pt, shadow: (blur: 4pt, spread: 2pt, color: blue))
+#h(10pt)
+#box(width: 30pt, height: 20pt, stroke: red, shadow: (dy: 0pt, spread: -2pt, blur: 2pt))
+
+--- shadow-text-runs ---
+// The shadow of the bold run must not cover the glyphs before it.
+#set text(size: 16pt, shadow: (dx: 4pt, dy: 2pt, blur: 3pt, color: red))
+A *bold* run, _italic_ too.
+
+--- shadow-clipped ---
+// The shadow of a clipped box follows its clipped content and isn't cut off
+// by the clip.
+#set page(width: 120pt)
+#box(
+  width: 40pt,
+  height: 20pt,
+  fill: aqua,
+  radius: 4pt,
+  clip: true,
+  shadow: (dx: 4pt, dy: 4pt, blur: 3pt),
+  circle(radius: 30pt, fill: teal),
+)